@@ -11,9 +11,11 @@ use crate::{Context, config::CONFIG, http::HTTP};
 
 #[tracing::instrument(skip(data))]
 pub async fn send(name: &str, data: impl serde::Serialize) -> Result<()> {
-    if let Some(endpoint) = &CONFIG.umami_endpoint
-        && let Some(website_id) = &CONFIG.umami_website_id
-        && let Some(hostname) = &CONFIG.umami_hostname
+    let config = CONFIG.get();
+
+    if let Some(endpoint) = &config.umami_endpoint
+        && let Some(website_id) = &config.umami_website_id
+        && let Some(hostname) = &config.umami_hostname
     {
         HTTP.post(endpoint)
             .json(&json!({
@@ -17,12 +17,14 @@ pub struct AnthropicContent {
 }
 
 pub async fn messages(body: impl serde::Serialize) -> Result<AnthropicResponse> {
+    let config = CONFIG.get();
+
     let data: AnthropicResponse = HTTP
         .post("https://api.anthropic.com/v1/messages")
         .header("anthropic-version", "2023-06-01")
         .header(
             "x-api-key",
-            CONFIG
+            config
                 .anthropic_api_key
                 .as_ref()
                 .ok_or_else(|| eyre!("ANTHROPIC_API_KEY unavailable"))?,
@@ -0,0 +1,304 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use poise::{CreateReply, serenity_prelude as serenity};
+
+use eyre::{Result, eyre};
+
+use crate::{
+    Context,
+    storage::{
+        StorageBackend,
+        ban_group::{BanGroup, BanGroupPolicy},
+    },
+};
+
+fn group_summary(group: &BanGroup) -> String {
+    let mut guilds = group
+        .guilds
+        .iter()
+        .map(|guild| format!("`{guild}`"))
+        .collect::<Vec<_>>();
+    guilds.sort();
+
+    format!(
+        "**{}** \u{00B7} {}\n-# {}",
+        group.name,
+        group.policy,
+        if guilds.is_empty() {
+            "No servers".to_owned()
+        } else {
+            guilds.join(", ")
+        }
+    )
+}
+
+async fn reply(ctx: Context<'_>, title: &str, content: String) -> Result<()> {
+    ctx.send(
+        CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .components(&[serenity::CreateComponent::Container(
+                serenity::CreateContainer::new(&[
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!("### {title}")),
+                    ),
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(content),
+                    ),
+                ])
+                .accent_color(0x4ade80),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn get_group(storage: &dyn StorageBackend, name: &str) -> Result<BanGroup> {
+    storage
+        .scan_ban_groups()
+        .await?
+        .into_iter()
+        .find(|group| group.name == name)
+        .ok_or_else(|| eyre!("ban group `{name}` does not exist"))
+}
+
+/// Manage groups of servers that share their bans
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    rename = "ban-group",
+    ephemeral,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    subcommands("create", "delete", "add", "remove", "policy", "list"),
+    subcommand_required
+)]
+pub async fn ban_group(ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Create a ban group
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild"
+)]
+async fn create(
+    ctx: Context<'_>,
+    #[description = "Name of the group"] name: String,
+    #[description = "How bans are shared (default: confirm)"] policy: Option<BanGroupPolicy>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    if storage
+        .scan_ban_groups()
+        .await?
+        .iter()
+        .any(|group| group.name == name)
+    {
+        ctx.say(format!("Ban group `{name}` already exists!"))
+            .await?;
+        return Ok(());
+    }
+
+    let group = BanGroup::new(name, policy.unwrap_or_default());
+    storage.set_ban_group(&group).await?;
+
+    reply(ctx, "Created ban group", group_summary(&group)).await
+}
+
+/// Delete a ban group
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild"
+)]
+async fn delete(ctx: Context<'_>, #[description = "Name of the group"] name: String) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let group = get_group(&**storage, &name).await?;
+    storage.del_ban_group(&group.name).await?;
+
+    reply(ctx, "Deleted ban group", group_summary(&group)).await
+}
+
+/// Add a server to a ban group
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild"
+)]
+async fn add(
+    ctx: Context<'_>,
+    #[description = "Name of the group"] name: String,
+    #[description = "ID of the server"] server: String,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut group = get_group(&**storage, &name).await?;
+
+    let server: serenity::GuildId = server
+        .trim()
+        .parse()
+        .map_err(|_| eyre!("invalid server ID `{server}`"))?;
+    let partial_guild = server.to_partial_guild(ctx.http()).await?;
+
+    group.guilds.insert(server);
+    storage.set_ban_group(&group).await?;
+
+    reply(
+        ctx,
+        &format!("Added {} to ban group", partial_guild.name),
+        group_summary(&group),
+    )
+    .await
+}
+
+/// Remove a server from a ban group
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild"
+)]
+async fn remove(
+    ctx: Context<'_>,
+    #[description = "Name of the group"] name: String,
+    #[description = "ID of the server"] server: String,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut group = get_group(&**storage, &name).await?;
+
+    let server: serenity::GuildId = server
+        .trim()
+        .parse()
+        .map_err(|_| eyre!("invalid server ID `{server}`"))?;
+
+    if !group.guilds.remove(&server) {
+        ctx.say(format!("`{server}` is not in ban group `{name}`!"))
+            .await?;
+        return Ok(());
+    }
+
+    storage.set_ban_group(&group).await?;
+
+    reply(ctx, "Removed server from ban group", group_summary(&group)).await
+}
+
+/// Change how a ban group shares bans
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild"
+)]
+async fn policy(
+    ctx: Context<'_>,
+    #[description = "Name of the group"] name: String,
+    #[description = "How bans are shared"] policy: BanGroupPolicy,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut group = get_group(&**storage, &name).await?;
+    group.policy = policy;
+    storage.set_ban_group(&group).await?;
+
+    reply(ctx, "Updated ban group", group_summary(&group)).await
+}
+
+/// List ban groups
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    owners_only,
+    default_member_permissions = "ADMINISTRATOR",
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild"
+)]
+async fn list(ctx: Context<'_>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let groups = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?
+        .scan_ban_groups()
+        .await?;
+
+    reply(
+        ctx,
+        "Ban groups",
+        if groups.is_empty() {
+            "*None*".to_owned()
+        } else {
+            groups
+                .iter()
+                .map(group_summary)
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+    )
+    .await
+}
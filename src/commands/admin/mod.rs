@@ -2,11 +2,13 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+mod ban_group;
 mod guilds;
 pub mod presence;
 mod say;
 mod sysinfo;
 
+use ban_group::ban_group;
 use guilds::guilds;
 use presence::presence;
 use say::say;
@@ -24,7 +26,7 @@ use eyre::Result;
     guild_only,
     install_context = "Guild",
     interaction_context = "Guild",
-    subcommands("guilds", "presence", "say", "sysinfo"),
+    subcommands("ban_group", "guilds", "presence", "say", "sysinfo"),
     subcommand_required
 )]
 pub async fn admin(ctx: Context<'_>) -> Result<()> {
@@ -15,6 +15,10 @@ pub mod restore {
     pub use super::useful::remind::restore as reminders;
 }
 
+pub mod actions {
+    pub use super::moderation::{Moderator, ban::unban, warn::execute as warn};
+}
+
 macro_rules! command {
     ($category: ident, $name: ident) => {
         $category::$name::$name()
@@ -39,11 +43,20 @@ pub fn all() -> Vec<poise::Command<Data, eyre::Report>> {
         // command!(useful, typst),
         command!(useful, unicode),
         command!(useful, user),
+        command!(useful, user, user_context_menu),
+        command!(moderation, automod),
         command!(moderation, ban),
         command!(moderation, ban_reason),
+        command!(moderation, case),
+        command!(moderation, case, cases),
         command!(moderation, kick),
+        command!(moderation, lockdown),
+        command!(moderation, logs),
+        command!(moderation, note),
         command!(moderation, purge),
         command!(moderation, purge_user),
+        command!(moderation, quarantine),
+        command!(moderation, quarantine, unquarantine),
         command!(moderation, rotate_logs),
         command!(moderation, timeout),
         command!(moderation, warn),
@@ -53,6 +66,7 @@ pub fn all() -> Vec<poise::Command<Data, eyre::Report>> {
         command!(fun, shiggy),
         command!(utils, config),
         command!(utils, ping),
+        command!(utils, privacy),
         command!(utils, rotate_color_roles),
         command!(utils, template_channel),
         command!(utils, version),
@@ -0,0 +1,311 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, bail, eyre};
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use crate::{
+    Context, config,
+    storage::automod::{AutomodAction, AutomodPattern, AutomodRule},
+    utils,
+};
+
+#[derive(poise::ChoiceParameter, PartialEq, Eq, Clone, Copy, Debug)]
+enum PatternKind {
+    Regex,
+    Literal,
+    #[name = "Word list"]
+    Words,
+}
+
+#[derive(poise::ChoiceParameter, PartialEq, Eq, Clone, Copy, Debug)]
+enum ActionKind {
+    Delete,
+    Warn,
+    Timeout,
+    #[name = "Log only"]
+    Log,
+}
+
+fn rule_summary(rule: &AutomodRule) -> String {
+    let mut summary = format!("`{}` \u{00B7} {} → {}", rule.id, rule.pattern, rule.action);
+
+    if !rule.channels.is_empty() {
+        summary.push_str(&format!(
+            "\n-# In {}",
+            rule.channels
+                .iter()
+                .map(|channel| channel.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    if !rule.exempt_roles.is_empty() {
+        summary.push_str(&format!(
+            "\n-# Exempt {}",
+            rule.exempt_roles
+                .iter()
+                .map(|role| role.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    summary
+}
+
+async fn reply(ctx: Context<'_>, title: &str, content: String) -> Result<()> {
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .allowed_mentions(serenity::CreateAllowedMentions::new())
+            .components(vec![serenity::CreateComponent::Container(
+                serenity::CreateContainer::new(vec![
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!("### {title}")),
+                    ),
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(content),
+                    ),
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!(
+                            "-# {}",
+                            serenity::FormattedTimestamp::now()
+                        )),
+                    ),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Manage automod rules
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    subcommands("add", "list", "remove", "test"),
+    subcommand_required,
+    default_member_permissions = "MANAGE_GUILD"
+)]
+pub async fn automod(ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Add an automod rule
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn add(
+    ctx: Context<'_>,
+    #[description = "How to match messages"] kind: PatternKind,
+    #[description = "The regex, text, or comma-separated words to match"] pattern: String,
+    #[description = "What to do with matching messages"] action: ActionKind,
+    #[description = "Duration of timeouts"] duration: Option<String>,
+    #[description = "Channels to apply the rule in (default: all)"] channels: Option<String>,
+    #[description = "Roles exempt from the rule"] exempt_roles: Option<String>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let pattern = match kind {
+        PatternKind::Regex => AutomodPattern::Regex(pattern),
+        PatternKind::Literal => AutomodPattern::Literal(pattern),
+        PatternKind::Words => AutomodPattern::Words(
+            pattern
+                .split(',')
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_owned)
+                .collect(),
+        ),
+    };
+
+    if matches!(&pattern, AutomodPattern::Words(words) if words.is_empty()) {
+        bail!("at least one word is required");
+    }
+    pattern.regex()?;
+
+    let action = match action {
+        ActionKind::Delete => AutomodAction::Delete,
+        ActionKind::Warn => AutomodAction::Warn,
+        ActionKind::Timeout => {
+            let Some(Ok(duration)) = duration.as_deref().map(humantime::parse_duration) else {
+                bail!("a valid duration is required for timeouts");
+            };
+
+            if duration > config::MAX_TIMEOUT {
+                ctx.say("Timeouts can be at most 28 days long!").await?;
+                return Ok(());
+            }
+
+            AutomodAction::Timeout { duration }
+        }
+        ActionKind::Log => AutomodAction::Log,
+    };
+
+    let mut rule = AutomodRule::new(pattern, action);
+
+    if let Some(channels) = &channels {
+        rule.channels = utils::serenity::parse_ids(channels)?
+            .into_iter()
+            .map(serenity::GenericChannelId::new)
+            .collect();
+    }
+    if let Some(exempt_roles) = &exempt_roles {
+        rule.exempt_roles = utils::serenity::parse_ids(exempt_roles)?
+            .into_iter()
+            .map(serenity::RoleId::new)
+            .collect();
+    }
+
+    storage.set_automod_rule(guild, &rule).await?;
+
+    reply(ctx, "Added automod rule", rule_summary(&rule)).await
+}
+
+/// List automod rules
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn list(ctx: Context<'_>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let rules = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?
+        .scan_automod_rules(guild)
+        .await?;
+
+    reply(
+        ctx,
+        "Automod",
+        if rules.is_empty() {
+            "*None*".to_owned()
+        } else {
+            rules
+                .iter()
+                .map(rule_summary)
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+    )
+    .await
+}
+
+/// Remove an automod rule
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn remove(ctx: Context<'_>, #[description = "The rule ID"] id: String) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let Some(rule) = storage
+        .scan_automod_rules(guild)
+        .await?
+        .into_iter()
+        .find(|rule| rule.id == id)
+    else {
+        ctx.say(format!("Rule `{id}` does not exist!")).await?;
+        return Ok(());
+    };
+
+    storage.del_automod_rule(guild, &rule.id).await?;
+
+    reply(ctx, "Removed automod rule", rule_summary(&rule)).await
+}
+
+/// Test which automod rules match a message
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn test(
+    ctx: Context<'_>,
+    #[description = "The message content to test"] content: String,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let rules = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?
+        .scan_automod_rules(guild)
+        .await?;
+
+    let matches = rules
+        .iter()
+        .filter_map(|rule| {
+            rule.find(&content).map(|matched| {
+                format!(
+                    "{}\n-# Matched `{}`",
+                    rule_summary(rule),
+                    utils::truncate(matched, 256)
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    reply(
+        ctx,
+        "Automod test",
+        if matches.is_empty() {
+            "No rules match".to_owned()
+        } else {
+            matches.join("\n")
+        },
+    )
+    .await
+}
@@ -5,7 +5,15 @@
 use eyre::{Result, eyre};
 use poise::serenity_prelude::{self as serenity, Mentionable as _};
 
-use crate::{Context, utils};
+use super::{Moderator, expires_at};
+use crate::{
+    Context, handlers,
+    storage::{
+        case::{self, CaseAction, CaseLogMessage, DmStatus, ModerationCase},
+        temp_ban,
+    },
+    utils,
+};
 
 /// Ban a user
 #[tracing::instrument(skip(ctx, user), fields(user = user.id.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
@@ -21,6 +29,7 @@ pub async fn ban(
     ctx: Context<'_>,
     #[description = "The user to ban"] user: serenity::User,
     #[description = "Reason for the ban"] reason: Option<String>,
+    #[description = "Duration of a temporary ban (default: permanent)"] duration: Option<String>,
 
     #[description = "Days of messages to delete (default: 0)"]
     #[min = 0]
@@ -29,7 +38,19 @@ pub async fn ban(
 
     #[description = "Notify with a direct message (default: true)"] dm: Option<bool>,
 ) -> Result<()> {
-    let delete_message_days = delete_message_days.unwrap_or(0);
+    let duration = if let Some(duration) = duration {
+        let Some(duration) = humantime::parse_duration(&duration)
+            .ok()
+            .filter(|duration| expires_at(*duration).is_some())
+        else {
+            ctx.say("Invalid duration provided!").await?;
+            return Ok(());
+        };
+
+        Some(duration)
+    } else {
+        None
+    };
 
     ctx.defer_ephemeral().await?;
 
@@ -38,6 +59,40 @@ pub async fn ban(
         .await
         .ok_or_else(|| eyre!("failed to obtain partial guild"))?;
 
+    let container = execute(
+        ctx.into(),
+        &partial_guild,
+        &user,
+        delete_message_days.unwrap_or(0),
+        duration,
+        reason,
+        dm.unwrap_or(true),
+    )
+    .await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .components(vec![serenity::CreateComponent::Container(container)]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Ban `user`, notifying them and logging the ban like `/ban` does.
+/// If `duration` is given, the ban is lifted automatically by [`crate::schedule`].
+pub async fn execute(
+    moderator: Moderator<'_>,
+    partial_guild: &serenity::PartialGuild,
+    user: &serenity::User,
+    delete_message_days: u32,
+    duration: Option<std::time::Duration>,
+    reason: Option<String>,
+    dm: bool,
+) -> Result<serenity::CreateContainer<'static>> {
+    let data = moderator.ctx.data::<crate::Data>();
+
     let mut container =
         serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
             serenity::CreateTextDisplay::new(format!(
@@ -47,11 +102,14 @@ pub async fn ban(
         )])
         .accent_color(0xda77f2);
 
-    let extra_message = if let Some(storage) = &ctx.data().storage {
+    let (extra_message, appeals) = if let Some(storage) = &data.storage {
         let guild_config = storage.get_config(partial_guild.id).await?;
-        guild_config.moderation_extra_message_ban
+        (
+            guild_config.moderation_extra_message_ban,
+            guild_config.appeals_channel.is_some(),
+        )
     } else {
-        None
+        (None, false)
     };
 
     if let Some(reason) = utils::option_strings(reason.as_deref(), extra_message.as_deref()) {
@@ -60,24 +118,41 @@ pub async fn ban(
         ));
     }
 
-    if dm.unwrap_or(true) {
-        let dm_container =
-            container
-                .clone()
-                .add_component(serenity::CreateContainerComponent::TextDisplay(
-                    serenity::CreateTextDisplay::new(format!(
-                        "-# {} \u{00B7} {}",
-                        partial_guild.name,
-                        serenity::FormattedTimestamp::now()
-                    )),
-                ));
+    let expires_at = duration
+        .map(|duration| expires_at(duration).ok_or_else(|| eyre!("ban duration is out of range")))
+        .transpose()?;
+
+    if let (Some(duration), Some(expires_at)) = (duration, expires_at) {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "**Duration**\n{} (until {})",
+                humantime::format_duration(duration),
+                serenity::FormattedTimestamp::new(expires_at.into(), None)
+            )),
+        ));
+    }
+
+    let dm_status = if dm {
+        let mut dm_container = container.clone();
+
+        if appeals {
+            dm_container = dm_container.add_component(handlers::appeal::button(partial_guild.id));
+        }
+
+        dm_container = dm_container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "-# {} \u{00B7} {}",
+                partial_guild.name,
+                serenity::FormattedTimestamp::now()
+            )),
+        ));
 
-        if let Ok(dm) = user.create_dm_channel(ctx).await
+        if let Ok(dm) = user.create_dm_channel(moderator.ctx).await
             && dm
                 .id
                 .widen()
                 .send_message(
-                    ctx.http(),
+                    &moderator.ctx.http,
                     serenity::CreateMessage::default()
                         .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
                         .allowed_mentions(serenity::CreateAllowedMentions::new())
@@ -86,19 +161,17 @@ pub async fn ban(
                 .await
                 .is_ok()
         {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nYes"),
-            ));
+            DmStatus::Yes
         } else {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nFailed"),
-            ));
+            DmStatus::Failed
         }
     } else {
-        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-            serenity::CreateTextDisplay::new("**User notified**\nNo"),
-        ));
-    }
+        DmStatus::No
+    };
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!("**User notified**\n{dm_status}")),
+    ));
 
     container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
         serenity::CreateTextDisplay::new(format!(
@@ -106,9 +179,33 @@ pub async fn ban(
         )),
     ));
 
+    let mut case = if let Some(storage) = &data.storage {
+        Some(
+            case::create(
+                &**storage,
+                partial_guild.id,
+                ModerationCase {
+                    reason: reason.clone(),
+                    duration,
+                    dm: dm_status,
+                    ..ModerationCase::new(CaseAction::Ban, user.id, moderator.user)
+                },
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    if let Some(case) = &case {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
+        ));
+    }
+
     let reply_container = container.clone();
 
-    if let Some(storage) = &ctx.data().storage {
+    if let Some(storage) = &data.storage {
         let guild_config = storage.get_config(partial_guild.id).await?;
 
         if let Some(logs_channel) = guild_config.moderation_logs_channel {
@@ -116,39 +213,203 @@ pub async fn ban(
                 container.add_component(serenity::CreateContainerComponent::TextDisplay(
                     serenity::CreateTextDisplay::new(format!(
                         "-# {} \u{00B7} {}",
-                        ctx.author().mention(),
+                        moderator.user.mention(),
                         serenity::FormattedTimestamp::now()
                     )),
                 ));
 
-            logs_channel
+            let log_message = logs_channel
                 .send_message(
-                    ctx.http(),
+                    &moderator.ctx.http,
                     serenity::CreateMessage::default()
                         .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
                         .allowed_mentions(serenity::CreateAllowedMentions::new())
                         .components(vec![serenity::CreateComponent::Container(log_container)]),
                 )
                 .await?;
+
+            if let Some(case) = &mut case {
+                case.log_message = Some(CaseLogMessage {
+                    channel: logs_channel,
+                    message: log_message.id,
+                });
+
+                storage.set_case(partial_guild.id, case).await?;
+            }
         }
     }
 
     partial_guild
         .id
         .ban(
-            ctx.http(),
+            &moderator.ctx.http,
             user.id,
             delete_message_days * 86400,
             reason.as_deref(),
         )
         .await?;
 
-    ctx.send(
-        poise::CreateReply::default()
-            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
-            .components(vec![serenity::CreateComponent::Container(reply_container)]),
-    )
-    .await?;
+    if let Some(storage) = &data.storage {
+        let case = case.as_ref().map(|case| case.id);
+        temp_ban::schedule(&**storage, partial_guild.id, user.id, expires_at, case).await?;
+    }
+
+    // temporary bans stay local to the guild they were issued in
+    if duration.is_none()
+        && let Err(err) = handlers::ban_group::propagate(
+            moderator.ctx,
+            partial_guild.id,
+            user.id,
+            reason.as_deref(),
+            Some(moderator.user),
+        )
+        .await
+    {
+        tracing::warn!("failed to share ban with ban groups: {err:?}");
+    }
+
+    Ok(reply_container)
+}
+
+/// DM `user` a single-use invite back to `guild`, if an invite channel is configured.
+async fn send_unban_invite(
+    http: &serenity::Http,
+    data: &crate::Data,
+    guild: serenity::GuildId,
+    user: serenity::UserId,
+    reason: &str,
+) -> Result<DmStatus> {
+    let Some(storage) = &data.storage else {
+        return Ok(DmStatus::No);
+    };
+
+    let Some(invite_channel) = storage.get_config(guild).await?.unban_invite_channel else {
+        return Ok(DmStatus::No);
+    };
+
+    let partial_guild = guild.to_partial_guild(http).await?;
+
+    let invite = invite_channel
+        .expect_channel()
+        .create_invite(
+            http,
+            serenity::CreateInvite::new()
+                .max_age(7 * 24 * 60 * 60)
+                .max_uses(1)
+                .unique(true)
+                .audit_log_reason(reason),
+        )
+        .await?;
+
+    let container = serenity::CreateContainer::new(vec![
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "### Unban\nYou have been unbanned from **{}**. You can rejoin with {}",
+            partial_guild.name,
+            invite.url()
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "**Reason**\n{reason}"
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "-# {} \u{00B7} {}",
+            partial_guild.name,
+            serenity::FormattedTimestamp::now()
+        ))),
+    ])
+    .accent_color(0xda77f2);
+
+    if let Ok(dm) = user.create_dm_channel(http).await
+        && dm
+            .id
+            .widen()
+            .send_message(
+                http,
+                serenity::CreateMessage::default()
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new())
+                    .components(vec![serenity::CreateComponent::Container(container)]),
+            )
+            .await
+            .is_ok()
+    {
+        Ok(DmStatus::Yes)
+    } else {
+        Ok(DmStatus::Failed)
+    }
+}
+
+/// Unban `user`, cancelling any scheduled unban, inviting them back if configured and
+/// logging the unban. Used for expired temporary bans and accepted appeals.
+pub async fn unban(
+    http: &serenity::Http,
+    data: &crate::Data,
+    guild: serenity::GuildId,
+    user: serenity::UserId,
+    reason: &str,
+    moderator: Option<serenity::UserId>,
+    case: Option<u64>,
+) -> Result<()> {
+    guild.unban(http, user, Some(reason)).await?;
+
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    temp_ban::schedule(&**storage, guild, user, None, None).await?;
+
+    let dm_status = send_unban_invite(http, data, guild, user, reason)
+        .await
+        .unwrap_or_else(|err| {
+            tracing::warn!("failed to send unban invite: {err:?}");
+            DmStatus::Failed
+        });
+
+    if let Some(logs_channel) = storage.get_config(guild).await?.moderation_logs_channel {
+        let mut container = serenity::CreateContainer::new(vec![
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "### Unban\n{}",
+                    utils::serenity::format_mentionable(Some(user))
+                ),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("**Reason**\n{reason}"),
+            )),
+        ])
+        .accent_color(0xda77f2);
+
+        if let Some(case) = case {
+            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!("**Case**\n{case}")),
+            ));
+        }
+
+        container = container
+            .add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!("**User notified**\n{dm_status}")),
+            ))
+            .add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(if let Some(moderator) = moderator {
+                    format!(
+                        "-# {} \u{00B7} {}",
+                        moderator.mention(),
+                        serenity::FormattedTimestamp::now()
+                    )
+                } else {
+                    format!("-# {}", serenity::FormattedTimestamp::now())
+                }),
+            ));
+
+        logs_channel
+            .send_message(
+                http,
+                serenity::CreateMessage::default()
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new())
+                    .components(vec![serenity::CreateComponent::Container(container)]),
+            )
+            .await?;
+    }
 
     Ok(())
 }
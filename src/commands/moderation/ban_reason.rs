@@ -5,7 +5,11 @@
 use eyre::{Result, eyre};
 use poise::serenity_prelude as serenity;
 
-use crate::{Context, utils};
+use crate::{
+    Context,
+    storage::case::{self, CaseAction},
+    utils,
+};
 
 /// Find the reason for a user's ban
 #[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
@@ -31,27 +35,43 @@ pub async fn ban_reason(
         return Ok(());
     };
 
+    let mut container = serenity::CreateContainer::new(vec![
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "### Ban\n{}",
+            utils::serenity::format_mentionable(Some(user)),
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "**Reason**\n{}",
+            ban.reason
+                .map_or_else(|| "*None*".to_owned(), |s| s.to_string())
+        ))),
+    ])
+    .accent_color(0xda77f2);
+
+    let ban_case = if let Some(storage) = &ctx.data().storage {
+        case::for_user(&**storage, guild, user)
+            .await?
+            .into_iter()
+            .rfind(|case| case.action == CaseAction::Ban)
+    } else {
+        None
+    };
+
+    if let Some(ban_case) = ban_case {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "**Case**\n{} \u{00B7} {}",
+                ban_case.id,
+                utils::serenity::format_mentionable(Some(ban_case.moderator)),
+            )),
+        ));
+    }
+
     ctx.send(
         poise::CreateReply::default()
             .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
-            .components(vec![serenity::CreateComponent::Container(
-                serenity::CreateContainer::new(vec![
-                    serenity::CreateContainerComponent::TextDisplay(
-                        serenity::CreateTextDisplay::new(format!(
-                            "### Ban\n{}",
-                            utils::serenity::format_mentionable(Some(user)),
-                        )),
-                    ),
-                    serenity::CreateContainerComponent::TextDisplay(
-                        serenity::CreateTextDisplay::new(format!(
-                            "**Reason**\n{}",
-                            ban.reason
-                                .map_or_else(|| "*None*".to_owned(), |s| s.to_string())
-                        )),
-                    ),
-                ])
-                .accent_color(0xda77f2),
-            )]),
+            .allowed_mentions(serenity::CreateAllowedMentions::new())
+            .components(vec![serenity::CreateComponent::Container(container)]),
     )
     .await?;
 
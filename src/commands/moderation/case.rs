@@ -0,0 +1,395 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use super::note;
+use crate::{
+    Context,
+    storage::{
+        StorageBackend,
+        case::{self, ModerationCase},
+    },
+    utils,
+};
+
+/// The maximum number of cases listed by `/cases`.
+const MAX_LISTED_CASES: usize = 20;
+
+fn case_container(
+    case: &ModerationCase,
+    guild: serenity::GuildId,
+) -> serenity::CreateContainer<'static> {
+    let mut container =
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "### Case {} \u{00B7} {}\n{}",
+                case.id,
+                case.action,
+                utils::serenity::format_mentionable(Some(case.target)),
+            )),
+        )])
+        .accent_color(0xfacc15);
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!(
+            "**Reason**\n{}",
+            case.reason.as_deref().unwrap_or("*None*")
+        )),
+    ));
+
+    if let Some(duration) = case.duration {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "**Duration**\n{}",
+                humantime::format_duration(duration)
+            )),
+        ));
+    }
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!("**User notified**\n{}", case.dm)),
+    ));
+
+    if let Some(link) = case.log_link(guild) {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Log message**\n{link}")),
+        ));
+    }
+
+    container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!(
+            "-# {} \u{00B7} {}",
+            case.moderator.mention(),
+            serenity::FormattedTimestamp::new(case.timestamp.into(), None)
+        )),
+    ))
+}
+
+async fn log_case_change(
+    ctx: Context<'_>,
+    storage: &dyn StorageBackend,
+    guild: serenity::GuildId,
+    container: serenity::CreateContainer<'_>,
+) -> Result<()> {
+    let guild_config = storage.get_config(guild).await?;
+
+    if let Some(logs_channel) = guild_config.moderation_logs_channel {
+        let log_container =
+            container.add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!(
+                    "-# {} \u{00B7} {}",
+                    ctx.author().mention(),
+                    serenity::FormattedTimestamp::now()
+                )),
+            ));
+
+        logs_channel
+            .send_message(
+                ctx.http(),
+                serenity::CreateMessage::default()
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new())
+                    .components(vec![serenity::CreateComponent::Container(log_container)]),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Manage moderation cases
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    subcommands("view", "edit_reason", "delete"),
+    subcommand_required,
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+pub async fn case(ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// View a moderation case
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+async fn view(ctx: Context<'_>, #[description = "The case number"] id: u64) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let Some(case) = storage.get_case(guild, id).await? else {
+        ctx.say(format!("Case {id} does not exist!")).await?;
+        return Ok(());
+    };
+
+    let mut components = vec![serenity::CreateComponent::Container(case_container(
+        &case, guild,
+    ))];
+
+    let notes = storage.scan_notes(case.target, guild).await?;
+    if !notes.is_empty() {
+        components.push(serenity::CreateComponent::Container(note::notes_container(
+            case.target,
+            &notes,
+        )));
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .allowed_mentions(serenity::CreateAllowedMentions::new())
+            .components(components),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Edit the reason of a moderation case
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    rename = "edit-reason",
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+async fn edit_reason(
+    ctx: Context<'_>,
+    #[description = "The case number"] id: u64,
+    #[description = "The new reason"] reason: String,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let Some(mut case) = storage.get_case(guild, id).await? else {
+        ctx.say(format!("Case {id} does not exist!")).await?;
+        return Ok(());
+    };
+
+    let old_reason = case.reason.replace(reason.clone());
+    storage.set_case(guild, &case).await?;
+
+    log_case_change(
+        ctx,
+        &**storage,
+        guild,
+        serenity::CreateContainer::new(vec![
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "### Case {id} reason edited\n{}",
+                    utils::serenity::format_mentionable(Some(case.target)),
+                ),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "**Old reason**\n{}",
+                    old_reason.as_deref().unwrap_or("*None*")
+                ),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("**New reason**\n{reason}"),
+            )),
+        ])
+        .accent_color(0xfacc15),
+    )
+    .await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .allowed_mentions(serenity::CreateAllowedMentions::new())
+            .components(vec![serenity::CreateComponent::Container(case_container(
+                &case, guild,
+            ))]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Delete a moderation case
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+async fn delete(ctx: Context<'_>, #[description = "The case number"] id: u64) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let Some(case) = storage.get_case(guild, id).await? else {
+        ctx.say(format!("Case {id} does not exist!")).await?;
+        return Ok(());
+    };
+
+    let (confirmed, reply) = utils::serenity::interaction_confirm(
+        &ctx,
+        case_container(&case, guild)
+            .add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!(
+                    "Are you sure you want to delete case {id}?"
+                )),
+            ))
+            .accent_color(0xffd43b),
+    )
+    .await?;
+
+    let container = if confirmed {
+        storage.del_case(guild, id).await?;
+
+        log_case_change(
+            ctx,
+            &**storage,
+            guild,
+            serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!(
+                    "### Case {id} deleted\n{}\n**Action**\n{}",
+                    utils::serenity::format_mentionable(Some(case.target)),
+                    case.action,
+                )),
+            )])
+            .accent_color(0xfacc15),
+        )
+        .await?;
+
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("### Deleted case {id}")),
+        )])
+        .accent_color(0x4ade80)
+    } else {
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new("### Deletion cancelled"),
+        )])
+        .accent_color(0xff6b6b)
+    };
+
+    reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .components(vec![serenity::CreateComponent::Container(container)]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// List the moderation cases of a user
+#[tracing::instrument(skip(ctx), fields(user = user.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+pub async fn cases(
+    ctx: Context<'_>,
+    #[description = "The user"] user: serenity::UserId,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let cases = case::for_user(&**storage, guild, user).await?;
+
+    let content = if cases.is_empty() {
+        "No cases have been recorded".to_owned()
+    } else {
+        let mut entries = cases
+            .iter()
+            .rev()
+            .take(MAX_LISTED_CASES)
+            .map(|case| {
+                format!(
+                    "**Case {}** \u{00B7} {} \u{00B7} {}\n-# {}",
+                    case.id,
+                    case.action,
+                    serenity::FormattedTimestamp::new(case.timestamp.into(), None),
+                    case.reason.as_deref().unwrap_or("*No reason*"),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if cases.len() > MAX_LISTED_CASES {
+            entries.push_str(&format!(
+                "\n-# {} older cases not shown",
+                cases.len() - MAX_LISTED_CASES
+            ));
+        }
+
+        entries
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .allowed_mentions(serenity::CreateAllowedMentions::new())
+            .components(vec![serenity::CreateComponent::Container(
+                serenity::CreateContainer::new(vec![
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!(
+                            "### Cases\n{}",
+                            utils::serenity::format_mentionable(Some(user)),
+                        )),
+                    ),
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(content),
+                    ),
+                ])
+                .accent_color(0xfacc15),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
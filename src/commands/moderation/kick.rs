@@ -5,7 +5,12 @@
 use eyre::{Result, eyre};
 use poise::serenity_prelude::{self as serenity, Mentionable as _};
 
-use crate::{Context, utils};
+use super::Moderator;
+use crate::{
+    Context,
+    storage::case::{self, CaseAction, CaseLogMessage, DmStatus, ModerationCase},
+    utils,
+};
 
 /// Kick a user
 #[tracing::instrument(skip(ctx, user), fields(user = user.id.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
@@ -30,6 +35,35 @@ pub async fn kick(
         .await
         .ok_or_else(|| eyre!("failed to obtain partial guild"))?;
 
+    let container = execute(
+        ctx.into(),
+        &partial_guild,
+        &user,
+        reason,
+        dm.unwrap_or(true),
+    )
+    .await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .components(vec![serenity::CreateComponent::Container(container)]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Kick `user`, notifying them and logging the kick like `/kick` does.
+pub async fn execute(
+    moderator: Moderator<'_>,
+    partial_guild: &serenity::PartialGuild,
+    user: &serenity::User,
+    reason: Option<String>,
+    dm: bool,
+) -> Result<serenity::CreateContainer<'static>> {
+    let data = moderator.ctx.data::<crate::Data>();
+
     let mut container =
         serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
             serenity::CreateTextDisplay::new(format!(
@@ -39,7 +73,7 @@ pub async fn kick(
         )])
         .accent_color(0xf783ac);
 
-    let extra_message = if let Some(storage) = &ctx.data().storage {
+    let extra_message = if let Some(storage) = &data.storage {
         let guild_config = storage.get_config(partial_guild.id).await?;
         guild_config.moderation_extra_message_kick
     } else {
@@ -52,7 +86,7 @@ pub async fn kick(
         ));
     }
 
-    if dm.unwrap_or(true) {
+    let dm_status = if dm {
         let dm_container =
             container
                 .clone()
@@ -64,12 +98,12 @@ pub async fn kick(
                     )),
                 ));
 
-        if let Ok(dm) = user.create_dm_channel(ctx).await
+        if let Ok(dm) = user.create_dm_channel(moderator.ctx).await
             && dm
                 .id
                 .widen()
                 .send_message(
-                    ctx.http(),
+                    &moderator.ctx.http,
                     serenity::CreateMessage::default()
                         .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
                         .allowed_mentions(serenity::CreateAllowedMentions::new())
@@ -78,23 +112,44 @@ pub async fn kick(
                 .await
                 .is_ok()
         {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nYes"),
-            ));
+            DmStatus::Yes
         } else {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nFailed"),
-            ));
+            DmStatus::Failed
         }
     } else {
+        DmStatus::No
+    };
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!("**User notified**\n{dm_status}")),
+    ));
+
+    let mut case = if let Some(storage) = &data.storage {
+        Some(
+            case::create(
+                &**storage,
+                partial_guild.id,
+                ModerationCase {
+                    reason: reason.clone(),
+                    dm: dm_status,
+                    ..ModerationCase::new(CaseAction::Kick, user.id, moderator.user)
+                },
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    if let Some(case) = &case {
         container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-            serenity::CreateTextDisplay::new("**User notified**\nNo"),
+            serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
         ));
     }
 
     let reply_container = container.clone();
 
-    if let Some(storage) = &ctx.data().storage {
+    if let Some(storage) = &data.storage {
         let guild_config = storage.get_config(partial_guild.id).await?;
 
         if let Some(logs_channel) = guild_config.moderation_logs_channel {
@@ -102,34 +157,36 @@ pub async fn kick(
                 container.add_component(serenity::CreateContainerComponent::TextDisplay(
                     serenity::CreateTextDisplay::new(format!(
                         "-# {} \u{00B7} {}",
-                        ctx.author().mention(),
+                        moderator.user.mention(),
                         serenity::FormattedTimestamp::now()
                     )),
                 ));
 
-            logs_channel
+            let log_message = logs_channel
                 .send_message(
-                    ctx.http(),
+                    &moderator.ctx.http,
                     serenity::CreateMessage::default()
                         .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
                         .allowed_mentions(serenity::CreateAllowedMentions::new())
                         .components(vec![serenity::CreateComponent::Container(log_container)]),
                 )
                 .await?;
+
+            if let Some(case) = &mut case {
+                case.log_message = Some(CaseLogMessage {
+                    channel: logs_channel,
+                    message: log_message.id,
+                });
+
+                storage.set_case(partial_guild.id, case).await?;
+            }
         }
     }
 
     partial_guild
         .id
-        .kick(ctx.http(), user.id, reason.as_deref())
+        .kick(&moderator.ctx.http, user.id, reason.as_deref())
         .await?;
 
-    ctx.send(
-        poise::CreateReply::default()
-            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
-            .components(vec![serenity::CreateComponent::Container(reply_container)]),
-    )
-    .await?;
-
-    Ok(())
+    Ok(reply_container)
 }
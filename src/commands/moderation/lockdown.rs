@@ -0,0 +1,46 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+
+use crate::{Context, handlers};
+
+/// Manage raid lockdowns
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    subcommands("end"),
+    subcommand_required,
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+pub async fn lockdown(ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// End the current lockdown before its cool-off timer runs out
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+async fn end(ctx: Context<'_>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    if handlers::raid::end_lockdown(ctx.http(), ctx.data(), guild, Some(ctx.author().id)).await? {
+        ctx.say("Lockdown ended.").await?;
+    } else {
+        ctx.say("This server is not in lockdown!").await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use crate::{
+    Context,
+    storage::log::{MessageLog, MessageLogQuery},
+    utils,
+};
+
+/// The number of messages shown on each page of search results.
+const PAGE_SIZE: usize = 10;
+
+/// The maximum number of messages returned by a search.
+const MAX_RESULTS: usize = 250;
+
+/// Parse a point in time given either as a duration ago (e.g. `2h`) or as a timestamp.
+fn parse_time(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let s = s.trim();
+
+    if let Ok(duration) = humantime::parse_duration(s) {
+        return chrono::TimeDelta::from_std(duration)
+            .ok()
+            .and_then(|duration| chrono::Utc::now().checked_sub_signed(duration));
+    }
+
+    humantime::parse_rfc3339_weak(s)
+        .ok()
+        .map(chrono::DateTime::from)
+}
+
+fn result_summary(guild: serenity::GuildId, id: serenity::MessageId, log: &MessageLog) -> String {
+    let mut summary = format!(
+        "{} \u{00B7} {}",
+        log.author.mention(),
+        serenity::FormattedTimestamp::new(id.created_at(), None)
+    );
+
+    if let Some(channel) = log.channel {
+        summary.push_str(&format!(
+            " \u{00B7} https://discord.com/channels/{guild}/{channel}/{id}"
+        ));
+    }
+
+    summary.push('\n');
+
+    if log.content.is_empty() {
+        summary.push_str("*No content*");
+    } else {
+        summary.push_str(&utils::truncate(&log.content, 256));
+    }
+
+    if !log.attachments.is_empty() {
+        summary.push_str(&format!("\n-# {} attachment(s)", log.attachments.len()));
+    }
+
+    summary
+}
+
+/// Query stored message logs
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    subcommands("search"),
+    subcommand_required,
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+pub async fn logs(ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Search messages stored for message logs
+#[tracing::instrument(skip(ctx, author), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+async fn search(
+    ctx: Context<'_>,
+    #[description = "Only messages sent by this user"] author: Option<serenity::User>,
+    #[description = "Only messages in this channel"] channel: Option<serenity::GenericChannelId>,
+    #[description = "Only messages containing this text"] contains: Option<String>,
+    #[description = "Sent after a time ago (e.g. 2h) or a timestamp"] after: Option<String>,
+    #[description = "Sent before a time ago (e.g. 30m) or a timestamp"] before: Option<String>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let (Some(after), Some(before)) = (
+        after
+            .as_deref()
+            .map_or(Some(chrono::DateTime::UNIX_EPOCH), parse_time),
+        before
+            .as_deref()
+            .map_or_else(|| Some(chrono::Utc::now()), parse_time),
+    ) else {
+        ctx.say("Invalid time provided! Use a duration ago (e.g. `2h`) or a timestamp.")
+            .await?;
+        return Ok(());
+    };
+
+    let results = storage
+        .scan_message_logs(
+            guild_id,
+            &MessageLogQuery {
+                after,
+                before,
+                author: author.map(|author| author.id),
+                channel,
+                contains,
+                limit: MAX_RESULTS,
+            },
+        )
+        .await?;
+
+    let summary = if results.len() >= MAX_RESULTS {
+        format!("Showing the {MAX_RESULTS} most recent results")
+    } else {
+        format!("{} result(s)", results.len())
+    };
+
+    let header =
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("### Message logs\n{summary}")),
+        )])
+        .accent_color(0x63e6be);
+
+    if results.is_empty() {
+        return utils::serenity::interaction_paginate(&ctx, vec![header]).await;
+    }
+
+    let pages = results
+        .chunks(PAGE_SIZE)
+        .map(|chunk| {
+            chunk.iter().fold(header.clone(), |container, (id, log)| {
+                container.add_component(serenity::CreateContainerComponent::TextDisplay(
+                    serenity::CreateTextDisplay::new(result_summary(guild_id, *id, log)),
+                ))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    utils::serenity::interaction_paginate(&ctx, pages).await
+}
@@ -2,11 +2,45 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+use poise::serenity_prelude as serenity;
+
+use crate::Context;
+
+pub mod automod;
 pub mod ban;
 pub mod ban_reason;
+pub mod case;
 pub mod kick;
+pub mod lockdown;
+pub mod logs;
+pub mod note;
 pub mod purge;
 pub mod purge_user;
+pub mod quarantine;
 pub mod rotate_logs;
 pub mod timeout;
 pub mod warn;
+
+/// Who a moderation action is carried out by, so that it can be taken outside of commands.
+#[derive(Clone, Copy)]
+pub struct Moderator<'a> {
+    pub ctx: &'a serenity::Context,
+    /// The user that cases and logs attribute the action to.
+    pub user: serenity::UserId,
+}
+
+impl<'a> From<Context<'a>> for Moderator<'a> {
+    fn from(ctx: Context<'a>) -> Self {
+        Self {
+            ctx: ctx.serenity_context(),
+            user: ctx.author().id,
+        }
+    }
+}
+
+/// When an action of `duration` starting now ends, or `None` if that is out of range.
+pub fn expires_at(duration: std::time::Duration) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::TimeDelta::from_std(duration)
+        .ok()
+        .and_then(|duration| chrono::Utc::now().checked_add_signed(duration))
+}
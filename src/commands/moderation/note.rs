@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use crate::{Context, storage::note::Note, utils};
+
+/// The maximum number of notes shown at once.
+const MAX_SHOWN_NOTES: usize = 10;
+
+pub fn notes_container(
+    user: serenity::UserId,
+    notes: &[Note],
+) -> serenity::CreateContainer<'static> {
+    let mut container =
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "### Notes\n{}",
+                utils::serenity::format_mentionable(Some(user))
+            )),
+        )])
+        .accent_color(0x63e6be);
+
+    if notes.is_empty() {
+        return container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new("*None*"),
+        ));
+    }
+
+    for note in notes.iter().rev().take(MAX_SHOWN_NOTES) {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "`{}` \u{00B7} {}\n-# {} \u{00B7} {}",
+                note.id,
+                utils::truncate(&note.content, 256),
+                note.author.mention(),
+                serenity::FormattedTimestamp::new(note.timestamp.into(), None)
+            )),
+        ));
+    }
+
+    if notes.len() > MAX_SHOWN_NOTES {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "-# Showing the {MAX_SHOWN_NOTES} most recent of {} notes",
+                notes.len()
+            )),
+        ));
+    }
+
+    container
+}
+
+async fn reply(ctx: Context<'_>, user: serenity::UserId, notes: &[Note]) -> Result<()> {
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .allowed_mentions(serenity::CreateAllowedMentions::new())
+            .components(vec![serenity::CreateComponent::Container(notes_container(
+                user, notes,
+            ))]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// The notes on `user` in the current guild, if the author is a moderator there.
+pub async fn moderator_notes(
+    ctx: Context<'_>,
+    user: serenity::UserId,
+) -> Result<Option<Vec<Note>>> {
+    let (Some(guild), Some(storage)) = (ctx.guild_id(), &ctx.data().storage) else {
+        return Ok(None);
+    };
+
+    let Some(member) = ctx.author_member().await else {
+        return Ok(None);
+    };
+
+    let moderator_role = storage.get_config(guild).await?.moderator_role;
+
+    if !member
+        .permissions
+        .is_some_and(|permissions| permissions.moderate_members())
+        && !moderator_role.is_some_and(|role| member.roles.contains(&role))
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(storage.scan_notes(user, guild).await?))
+}
+
+/// Manage private moderator notes on users
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    subcommands("add", "list", "remove"),
+    subcommand_required,
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+pub async fn note(ctx: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Add a note on a user
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+async fn add(
+    ctx: Context<'_>,
+    #[description = "The user to add a note on"] user: serenity::UserId,
+    #[description = "The content of the note"]
+    #[max_length = 1000]
+    content: String,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let note = Note::new(ctx.author().id, content);
+    storage.add_note(user, guild, &note).await?;
+
+    let notes = storage.scan_notes(user, guild).await?;
+
+    reply(ctx, user, &notes).await
+}
+
+/// List the notes on a user
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+async fn list(
+    ctx: Context<'_>,
+    #[description = "The user to list notes on"] user: serenity::UserId,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let notes = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?
+        .scan_notes(user, guild)
+        .await?;
+
+    reply(ctx, user, &notes).await
+}
+
+/// Remove a note on a user
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+async fn remove(
+    ctx: Context<'_>,
+    #[description = "The user the note is on"] user: serenity::UserId,
+    #[description = "The note ID"] id: String,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut notes = storage.scan_notes(user, guild).await?;
+
+    let Some(idx) = notes.iter().position(|note| note.id == id) else {
+        ctx.say(format!("Note `{id}` does not exist!")).await?;
+        return Ok(());
+    };
+
+    storage.del_note(user, guild, &id).await?;
+    notes.remove(idx);
+
+    reply(ctx, user, &notes).await
+}
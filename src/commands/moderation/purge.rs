@@ -2,12 +2,106 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
-use eyre::Result;
-use poise::serenity_prelude as serenity;
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+use regex::{Regex, RegexBuilder};
+use std::{collections::HashSet, sync::LazyLock};
 
-use crate::Context;
+use crate::{Context, utils};
 
-/// Purge a number of messages from a channel
+/// The maximum number of messages to look through when searching for messages to purge.
+const MAX_SCANNED: usize = 5000;
+
+static LINK_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"https?://\S").unwrap());
+
+#[derive(poise::ChoiceParameter, PartialEq, Eq, Clone, Copy, Debug)]
+enum PurgeHas {
+    Attachments,
+    Links,
+    Embeds,
+}
+
+/// The filters that a message needs to pass to be purged.
+#[derive(Debug)]
+struct PurgeFilter {
+    bots: Option<bool>,
+    content: Option<Regex>,
+    has: Option<PurgeHas>,
+    users: HashSet<serenity::UserId>,
+    older_than: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl PurgeFilter {
+    fn matches(&self, message: &serenity::Message) -> bool {
+        message.kind != serenity::MessageType::ThreadStarterMessage
+            && self.bots.is_none_or(|bots| message.author.bot() == bots)
+            && self
+                .content
+                .as_ref()
+                .is_none_or(|content| content.is_match(&message.content))
+            && self.has.is_none_or(|has| match has {
+                PurgeHas::Attachments => !message.attachments.is_empty(),
+                PurgeHas::Links => LINK_REGEX.is_match(&message.content),
+                PurgeHas::Embeds => !message.embeds.is_empty(),
+            })
+            && (self.users.is_empty() || self.users.contains(&message.author.id))
+            && self
+                .older_than
+                .is_none_or(|cutoff| *message.timestamp < cutoff)
+    }
+}
+
+/// Parse `/pattern/` as a regex and anything else as case-insensitive text.
+fn parse_content(s: &str) -> Result<Regex> {
+    if let Some(pattern) = s
+        .strip_prefix('/')
+        .and_then(|s| s.strip_suffix('/'))
+        .filter(|pattern| !pattern.is_empty())
+    {
+        return Ok(Regex::new(pattern)?);
+    }
+
+    Ok(RegexBuilder::new(&regex::escape(s))
+        .case_insensitive(true)
+        .build()?)
+}
+
+/// Parse a message ID or message link.
+fn parse_message(s: &str) -> Result<serenity::MessageId> {
+    s.trim()
+        .rsplit('/')
+        .next()
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| eyre!("invalid message ID or link `{s}`"))
+}
+
+async fn edit_status(
+    ctx: Context<'_>,
+    reply: &poise::ReplyHandle<'_>,
+    title: &str,
+    content: String,
+    accent_color: u32,
+) -> Result<()> {
+    reply
+        .edit(
+            ctx,
+            poise::CreateReply::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .components(vec![serenity::CreateComponent::Container(
+                    serenity::CreateContainer::new(vec![
+                        serenity::CreateContainerComponent::TextDisplay(
+                            serenity::CreateTextDisplay::new(format!("### {title}\n{content}")),
+                        ),
+                    ])
+                    .accent_color(accent_color),
+                )]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Purge messages from a channel
 #[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
 #[poise::command(
     slash_command,
@@ -17,6 +111,7 @@ use crate::Context;
     interaction_context = "Guild",
     default_member_permissions = "MANAGE_MESSAGES"
 )]
+#[expect(clippy::too_many_arguments)]
 pub async fn purge(
     ctx: Context<'_>,
 
@@ -26,51 +121,168 @@ pub async fn purge(
 
     #[description = "Channel to delete messages from (defaults to current channel)"]
     channel: Option<serenity::GenericChannelId>,
+
+    #[description = "Only messages from bots (or only from humans)"] bots: Option<bool>,
+    #[description = "Only messages containing this text or /regex/"] contains: Option<String>,
+    #[description = "Only messages with attachments, links, or embeds"] has: Option<PurgeHas>,
+    #[description = "Only messages from these users"] users: Option<String>,
+    #[description = "Only messages before this message ID or link"] before: Option<String>,
+    #[description = "Only messages after this message ID or link"] after: Option<String>,
+    #[description = "Only messages older than this duration"] older_than: Option<String>,
 ) -> Result<()> {
     ctx.defer_ephemeral().await?;
 
     let channel = channel.unwrap_or_else(|| ctx.channel_id());
+    let count = usize::try_from(count)?;
 
-    let mut count_remaining = count;
-    let mut count_success = 0usize;
+    let older_than = if let Some(older_than) = older_than {
+        let Some(older_than) = humantime::parse_duration(&older_than)
+            .ok()
+            .and_then(|duration| chrono::TimeDelta::from_std(duration).ok())
+            .and_then(|duration| chrono::Utc::now().checked_sub_signed(duration))
+        else {
+            ctx.say("Invalid duration provided!").await?;
+            return Ok(());
+        };
 
-    while count_remaining > 0 {
-        let count_current: u8 = count_remaining.min(100).try_into()?;
+        Some(older_than)
+    } else {
+        None
+    };
 
-        let messages = channel
-            .messages(ctx, serenity::GetMessages::new().limit(count_current))
-            .await?
-            .iter()
-            .filter(|m| {
-                *m.timestamp >= chrono::Utc::now() - chrono::Duration::weeks(2)
-                    && m.kind != serenity::MessageType::ThreadStarterMessage
-            })
-            .map(|m| m.id)
-            .take(count_current.into())
-            .collect::<Vec<_>>();
+    let filter = PurgeFilter {
+        bots,
+        content: contains.as_deref().map(parse_content).transpose()?,
+        has,
+        users: users
+            .as_deref()
+            .map(utils::serenity::parse_ids)
+            .transpose()?
+            .unwrap_or_default()
+            .into_iter()
+            .map(serenity::UserId::new)
+            .collect(),
+        older_than,
+    };
+
+    let after = after.as_deref().map(parse_message).transpose()?;
+    let mut cursor = before.as_deref().map(parse_message).transpose()?;
+
+    let mut scanned = 0usize;
+    let mut matched = Vec::new();
 
-        if messages.is_empty() {
-            break;
+    'scan: while matched.len() < count && scanned < MAX_SCANNED {
+        let mut request = serenity::GetMessages::new().limit(100);
+        if let Some(cursor) = cursor {
+            request = request.before(cursor);
         }
 
-        channel
-            .delete_messages(
-                ctx.http(),
-                &messages,
-                Some(&format!(
-                    "Purge by @{} ({})",
-                    ctx.author().name,
-                    ctx.author().id
-                )),
-            )
+        let messages = channel.messages(ctx, request).await?;
+        scanned += messages.len();
+
+        for message in &messages {
+            if after.is_some_and(|after| message.id <= after) {
+                break 'scan;
+            }
+
+            if filter.matches(message) {
+                matched.push((message.id, *message.timestamp));
+                if matched.len() >= count {
+                    break 'scan;
+                }
+            }
+        }
+
+        match messages.last() {
+            Some(last) if messages.len() == 100 => cursor = Some(last.id),
+            _ => break,
+        }
+    }
+
+    if matched.is_empty() {
+        ctx.say(format!("No messages matched out of {scanned} scanned."))
             .await?;
+        return Ok(());
+    }
 
-        count_remaining -= u64::from(count_current);
-        count_success += messages.len();
+    // messages older than two weeks cannot be bulk deleted
+    let bulk_cutoff = chrono::Utc::now() - chrono::Duration::weeks(2);
+    let (recent, old): (Vec<_>, Vec<_>) = matched
+        .into_iter()
+        .partition(|(_, timestamp)| *timestamp >= bulk_cutoff);
+    let recent = recent.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+    let old = old.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+    let total = recent.len() + old.len();
+
+    let mut preview = format!(
+        "### Purge\nAre you sure you want to delete {total} messages from {}?\n-# Matched out of {scanned} scanned messages",
+        channel.mention()
+    );
+    if !old.is_empty() {
+        preview.push_str(&format!(
+            "\n-# {} messages are older than 14 days and will be deleted one by one",
+            old.len()
+        ));
     }
 
-    ctx.say(format!("**Success!** Deleted {count_success} messages."))
+    let (confirmed, reply) = utils::serenity::interaction_confirm(
+        &ctx,
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(preview),
+        )])
+        .accent_color(0xffd43b),
+    )
+    .await?;
+
+    if !confirmed {
+        edit_status(
+            ctx,
+            &reply,
+            "Purge cancelled",
+            "No messages were deleted.".to_owned(),
+            0xff6b6b,
+        )
         .await?;
+        return Ok(());
+    }
 
-    Ok(())
+    let reason = format!("Purge by @{} ({})", ctx.author().name, ctx.author().id);
+
+    for chunk in recent.chunks(100) {
+        if let [id] = chunk {
+            channel
+                .delete_message(ctx.http(), *id, Some(&reason))
+                .await?;
+        } else {
+            channel
+                .delete_messages(ctx.http(), chunk, Some(&reason))
+                .await?;
+        }
+    }
+
+    for (idx, id) in old.iter().enumerate() {
+        if idx % 10 == 0 {
+            edit_status(
+                ctx,
+                &reply,
+                "Purging",
+                format!("Deleted {} of {total} messages...", recent.len() + idx),
+                0xffd43b,
+            )
+            .await?;
+        }
+
+        channel
+            .delete_message(ctx.http(), *id, Some(&reason))
+            .await?;
+    }
+
+    edit_status(
+        ctx,
+        &reply,
+        "Purged messages",
+        format!("**Success!** Deleted {total} messages."),
+        0x4ade80,
+    )
+    .await
 }
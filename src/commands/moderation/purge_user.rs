@@ -5,7 +5,11 @@
 use eyre::{Result, eyre};
 use poise::serenity_prelude::{self as serenity, Mentionable as _};
 
-use crate::{Context, utils};
+use crate::{
+    Context,
+    storage::case::{self, CaseAction, CaseLogMessage, DmStatus, ModerationCase},
+    utils,
+};
 
 /// Kick a user and purge recent messages from them
 #[tracing::instrument(skip(ctx, user), fields(user = user.id.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
@@ -54,7 +58,7 @@ pub async fn purge_user(
         ));
     }
 
-    if dm.unwrap_or(true) {
+    let dm_status = if dm.unwrap_or(true) {
         let dm_container =
             container
                 .clone()
@@ -80,19 +84,17 @@ pub async fn purge_user(
                 .await
                 .is_ok()
         {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nYes"),
-            ));
+            DmStatus::Yes
         } else {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nFailed"),
-            ));
+            DmStatus::Failed
         }
     } else {
-        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-            serenity::CreateTextDisplay::new("**User notified**\nNo"),
-        ));
-    }
+        DmStatus::No
+    };
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!("**User notified**\n{dm_status}")),
+    ));
 
     container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
         serenity::CreateTextDisplay::new(format!(
@@ -100,6 +102,29 @@ pub async fn purge_user(
         )),
     ));
 
+    let mut case = if let Some(storage) = &ctx.data().storage {
+        Some(
+            case::create(
+                &**storage,
+                partial_guild.id,
+                ModerationCase {
+                    reason: reason.clone(),
+                    dm: dm_status,
+                    ..ModerationCase::new(CaseAction::Purge, user.id, ctx.author().id)
+                },
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    if let Some(case) = &case {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
+        ));
+    }
+
     let reply_container = container.clone();
 
     if let Some(storage) = &ctx.data().storage {
@@ -115,7 +140,7 @@ pub async fn purge_user(
                     )),
                 ));
 
-            logs_channel
+            let log_message = logs_channel
                 .send_message(
                     ctx.http(),
                     serenity::CreateMessage::default()
@@ -124,6 +149,15 @@ pub async fn purge_user(
                         .components(vec![serenity::CreateComponent::Container(log_container)]),
                 )
                 .await?;
+
+            if let Some(case) = &mut case {
+                case.log_message = Some(CaseLogMessage {
+                    channel: logs_channel,
+                    message: log_message.id,
+                });
+
+                storage.set_case(partial_guild.id, case).await?;
+            }
         }
     }
 
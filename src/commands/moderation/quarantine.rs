@@ -0,0 +1,338 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use super::{Moderator, expires_at};
+use crate::{
+    Context, handlers,
+    storage::{
+        case::{self, CaseAction, CaseLogMessage, DmStatus, ModerationCase},
+        quarantine::Quarantine,
+    },
+    utils,
+};
+
+/// Quarantine a user, replacing their roles until they are released
+#[tracing::instrument(skip(ctx, user), fields(user = user.id.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+pub async fn quarantine(
+    ctx: Context<'_>,
+    #[description = "The user to quarantine"] user: serenity::User,
+    #[description = "Duration of quarantine (default: until released)"] duration: Option<String>,
+    #[description = "Reason for the quarantine"] reason: Option<String>,
+    #[description = "Notify with a direct message (default: true)"] dm: Option<bool>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let partial_guild = ctx
+        .partial_guild()
+        .await
+        .ok_or_else(|| eyre!("failed to obtain partial guild"))?;
+
+    let storage = ctx
+        .data()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let Some(role) = storage.get_config(partial_guild.id).await?.quarantine_role else {
+        ctx.say("No quarantine role is configured! Set one with `/config edit`.")
+            .await?;
+        return Ok(());
+    };
+
+    if storage
+        .get_quarantine(partial_guild.id, user.id)
+        .await?
+        .is_some()
+    {
+        ctx.say(format!("{} is already quarantined!", user.mention()))
+            .await?;
+        return Ok(());
+    }
+
+    let duration = match duration.as_deref().map(humantime::parse_duration) {
+        Some(Ok(duration)) if expires_at(duration).is_some() => Some(duration),
+        Some(_) => {
+            ctx.say("Invalid duration provided!").await?;
+            return Ok(());
+        }
+        None => None,
+    };
+
+    let container = execute(
+        ctx.into(),
+        &partial_guild,
+        &user,
+        role,
+        duration,
+        reason,
+        dm.unwrap_or(true),
+    )
+    .await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .components(vec![serenity::CreateComponent::Container(container)]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Replace the roles of `user` with `role`, saving them for release, then notify and log.
+async fn execute(
+    moderator: Moderator<'_>,
+    partial_guild: &serenity::PartialGuild,
+    user: &serenity::User,
+    role: serenity::RoleId,
+    duration: Option<std::time::Duration>,
+    reason: Option<String>,
+    dm: bool,
+) -> Result<serenity::CreateContainer<'static>> {
+    let storage = moderator
+        .ctx
+        .data::<crate::Data>()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let member = partial_guild.id.member(moderator.ctx, user.id).await?;
+    let managed = handlers::quarantine::managed_roles(partial_guild, &member.roles);
+
+    let mut quarantine = Quarantine {
+        guild: partial_guild.id,
+        user: user.id,
+        roles: member
+            .roles
+            .iter()
+            .filter(|r| **r != role && !managed.contains(r))
+            .copied()
+            .collect(),
+        started_at: chrono::Utc::now(),
+        expires_at: duration
+            .map(|duration| {
+                expires_at(duration).ok_or_else(|| eyre!("quarantine duration is out of range"))
+            })
+            .transpose()?,
+        case: None,
+    };
+
+    // The saved roles are stored first so that they are never lost
+    storage.set_quarantine(&quarantine).await?;
+
+    let mut edit_member = serenity::EditMember::default()
+        .roles(managed.into_iter().chain([role]).collect::<Vec<_>>());
+
+    if let Some(reason) = &reason {
+        edit_member = edit_member.audit_log_reason(reason);
+    }
+
+    if let Err(err) = partial_guild
+        .id
+        .edit_member(&moderator.ctx.http, user.id, edit_member)
+        .await
+    {
+        storage.del_quarantine(partial_guild.id, user.id).await?;
+        return Err(err.into());
+    }
+
+    let mut container =
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "### Quarantine\n{}",
+                utils::serenity::format_mentionable(Some(user.id)),
+            )),
+        )])
+        .accent_color(0x9775fa);
+
+    if let Some(reason) = &reason {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Reason**\n{reason}")),
+        ));
+    }
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!(
+            "**Duration**\n{}",
+            duration.map_or_else(
+                || "Until released".to_owned(),
+                |duration| humantime::format_duration(duration).to_string()
+            )
+        )),
+    ));
+
+    let dm_status = if dm {
+        let dm_container =
+            container
+                .clone()
+                .add_component(serenity::CreateContainerComponent::TextDisplay(
+                    serenity::CreateTextDisplay::new(format!(
+                        "-# {} \u{00B7} {}",
+                        partial_guild.name,
+                        serenity::FormattedTimestamp::now()
+                    )),
+                ));
+
+        if let Ok(dm) = user.create_dm_channel(moderator.ctx).await
+            && dm
+                .id
+                .widen()
+                .send_message(
+                    &moderator.ctx.http,
+                    serenity::CreateMessage::default()
+                        .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                        .allowed_mentions(serenity::CreateAllowedMentions::new())
+                        .components(vec![serenity::CreateComponent::Container(dm_container)]),
+                )
+                .await
+                .is_ok()
+        {
+            DmStatus::Yes
+        } else {
+            DmStatus::Failed
+        }
+    } else {
+        DmStatus::No
+    };
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!("**User notified**\n{dm_status}")),
+    ));
+
+    let mut case = case::create(
+        &**storage,
+        partial_guild.id,
+        ModerationCase {
+            reason,
+            duration,
+            dm: dm_status,
+            ..ModerationCase::new(CaseAction::Quarantine, user.id, moderator.user)
+        },
+    )
+    .await?;
+
+    quarantine.case = Some(case.id);
+    storage.set_quarantine(&quarantine).await?;
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
+    ));
+
+    let reply_container = container.clone();
+
+    if let Some(logs_channel) = storage
+        .get_config(partial_guild.id)
+        .await?
+        .moderation_logs_channel
+    {
+        let log_container =
+            container.add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!(
+                    "-# {} \u{00B7} {}",
+                    moderator.user.mention(),
+                    serenity::FormattedTimestamp::now()
+                )),
+            ));
+
+        let log_message = logs_channel
+            .send_message(
+                &moderator.ctx.http,
+                serenity::CreateMessage::default()
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new())
+                    .components(vec![serenity::CreateComponent::Container(log_container)]),
+            )
+            .await?;
+
+        case.log_message = Some(CaseLogMessage {
+            channel: logs_channel,
+            message: log_message.id,
+        });
+
+        storage.set_case(partial_guild.id, &case).await?;
+    }
+
+    Ok(reply_container)
+}
+
+/// Release a user from quarantine, restoring their saved roles
+#[tracing::instrument(skip(ctx, user), fields(user = user.id.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MODERATE_MEMBERS"
+)]
+pub async fn unquarantine(
+    ctx: Context<'_>,
+    #[description = "The user to release"] user: serenity::User,
+    #[description = "Reason for the release"] reason: Option<String>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild = ctx.guild_id().ok_or_else(|| eyre!("no available guild"))?;
+
+    let Some(quarantine) = handlers::quarantine::release(
+        ctx.http(),
+        ctx.data(),
+        guild,
+        user.id,
+        reason.as_deref().unwrap_or("Released from quarantine"),
+        Some(ctx.author().id),
+    )
+    .await?
+    else {
+        ctx.say(format!("{} is not quarantined!", user.mention()))
+            .await?;
+        return Ok(());
+    };
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .allowed_mentions(serenity::CreateAllowedMentions::new())
+            .components(vec![serenity::CreateComponent::Container(
+                serenity::CreateContainer::new(vec![
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!(
+                            "### Unquarantine\n{}",
+                            utils::serenity::format_mentionable(Some(user.id))
+                        )),
+                    ),
+                    serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!(
+                            "**Restored roles**\n{}",
+                            if quarantine.roles.is_empty() {
+                                "*None*".to_owned()
+                            } else {
+                                quarantine
+                                    .roles
+                                    .iter()
+                                    .map(|role| role.mention().to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(" ")
+                            }
+                        )),
+                    ),
+                ])
+                .accent_color(0x9775fa),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
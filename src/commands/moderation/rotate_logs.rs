@@ -8,7 +8,7 @@ use poise::{
     serenity_prelude::{self as serenity, Mentionable as _},
 };
 
-use crate::{Context, utils};
+use crate::{Context, handlers, utils};
 
 async fn recreate(
     ctx: Context<'_>,
@@ -54,6 +54,7 @@ enum RotateLogsKind {
     Message,
     Moderation,
     Member,
+    Server,
 }
 
 /// Rotate a logs channel by recreating and automatically configuring it
@@ -126,9 +127,18 @@ Are you sure you want to rotate this logs channel? This will delete the configur
                     new_channel = Some(ch);
                 }
             }
+
+            RotateLogsKind::Server => {
+                if let Some(channel) = guild_config.server_logs_channel
+                    && let Some(ch) = recreate(ctx, channel, guild_id, actor).await?
+                {
+                    guild_config.server_logs_channel = Some(ch);
+                    new_channel = Some(ch);
+                }
+            }
         }
 
-        storage.set_config(guild_id, &guild_config).await?;
+        handlers::config::save(ctx.http(), &**storage, guild_id, actor.id, &guild_config).await?;
 
         if let Some(new_channel) = new_channel {
             reply
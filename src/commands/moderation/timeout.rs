@@ -5,7 +5,12 @@
 use eyre::{Result, eyre};
 use poise::serenity_prelude::{self as serenity, Mentionable as _};
 
-use crate::{Context, utils};
+use super::Moderator;
+use crate::{
+    Context,
+    storage::case::{self, CaseAction, CaseLogMessage, DmStatus, ModerationCase},
+    utils,
+};
 
 /// Timeout a user
 #[tracing::instrument(skip(ctx, user), fields(user = user.id.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
@@ -36,6 +41,37 @@ pub async fn timeout(
         return Ok(());
     };
 
+    let container = execute(
+        ctx.into(),
+        &partial_guild,
+        &user,
+        duration,
+        reason,
+        dm.unwrap_or(true),
+    )
+    .await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .components(vec![serenity::CreateComponent::Container(container)]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Time out `user`, notifying them and logging the timeout like `/timeout` does.
+pub async fn execute(
+    moderator: Moderator<'_>,
+    partial_guild: &serenity::PartialGuild,
+    user: &serenity::User,
+    duration: std::time::Duration,
+    reason: Option<String>,
+    dm: bool,
+) -> Result<serenity::CreateContainer<'static>> {
+    let data = moderator.ctx.data::<crate::Data>();
+
     let end = chrono::Utc::now() + duration;
 
     let mut container =
@@ -47,7 +83,7 @@ pub async fn timeout(
         )])
         .accent_color(0x9775fa);
 
-    let extra_message = if let Some(storage) = &ctx.data().storage {
+    let extra_message = if let Some(storage) = &data.storage {
         let guild_config = storage.get_config(partial_guild.id).await?;
         guild_config.moderation_extra_message_timeout
     } else {
@@ -67,7 +103,7 @@ pub async fn timeout(
         )),
     ));
 
-    if dm.unwrap_or(true) {
+    let dm_status = if dm {
         let dm_container =
             container
                 .clone()
@@ -79,12 +115,12 @@ pub async fn timeout(
                     )),
                 ));
 
-        if let Ok(dm) = user.create_dm_channel(ctx).await
+        if let Ok(dm) = user.create_dm_channel(moderator.ctx).await
             && dm
                 .id
                 .widen()
                 .send_message(
-                    ctx.http(),
+                    &moderator.ctx.http,
                     serenity::CreateMessage::default()
                         .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
                         .allowed_mentions(serenity::CreateAllowedMentions::new())
@@ -93,23 +129,45 @@ pub async fn timeout(
                 .await
                 .is_ok()
         {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nYes"),
-            ));
+            DmStatus::Yes
         } else {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nFailed"),
-            ));
+            DmStatus::Failed
         }
     } else {
+        DmStatus::No
+    };
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!("**User notified**\n{dm_status}")),
+    ));
+
+    let mut case = if let Some(storage) = &data.storage {
+        Some(
+            case::create(
+                &**storage,
+                partial_guild.id,
+                ModerationCase {
+                    reason: reason.clone(),
+                    duration: Some(duration),
+                    dm: dm_status,
+                    ..ModerationCase::new(CaseAction::Timeout, user.id, moderator.user)
+                },
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    if let Some(case) = &case {
         container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-            serenity::CreateTextDisplay::new("**User notified**\nNo"),
+            serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
         ));
     }
 
     let reply_container = container.clone();
 
-    if let Some(storage) = &ctx.data().storage {
+    if let Some(storage) = &data.storage {
         let guild_config = storage.get_config(partial_guild.id).await?;
 
         if let Some(logs_channel) = guild_config.moderation_logs_channel {
@@ -117,20 +175,29 @@ pub async fn timeout(
                 container.add_component(serenity::CreateContainerComponent::TextDisplay(
                     serenity::CreateTextDisplay::new(format!(
                         "-# {} \u{00B7} {}",
-                        ctx.author().mention(),
+                        moderator.user.mention(),
                         serenity::FormattedTimestamp::now()
                     )),
                 ));
 
-            logs_channel
+            let log_message = logs_channel
                 .send_message(
-                    ctx.http(),
+                    &moderator.ctx.http,
                     serenity::CreateMessage::default()
                         .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
                         .allowed_mentions(serenity::CreateAllowedMentions::new())
                         .components(vec![serenity::CreateComponent::Container(log_container)]),
                 )
                 .await?;
+
+            if let Some(case) = &mut case {
+                case.log_message = Some(CaseLogMessage {
+                    channel: logs_channel,
+                    message: log_message.id,
+                });
+
+                storage.set_case(partial_guild.id, case).await?;
+            }
         }
     }
 
@@ -142,17 +209,10 @@ pub async fn timeout(
 
     partial_guild
         .id
-        .member(&ctx, user.id)
+        .member(moderator.ctx, user.id)
         .await?
-        .edit(ctx.http(), edit_member)
+        .edit(&moderator.ctx.http, edit_member)
         .await?;
 
-    ctx.send(
-        poise::CreateReply::default()
-            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
-            .components(vec![serenity::CreateComponent::Container(reply_container)]),
-    )
-    .await?;
-
-    Ok(())
+    Ok(reply_container)
 }
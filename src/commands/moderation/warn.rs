@@ -5,7 +5,16 @@
 use eyre::{Result, eyre};
 use poise::serenity_prelude::{self as serenity, Mentionable as _};
 
-use crate::{Context, utils};
+use super::{Moderator, ban, kick, timeout};
+use crate::{
+    Context,
+    config::WarnEscalation,
+    storage::{
+        case::{self, CaseAction, CaseLogMessage, DmStatus, ModerationCase},
+        warn::{Warn, WarnCounts},
+    },
+    utils,
+};
 
 /// Warn a user
 #[tracing::instrument(skip(ctx, user), fields(user = user.id.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
@@ -30,8 +39,47 @@ pub async fn warn(
         .await
         .ok_or_else(|| eyre!("failed to obtain partial guild"))?;
 
-    let warn_count = if let Some(storage) = &ctx.data().storage {
-        Some(storage.incr_warn_count(user.id, partial_guild.id).await?)
+    let (components, _) = execute(
+        ctx.into(),
+        &partial_guild,
+        &user,
+        reason,
+        dm.unwrap_or(true),
+    )
+    .await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+            .components(components),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Warn `user`, notifying them, logging the warn, and escalating like `/warn` does.
+/// Returns the components to reply with and the case of the warn.
+pub async fn execute(
+    moderator: Moderator<'_>,
+    partial_guild: &serenity::PartialGuild,
+    user: &serenity::User,
+    reason: Option<String>,
+    dm: bool,
+) -> Result<(
+    Vec<serenity::CreateComponent<'static>>,
+    Option<ModerationCase>,
+)> {
+    let data = moderator.ctx.data::<crate::Data>();
+
+    // Counts including the warn being issued
+    let warn_counts = if let Some(storage) = &data.storage {
+        let counts = WarnCounts::fetch(&**storage, user.id, partial_guild.id).await?;
+
+        Some(WarnCounts {
+            active: counts.active + 1,
+            total: counts.total + 1,
+        })
     } else {
         None
     };
@@ -51,13 +99,16 @@ pub async fn warn(
         ));
     }
 
-    if let Some(warn_count) = &warn_count {
+    if let Some(warn_counts) = &warn_counts {
         container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-            serenity::CreateTextDisplay::new(format!("**Warn count**\n{warn_count}")),
+            serenity::CreateTextDisplay::new(format!(
+                "**Warns**\n{} active \u{00B7} {} total",
+                warn_counts.active, warn_counts.total
+            )),
         ));
     }
 
-    if dm.unwrap_or(true) {
+    let dm_status = if dm {
         let dm_container =
             container
                 .clone()
@@ -69,12 +120,12 @@ pub async fn warn(
                     )),
                 ));
 
-        if let Ok(dm) = user.create_dm_channel(ctx).await
+        if let Ok(dm) = user.create_dm_channel(moderator.ctx).await
             && dm
                 .id
                 .widen()
                 .send_message(
-                    ctx.http(),
+                    &moderator.ctx.http,
                     serenity::CreateMessage::default()
                         .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
                         .allowed_mentions(serenity::CreateAllowedMentions::new())
@@ -83,23 +134,50 @@ pub async fn warn(
                 .await
                 .is_ok()
         {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nYes"),
-            ));
+            DmStatus::Yes
         } else {
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-                serenity::CreateTextDisplay::new("**User notified**\nFailed"),
-            ));
+            DmStatus::Failed
         }
     } else {
+        DmStatus::No
+    };
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!("**User notified**\n{dm_status}")),
+    ));
+
+    let mut case = if let Some(storage) = &data.storage {
+        Some(
+            case::create(
+                &**storage,
+                partial_guild.id,
+                ModerationCase {
+                    reason: reason.clone(),
+                    dm: dm_status,
+                    ..ModerationCase::new(CaseAction::Warn, user.id, moderator.user)
+                },
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    if let Some(storage) = &data.storage
+        && let Some(case) = &case
+    {
+        storage
+            .add_warn(user.id, partial_guild.id, &Warn::new(Some(case.id)))
+            .await?;
+
         container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
-            serenity::CreateTextDisplay::new("**User notified**\nNo"),
+            serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
         ));
     }
 
     let reply_container = container.clone();
 
-    if let Some(storage) = &ctx.data().storage {
+    if let Some(storage) = &data.storage {
         let guild_config = storage.get_config(partial_guild.id).await?;
 
         if let Some(logs_channel) = guild_config.moderation_logs_channel {
@@ -107,34 +185,87 @@ pub async fn warn(
                 container.add_component(serenity::CreateContainerComponent::TextDisplay(
                     serenity::CreateTextDisplay::new(format!(
                         "-# {} \u{00B7} {}",
-                        ctx.author().mention(),
+                        moderator.user.mention(),
                         serenity::FormattedTimestamp::now()
                     )),
                 ));
 
-            logs_channel
+            let log_message = logs_channel
                 .send_message(
-                    ctx.http(),
+                    &moderator.ctx.http,
                     serenity::CreateMessage::default()
                         .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
                         .allowed_mentions(serenity::CreateAllowedMentions::new())
                         .components(vec![serenity::CreateComponent::Container(log_container)]),
                 )
                 .await?;
+
+            if let Some(case) = &mut case {
+                case.log_message = Some(CaseLogMessage {
+                    channel: logs_channel,
+                    message: log_message.id,
+                });
+
+                storage.set_case(partial_guild.id, case).await?;
+            }
         }
     }
 
-    ctx.send(
-        poise::CreateReply::default()
-            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
-            .components(vec![serenity::CreateComponent::Container(reply_container)]),
-    )
-    .await?;
+    let mut components = vec![serenity::CreateComponent::Container(reply_container)];
 
-    Ok(())
+    if let Some(case) = &case
+        && let Some(warn_counts) = warn_counts
+        && let Some(container) =
+            escalate(moderator, partial_guild, user, warn_counts.active, case, dm).await?
+    {
+        components.push(serenity::CreateComponent::Container(container));
+    }
+
+    Ok((components, case))
+}
+
+/// Apply the guild's escalation policy for `warn_count` active warns, if there is one.
+async fn escalate(
+    moderator: Moderator<'_>,
+    partial_guild: &serenity::PartialGuild,
+    user: &serenity::User,
+    warn_count: u64,
+    case: &ModerationCase,
+    dm: bool,
+) -> Result<Option<serenity::CreateContainer<'static>>> {
+    let Some(storage) = &moderator.ctx.data::<crate::Data>().storage else {
+        return Ok(None);
+    };
+
+    let Some(escalation) = storage
+        .get_config(partial_guild.id)
+        .await?
+        .warn_escalations
+        .get(&warn_count)
+        .copied()
+    else {
+        return Ok(None);
+    };
+
+    let reason = Some(format!(
+        "Reached {warn_count} active warns with warn case {}",
+        case.id
+    ));
+
+    let container = match escalation {
+        WarnEscalation::Timeout { duration } => {
+            timeout::execute(moderator, partial_guild, user, duration, reason, dm).await?
+        }
+        WarnEscalation::Kick => kick::execute(moderator, partial_guild, user, reason, dm).await?,
+        WarnEscalation::Ban => {
+            ban::execute(moderator, partial_guild, user, 0, None, reason, dm).await?
+        }
+    };
+
+    Ok(Some(container))
 }
 
-/// Reset a user's warn count to zero
+/// Pardon a single warn of a user, or reset all of their warns
 #[tracing::instrument(skip(ctx), fields(user = user.id.get(), ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
 #[poise::command(
     slash_command,
@@ -148,6 +279,7 @@ pub async fn warn(
 pub async fn warn_reset(
     ctx: Context<'_>,
     #[description = "The user to reset warns for"] user: serenity::User,
+    #[description = "Case number of a warn to pardon (default: all warns)"] case: Option<u64>,
 ) -> Result<()> {
     ctx.defer_ephemeral().await?;
 
@@ -156,20 +288,57 @@ pub async fn warn_reset(
         .await
         .ok_or_else(|| eyre!("failed to obtain partial guild"))?;
 
-    if let Some(storage) = &ctx.data().storage {
-        storage.del_warn_count(user.id, partial_guild.id).await?;
+    let warn_counts = if let Some(storage) = &ctx.data().storage {
+        if let Some(case) = case {
+            let Some(warn) = storage
+                .scan_warns(user.id, partial_guild.id)
+                .await?
+                .into_iter()
+                .find(|warn| warn.case == Some(case))
+            else {
+                ctx.say(format!("Case {case} is not a warn of this user!"))
+                    .await?;
+                return Ok(());
+            };
+
+            storage
+                .del_warn(user.id, partial_guild.id, &warn.id)
+                .await?;
+        } else {
+            storage.del_warns(user.id, partial_guild.id).await?;
+        }
+
+        WarnCounts::fetch(&**storage, user.id, partial_guild.id).await?
+    } else {
+        WarnCounts::default()
+    };
+
+    let mut container =
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "### {}\n{}",
+                if case.is_some() {
+                    "Warn pardoned"
+                } else {
+                    "Warn reset"
+                },
+                utils::serenity::format_mentionable(Some(user.id)),
+            )),
+        )])
+        .accent_color(0xfacc15);
+
+    if let Some(case) = case {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Case**\n{case}")),
+        ));
     }
 
-    let container = serenity::CreateContainer::new(vec![
-        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
-            "### Warn reset\n{}",
-            utils::serenity::format_mentionable(Some(user.id)),
-        ))),
-        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
-            "**Warn count**\n0".to_string(),
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!(
+            "**Warns**\n{} active \u{00B7} {} total",
+            warn_counts.active, warn_counts.total
         )),
-    ])
-    .accent_color(0xfacc15);
+    ));
 
     let reply_container = container.clone();
 
@@ -69,7 +69,9 @@ pub async fn lighthouse(
         return Ok(());
     }
 
-    let Some(key) = &CONFIG.pagespeed_api_key else {
+    let config = CONFIG.get();
+
+    let Some(key) = &config.pagespeed_api_key else {
         ctx.send(
             CreateReply::default()
                 .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
@@ -70,7 +70,7 @@ async fn translate_call(src: &str) -> Result<TranslateResult> {
 pub async fn translate(ctx: Context<'_>, message: serenity::Message) -> Result<()> {
     ctx.defer().await?;
 
-    if CONFIG.anthropic_api_key.is_none() {
+    if CONFIG.get().anthropic_api_key.is_none() {
         ctx.send(
             CreateReply::default()
                 .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
@@ -165,7 +165,7 @@ There is no content to translate.",
 pub async fn translate_ephemeral(ctx: Context<'_>, message: serenity::Message) -> Result<()> {
     ctx.defer_ephemeral().await?;
 
-    if CONFIG.anthropic_api_key.is_none() {
+    if CONFIG.get().anthropic_api_key.is_none() {
         ctx.send(
             CreateReply::default()
                 .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
@@ -7,7 +7,7 @@ use std::borrow::Cow;
 use eyre::Result;
 use poise::serenity_prelude as serenity;
 
-use crate::Context;
+use crate::{Context, commands::moderation::note};
 
 fn short_link<'a>(url: impl Into<Cow<'a, str>>) -> String {
     let url: Cow<'a, str> = url.into();
@@ -21,18 +21,7 @@ fn short_link<'a>(url: impl Into<Cow<'a, str>>) -> String {
     )
 }
 
-/// Show information about a user
-#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
-#[poise::command(
-    slash_command,
-    install_context = "Guild | User",
-    interaction_context = "Guild | BotDm | PrivateChannel"
-)]
-pub async fn user(ctx: Context<'_>, user: serenity::UserId) -> Result<()> {
-    ctx.defer().await?;
-
-    let user = user.to_user(&ctx).await?;
-
+async fn show(ctx: Context<'_>, user: &serenity::User) -> Result<()> {
     let mut container =
         serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::Section(
             serenity::CreateSection::new(
@@ -91,5 +80,50 @@ pub async fn user(ctx: Context<'_>, user: serenity::UserId) -> Result<()> {
     )
     .await?;
 
+    if let Some(notes) = note::moderator_notes(ctx, user.id).await?
+        && !notes.is_empty()
+    {
+        ctx.send(
+            poise::CreateReply::default()
+                .ephemeral(true)
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(serenity::CreateAllowedMentions::new())
+                .components(vec![serenity::CreateComponent::Container(
+                    note::notes_container(user.id, &notes),
+                )]),
+        )
+        .await?;
+    }
+
     Ok(())
 }
+
+/// Show information about a user
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    install_context = "Guild | User",
+    interaction_context = "Guild | BotDm | PrivateChannel"
+)]
+pub async fn user(ctx: Context<'_>, user: serenity::UserId) -> Result<()> {
+    ctx.defer().await?;
+
+    let user = user.to_user(&ctx).await?;
+
+    show(ctx, &user).await
+}
+
+/// Show information about a user
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    context_menu_command = "User information",
+    install_context = "Guild | User",
+    interaction_context = "Guild | BotDm | PrivateChannel"
+)]
+pub async fn user_context_menu(ctx: Context<'_>, user: serenity::User) -> Result<()> {
+    ctx.defer().await?;
+
+    let user = user.id.to_user(&ctx).await?;
+
+    show(ctx, &user).await
+}
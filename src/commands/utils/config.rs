@@ -2,16 +2,32 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
-use eyre::{Result, eyre};
+use eyre::{Result, bail, eyre};
 use poise::{
-    CreateReply,
+    ChoiceParameter as _, CreateReply,
     serenity_prelude::{
-        ChannelType, CreateActionRow, CreateComponent, CreateContainer, CreateContainerComponent,
-        CreateSelectMenu, CreateSelectMenuKind, CreateTextDisplay, MessageFlags,
+        Attachment, ChannelType, CreateActionRow, CreateAllowedMentions, CreateAttachment,
+        CreateComponent, CreateContainer, CreateContainerComponent, CreateSelectMenu,
+        CreateSelectMenuKind, CreateSelectMenuOption, CreateTextDisplay, FormattedTimestamp,
+        GuildChannel, GuildId, Mentionable as _, MessageFlags,
     },
 };
 
-use crate::Context;
+use crate::{
+    Context,
+    commands::moderation::expires_at,
+    config::{self, CONFIG, Feature, GuildConfig, MemberChange, WarnEscalation},
+    handlers,
+    http::HTTP,
+    storage::{
+        appeal,
+        archive::{ArchiveFormat, GuildArchive},
+    },
+    utils,
+};
+
+/// Maximum size of an archive accepted by `/config import`, in bytes.
+const MAX_ARCHIVE_SIZE: u32 = 1024 * 1024;
 
 // fn parse_id_set<T>(s: &str) -> Result<HashSet<T>>
 // where
@@ -30,7 +46,26 @@ use crate::Context;
     guild_only,
     install_context = "Guild",
     interaction_context = "Guild",
-    subcommands("edit", "starboard", "moderation", "raw", "reset"),
+    subcommands(
+        "edit",
+        "starboard",
+        "moderation",
+        "features",
+        "members",
+        "escalation",
+        "appeals",
+        "raid",
+        "spam",
+        "invites",
+        "attachments",
+        "retention",
+        "raw",
+        "reset",
+        "history",
+        "rollback",
+        "export",
+        "import"
+    ),
     subcommand_required,
     default_member_permissions = "MANAGE_GUILD"
 )]
@@ -169,6 +204,23 @@ async fn edit(ctx: Context<'_>) -> Result<()> {
                     .min_values(0)
                 )),
 
+                CreateComponent::TextDisplay(CreateTextDisplay::new(
+                    "**Server logs channel**\n-# Channel for server logs (e.g. channel, role, and permission changes)",
+                )),
+                CreateComponent::ActionRow(CreateActionRow::SelectMenu(
+                    CreateSelectMenu::new(
+                        "cfg:server_logs_channel",
+                        CreateSelectMenuKind::Channel {
+                            channel_types: Some(vec![ChannelType::Text].into()),
+                            default_channels: Some(
+                                data.server_logs_channel.map_or_else(Vec::new, |c| vec![c])
+                                .into(),
+                            ),
+                        },
+                    )
+                    .min_values(0)
+                )),
+
                 CreateComponent::TextDisplay(CreateTextDisplay::new(
                     "**Logs excluded channels**\n-# List of channels excluded from message logs",
                 )),
@@ -202,6 +254,22 @@ async fn edit(ctx: Context<'_>) -> Result<()> {
                     .min_values(0)
                 )),
 
+                CreateComponent::TextDisplay(CreateTextDisplay::new(
+                    "**Quarantine role**\n-# Role that replaces all other roles of quarantined members",
+                )),
+                CreateComponent::ActionRow(CreateActionRow::SelectMenu(
+                    CreateSelectMenu::new(
+                        "cfg:quarantine_role",
+                        CreateSelectMenuKind::Role {
+                            default_roles: Some(
+                                data.quarantine_role.map_or_else(Vec::new, |c| vec![c])
+                                .into(),
+                            ),
+                        },
+                    )
+                    .min_values(0)
+                )),
+
                 CreateComponent::TextDisplay(CreateTextDisplay::new(
                     "**Random color roles**\n-# List of roles to rotate colors for daily",
                 )),
@@ -217,6 +285,27 @@ async fn edit(ctx: Context<'_>) -> Result<()> {
                     .min_values(0)
                     .max_values(10)
                 )),
+
+                CreateComponent::TextDisplay(CreateTextDisplay::new(
+                    "**Disabled features**\n-# Passive features to disable throughout the guild, see `/config features` for per-channel overrides",
+                )),
+                CreateComponent::ActionRow(CreateActionRow::SelectMenu(
+                    CreateSelectMenu::new(
+                        "cfg:disabled_features",
+                        CreateSelectMenuKind::String {
+                            options: Feature::ALL
+                                .iter()
+                                .map(|f| {
+                                    CreateSelectMenuOption::new(f.name(), f.key())
+                                        .default_selection(data.features.disabled.contains(f))
+                                })
+                                .collect::<Vec<_>>()
+                                .into(),
+                        },
+                    )
+                    .min_values(0)
+                    .max_values(u8::try_from(Feature::ALL.len())?)
+                )),
             ]),
     )
     .await?;
@@ -276,7 +365,7 @@ async fn starboard(
         }
     }
 
-    storage.set_config(guild_id, &data).await?;
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
 
     ctx.send(
         CreateReply::default()
@@ -314,8 +403,11 @@ async fn moderation(
     #[description = "Extra message to add to bans"] extra_message_ban: Option<String>,
     #[description = "Extra message to add to kicks"] extra_message_kick: Option<String>,
     #[description = "Extra message to add to timeouts"] extra_message_timeout: Option<String>,
+    #[description = "How long warns stay active for (e.g. 90d)"] warn_expiry: Option<String>,
+    #[description = "Channel to invite users back to after temporary bans"]
+    unban_invite_channel: Option<GuildChannel>,
 
-    #[description = "Clear all extra messages"]
+    #[description = "Clear all of these settings"]
     #[flag]
     clear: bool,
 ) -> Result<()> {
@@ -337,6 +429,8 @@ async fn moderation(
         data.moderation_extra_message_ban = None;
         data.moderation_extra_message_kick = None;
         data.moderation_extra_message_timeout = None;
+        data.warn_expiry = None;
+        data.unban_invite_channel = None;
     } else {
         if let Some(message) = &extra_message_ban {
             data.moderation_extra_message_ban = Some(message.to_owned());
@@ -347,9 +441,15 @@ async fn moderation(
         if let Some(message) = &extra_message_timeout {
             data.moderation_extra_message_timeout = Some(message.to_owned());
         }
+        if let Some(warn_expiry) = &warn_expiry {
+            data.warn_expiry = Some(humantime::parse_duration(warn_expiry)?);
+        }
+        if let Some(channel) = &unban_invite_channel {
+            data.unban_invite_channel = Some(channel.id.widen());
+        }
     }
 
-    storage.set_config(guild_id, &data).await?;
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
 
     ctx.send(
         CreateReply::default()
@@ -372,7 +472,7 @@ async fn moderation(
     Ok(())
 }
 
-/// View the guild configuration in raw JSON
+/// Enable or disable passive features, optionally for a single channel
 #[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
 #[poise::command(
     slash_command,
@@ -381,7 +481,14 @@ async fn moderation(
     interaction_context = "Guild",
     default_member_permissions = "MANAGE_GUILD"
 )]
-async fn raw(ctx: Context<'_>) -> Result<()> {
+async fn features(
+    ctx: Context<'_>,
+
+    #[description = "Feature to configure"] feature: Feature,
+    #[description = "Whether the feature is enabled (leave empty to clear a channel override)"]
+    enabled: Option<bool>,
+    #[description = "Channel to override the guild setting in"] channel: Option<GuildChannel>,
+) -> Result<()> {
     ctx.defer_ephemeral().await?;
 
     let guild_id = ctx
@@ -394,7 +501,18 @@ async fn raw(ctx: Context<'_>) -> Result<()> {
         .as_ref()
         .ok_or_else(|| eyre!("storage is not available"))?;
 
-    let data = storage.get_config(guild_id).await?;
+    let mut data = storage.get_config(guild_id).await?;
+
+    if let Some(channel) = &channel {
+        data.features
+            .set_override(feature, channel.id.widen(), enabled);
+    } else if enabled.unwrap_or(true) {
+        data.features.disabled.remove(&feature);
+    } else {
+        data.features.disabled.insert(feature);
+    }
+
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
 
     ctx.send(
         CreateReply::default()
@@ -406,7 +524,7 @@ async fn raw(ctx: Context<'_>) -> Result<()> {
                     )),
                     CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
                         "```json\n{}\n```",
-                        serde_json::to_string_pretty(&data)?
+                        serde_json::to_string_pretty(&data.features)?
                     ))),
                 ])
                 .accent_color(0x63e6be),
@@ -417,7 +535,7 @@ async fn raw(ctx: Context<'_>) -> Result<()> {
     Ok(())
 }
 
-/// Reset the guild configuration to defaults
+/// Choose which member updates are posted to the member logs
 #[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
 #[poise::command(
     slash_command,
@@ -426,7 +544,91 @@ async fn raw(ctx: Context<'_>) -> Result<()> {
     interaction_context = "Guild",
     default_member_permissions = "MANAGE_GUILD"
 )]
-async fn reset(ctx: Context<'_>) -> Result<()> {
+async fn members(
+    ctx: Context<'_>,
+
+    #[description = "Kind of member update"] change: MemberChange,
+    #[description = "Whether updates of this kind are logged"] enabled: bool,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut data = storage.get_config(guild_id).await?;
+
+    if enabled {
+        data.member_logs_ignored.remove(&change);
+    } else {
+        data.member_logs_ignored.insert(change);
+    }
+
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
+
+    let mut ignored = data
+        .member_logs_ignored
+        .iter()
+        .map(|change| change.name())
+        .collect::<Vec<_>>();
+    ignored.sort_unstable();
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Member logs",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+                        "**Not logged**\n{}",
+                        if ignored.is_empty() {
+                            "*None*".to_owned()
+                        } else {
+                            ignored.join(", ")
+                        }
+                    ))),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[derive(poise::ChoiceParameter, PartialEq, Eq, Clone, Copy, Debug)]
+enum EscalationAction {
+    Timeout,
+    Kick,
+    Ban,
+}
+
+/// Configure actions applied automatically when a user reaches a number of warns
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn escalation(
+    ctx: Context<'_>,
+
+    #[description = "Number of warns that triggers the action"]
+    #[min = 1]
+    warns: Option<u64>,
+    #[description = "Action to apply (leave empty to remove)"] action: Option<EscalationAction>,
+    #[description = "Duration of timeouts"] duration: Option<String>,
+) -> Result<()> {
     ctx.defer_ephemeral().await?;
 
     let guild_id = ctx
@@ -439,15 +641,252 @@ async fn reset(ctx: Context<'_>) -> Result<()> {
         .as_ref()
         .ok_or_else(|| eyre!("storage is not available"))?;
 
-    storage.del_config(guild_id).await?;
+    let mut data = storage.get_config(guild_id).await?;
+
+    if let Some(warns) = warns {
+        let escalation = match action {
+            Some(EscalationAction::Timeout) => {
+                let Some(Ok(duration)) = duration.as_deref().map(humantime::parse_duration) else {
+                    bail!("a valid duration is required for timeouts");
+                };
+
+                Some(WarnEscalation::Timeout { duration })
+            }
+            Some(EscalationAction::Kick) => Some(WarnEscalation::Kick),
+            Some(EscalationAction::Ban) => Some(WarnEscalation::Ban),
+            None => None,
+        };
+
+        if let Some(escalation) = escalation {
+            data.warn_escalations.insert(warns, escalation);
+        } else {
+            data.warn_escalations.remove(&warns);
+        }
+
+        handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
+    }
+
+    let escalations = if data.warn_escalations.is_empty() {
+        "No escalations are configured".to_owned()
+    } else {
+        data.warn_escalations
+            .iter()
+            .map(|(warns, escalation)| format!("**{warns} warns** \u{00B7} {escalation}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
 
     ctx.send(
         CreateReply::default()
             .flags(MessageFlags::IS_COMPONENTS_V2)
             .components(&[CreateComponent::Container(
-                CreateContainer::new(&[CreateContainerComponent::TextDisplay(
-                    CreateTextDisplay::new("### Reset configuration"),
-                )])
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Warn escalations",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(escalations)),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Configure where ban appeals are sent and how often users can appeal
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn appeals(
+    ctx: Context<'_>,
+
+    #[description = "Channel to send ban appeals to"] channel: Option<GuildChannel>,
+    #[description = "Time between appeals of a user (default: 7d)"] cooldown: Option<String>,
+
+    #[description = "Stop accepting ban appeals"]
+    #[flag]
+    clear: bool,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut data = storage.get_config(guild_id).await?;
+
+    if clear {
+        data.appeals_channel = None;
+        data.appeal_cooldown = None;
+    } else {
+        if let Some(channel) = &channel {
+            data.appeals_channel = Some(channel.id.widen());
+        }
+        if let Some(cooldown) = &cooldown {
+            data.appeal_cooldown = Some(humantime::parse_duration(cooldown)?);
+        }
+    }
+
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
+
+    let status = data.appeals_channel.map_or_else(
+        || "Ban appeals are disabled".to_owned(),
+        |channel| {
+            format!(
+                "**Channel**\n{}\n**Cooldown**\n{}",
+                channel.mention(),
+                humantime::format_duration(
+                    data.appeal_cooldown.unwrap_or(appeal::DEFAULT_COOLDOWN)
+                )
+            )
+        },
+    );
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Ban appeals",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status)),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Configure raid detection and lockdowns
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn raid(
+    ctx: Context<'_>,
+
+    #[description = "Join score that starts a lockdown (default: 10)"]
+    #[min = 1]
+    threshold: Option<u64>,
+    #[description = "Time window joins are counted in (default: 1m)"] window: Option<String>,
+    #[description = "Minimum account age (default: 7d)"] account_age: Option<String>,
+    #[description = "Lockdown length after the last join (default: 30m)"] cooloff: Option<String>,
+    #[description = "Kick members joining during a lockdown"] kick_joins: Option<bool>,
+
+    #[description = "Disable raid detection"]
+    #[flag]
+    clear: bool,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut data = storage.get_config(guild_id).await?;
+
+    if clear {
+        data.raid_protection = None;
+    } else {
+        let mut protection = data.raid_protection.unwrap_or_default();
+
+        if let Some(threshold) = threshold {
+            protection.threshold = threshold;
+        }
+        if let Some(window) = &window {
+            protection.window = humantime::parse_duration(window)?;
+        }
+        if let Some(account_age) = &account_age {
+            protection.min_account_age =
+                Some(humantime::parse_duration(account_age)?).filter(|age| !age.is_zero());
+        }
+        if let Some(cooloff) = &cooloff {
+            let cooloff = humantime::parse_duration(cooloff)?;
+
+            if expires_at(cooloff).is_none() {
+                ctx.say("Invalid duration provided!").await?;
+                return Ok(());
+            }
+
+            protection.cooloff = cooloff;
+        }
+        if let Some(kick_joins) = kick_joins {
+            protection.kick_joins = kick_joins;
+        }
+
+        data.raid_protection = Some(protection);
+    }
+
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
+
+    let status = data.raid_protection.map_or_else(
+        || "Raid detection is disabled".to_owned(),
+        |protection| {
+            [
+                format!(
+                    "**Threshold**\n{} within {}",
+                    protection.threshold,
+                    humantime::format_duration(protection.window)
+                ),
+                format!(
+                    "**Minimum account age**\n{}",
+                    protection.min_account_age.map_or_else(
+                        || "None".to_owned(),
+                        |age| humantime::format_duration(age).to_string()
+                    )
+                ),
+                format!(
+                    "**Cool-off**\n{}",
+                    humantime::format_duration(protection.cooloff)
+                ),
+                format!(
+                    "**New joins during lockdown**\n{}",
+                    if protection.kick_joins {
+                        "Kicked"
+                    } else {
+                        "Allowed"
+                    }
+                ),
+            ]
+            .join("\n")
+        },
+    );
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Raid protection",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status)),
+                ])
                 .accent_color(0x63e6be),
             )]),
     )
@@ -455,3 +894,817 @@ async fn reset(ctx: Context<'_>) -> Result<()> {
 
     Ok(())
 }
+
+#[derive(poise::ChoiceParameter, PartialEq, Eq, Clone, Copy, Debug)]
+enum SpamLimit {
+    #[name = "Messages per window"]
+    Messages,
+    #[name = "Identical messages per window"]
+    Duplicates,
+    #[name = "Window"]
+    Window,
+    #[name = "Mentions per message"]
+    Mentions,
+    #[name = "Emoji per message"]
+    Emoji,
+    #[name = "Newlines per message"]
+    Newlines,
+    #[name = "Timeout"]
+    Timeout,
+}
+
+/// Configure spam detection limits
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn spam(
+    ctx: Context<'_>,
+
+    #[description = "Limit to change"] limit: Option<SpamLimit>,
+    #[description = "New value of the limit (a number, or a duration for windows and timeouts)"]
+    value: Option<String>,
+
+    #[description = "Disable spam detection"]
+    #[flag]
+    clear: bool,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut data = storage.get_config(guild_id).await?;
+
+    if clear {
+        data.spam_protection = None;
+    } else {
+        let mut protection = data.spam_protection.unwrap_or_default();
+
+        if let Some(limit) = limit {
+            let value = value.ok_or_else(|| eyre!("a value is required to change a limit"))?;
+
+            match limit {
+                SpamLimit::Messages => protection.max_messages = value.parse()?,
+                SpamLimit::Duplicates => protection.max_duplicates = value.parse()?,
+                SpamLimit::Window => protection.window = humantime::parse_duration(&value)?,
+                SpamLimit::Mentions => protection.max_mentions = value.parse()?,
+                SpamLimit::Emoji => protection.max_emoji = value.parse()?,
+                SpamLimit::Newlines => protection.max_newlines = value.parse()?,
+                SpamLimit::Timeout => {
+                    let timeout = humantime::parse_duration(&value)?;
+
+                    if timeout > config::MAX_TIMEOUT {
+                        ctx.say("Timeouts can be at most 28 days long!").await?;
+                        return Ok(());
+                    }
+
+                    protection.timeout = timeout;
+                }
+            }
+        }
+
+        data.spam_protection = Some(protection);
+    }
+
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
+
+    let status = data.spam_protection.map_or_else(
+        || "Spam detection is disabled".to_owned(),
+        |protection| {
+            [
+                format!(
+                    "**Messages**\n{} ({} identical) within {}",
+                    protection.max_messages,
+                    protection.max_duplicates,
+                    humantime::format_duration(protection.window)
+                ),
+                format!(
+                    "**Per message**\n{} mentions, {} emoji, {} newlines",
+                    protection.max_mentions, protection.max_emoji, protection.max_newlines
+                ),
+                format!(
+                    "**Timeout**\n{}",
+                    humantime::format_duration(protection.timeout)
+                ),
+            ]
+            .join("\n")
+        },
+    );
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Spam detection",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status)),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Resolve a server from an invite link or a guild ID.
+async fn parse_server(ctx: Context<'_>, server: &str) -> Result<GuildId> {
+    if let Some(code) = handlers::invite_filter::invite_codes(server).first() {
+        return handlers::invite_filter::resolve(ctx.http(), code)
+            .await?
+            .map(|target| target.guild)
+            .ok_or_else(|| eyre!("invite `{code}` is invalid or expired"));
+    }
+
+    Ok(server.trim().parse()?)
+}
+
+/// Configure which servers members may post invites to
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn invites(
+    ctx: Context<'_>,
+
+    #[description = "Server to allow invites to (invite link or server ID)"] allow: Option<String>,
+    #[description = "Server to stop allowing invites to (invite link or server ID)"]
+    disallow: Option<String>,
+    #[description = "Let the moderator role post any invite"] moderator_bypass: Option<bool>,
+
+    #[description = "Disable the invite filter"]
+    #[flag]
+    clear: bool,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut data = storage.get_config(guild_id).await?;
+
+    if clear {
+        data.invite_filter = None;
+    } else {
+        let mut filter = data.invite_filter.unwrap_or_default();
+
+        if let Some(allow) = &allow {
+            filter
+                .allowed_guilds
+                .insert(parse_server(ctx, allow).await?);
+        }
+        if let Some(disallow) = &disallow {
+            filter
+                .allowed_guilds
+                .remove(&parse_server(ctx, disallow).await?);
+        }
+        if let Some(moderator_bypass) = moderator_bypass {
+            filter.moderator_bypass = moderator_bypass;
+        }
+
+        data.invite_filter = Some(filter);
+    }
+
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
+
+    let status = data.invite_filter.as_ref().map_or_else(
+        || "The invite filter is disabled".to_owned(),
+        |filter| {
+            let mut allowed = filter
+                .allowed_guilds
+                .iter()
+                .map(|guild| format!("`{guild}`"))
+                .collect::<Vec<_>>();
+            allowed.sort();
+
+            format!(
+                "**Allowed servers**\n{}\n**Moderator bypass**\n{}",
+                if allowed.is_empty() {
+                    "*None*".to_owned()
+                } else {
+                    allowed.join(", ")
+                },
+                if filter.moderator_bypass { "Yes" } else { "No" }
+            )
+        },
+    );
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Invite filter",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status)),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Configure re-uploading attachments of deleted messages to the message logs
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn attachments(
+    ctx: Context<'_>,
+
+    #[description = "Largest attachment to re-upload (default: 8MiB)"] max_size: Option<String>,
+    #[description = "Content type to re-upload, e.g. image/*"] allow: Option<String>,
+    #[description = "Content type to stop re-uploading"] disallow: Option<String>,
+
+    #[description = "Disable re-uploading attachments"]
+    #[flag]
+    clear: bool,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let mut data = storage.get_config(guild_id).await?;
+
+    if clear {
+        data.attachment_logs = None;
+    } else {
+        let mut logs = data.attachment_logs.unwrap_or_default();
+
+        if let Some(max_size) = &max_size {
+            logs.max_size = max_size
+                .trim()
+                .parse::<bytesize::ByteSize>()
+                .map_err(|err| eyre!("invalid size `{max_size}`: {err}"))?
+                .as_u64();
+        }
+        if let Some(allow) = &allow {
+            logs.content_types.insert(allow.trim().to_ascii_lowercase());
+        }
+        if let Some(disallow) = &disallow {
+            logs.content_types
+                .remove(&disallow.trim().to_ascii_lowercase());
+        }
+
+        data.attachment_logs = Some(logs);
+    }
+
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
+
+    let status = data.attachment_logs.as_ref().map_or_else(
+        || "Re-uploading attachments is disabled".to_owned(),
+        |logs| {
+            format!(
+                "**Maximum size**\n{}\n**Content types**\n{}",
+                utils::format_bytes(logs.max_size),
+                if logs.content_types.is_empty() {
+                    "*None*".to_owned()
+                } else {
+                    logs.content_types
+                        .iter()
+                        .map(|ct| format!("`{ct}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }
+            )
+        },
+    );
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Attachment logs",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(status)),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Configure how long messages are kept for message logs and `/logs search`
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn retention(
+    ctx: Context<'_>,
+
+    #[description = "How long messages are kept (e.g. 7d)"] duration: Option<String>,
+
+    #[description = "Reset to the default retention"]
+    #[flag]
+    clear: bool,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let limit = CONFIG.get().message_log_retention_limit();
+    let mut data = storage.get_config(guild_id).await?;
+
+    if clear {
+        data.message_log_retention = None;
+    } else if let Some(duration) = &duration {
+        let Ok(duration) = humantime::parse_duration(duration) else {
+            ctx.say("Invalid duration provided!").await?;
+            return Ok(());
+        };
+
+        if duration > limit {
+            ctx.say(format!(
+                "Message logs can be kept for at most {}!",
+                humantime::format_duration(limit)
+            ))
+            .await?;
+            return Ok(());
+        }
+
+        data.message_log_retention = Some(duration);
+    }
+
+    handlers::config::save(ctx.http(), &**storage, guild_id, ctx.author().id, &data).await?;
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Message log retention",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+                        "**Retention**\n{}\n**Maximum**\n{}\n-# Only applies to messages sent after the change.",
+                        humantime::format_duration(data.message_log_ttl(limit)),
+                        humantime::format_duration(limit),
+                    ))),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// View the guild configuration in raw JSON
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn raw(ctx: Context<'_>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let data = storage.get_config(guild_id).await?;
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                        "### Configuration",
+                    )),
+                    CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+                        "```json\n{}\n```",
+                        serde_json::to_string_pretty(&data)?
+                    ))),
+                ])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Reset the guild configuration to defaults
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn reset(ctx: Context<'_>) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    handlers::config::save(
+        ctx.http(),
+        &**storage,
+        guild_id,
+        ctx.author().id,
+        &GuildConfig::default(),
+    )
+    .await?;
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[CreateContainerComponent::TextDisplay(
+                    CreateTextDisplay::new("### Reset configuration"),
+                )])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Browse the history of guild configuration changes
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn history(
+    ctx: Context<'_>,
+    #[description = "Version to view the full changes of"] version: Option<u64>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let history = storage.get_config_history(guild_id).await?;
+
+    let content = if let Some(version) = version {
+        let entry = history
+            .iter()
+            .find(|entry| entry.version == version)
+            .ok_or_else(|| eyre!("configuration version {version} not found"))?;
+
+        format!(
+            "### Configuration version {}\n{}\n-# {} \u{00B7} {}",
+            entry.version,
+            handlers::config::format_changes(&entry.changes, 3000),
+            entry.actor.mention(),
+            FormattedTimestamp::new(entry.timestamp.into(), None),
+        )
+    } else if history.is_empty() {
+        "### Configuration history\nNo configuration changes have been recorded".to_owned()
+    } else {
+        let entries = history
+            .iter()
+            .rev()
+            .take(10)
+            .map(|entry| {
+                format!(
+                    "**Version {}** \u{00B7} {} \u{00B7} {}\n-# {}",
+                    entry.version,
+                    entry.actor.mention(),
+                    FormattedTimestamp::new(entry.timestamp.into(), None),
+                    entry
+                        .changes
+                        .iter()
+                        .map(|change| format!("`{}`", change.field))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!("### Configuration history\n{entries}")
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .allowed_mentions(CreateAllowedMentions::new())
+            .components(&[CreateComponent::Container(
+                CreateContainer::new(&[CreateContainerComponent::TextDisplay(
+                    CreateTextDisplay::new(content),
+                )])
+                .accent_color(0x63e6be),
+            )]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Roll back the guild configuration to an earlier version
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn rollback(
+    ctx: Context<'_>,
+    #[description = "Version to roll back to"] version: u64,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let entry = storage
+        .get_config_history(guild_id)
+        .await?
+        .into_iter()
+        .find(|entry| entry.version == version)
+        .ok_or_else(|| eyre!("configuration version {version} not found"))?;
+
+    let changes = storage.get_config(guild_id).await?.diff(&entry.config)?;
+
+    let (confirmed, reply) = utils::serenity::interaction_confirm(
+        &ctx,
+        CreateContainer::new(vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!(
+                "### Roll back configuration\nAre you sure you want to roll back to version {version}?\n{}",
+                handlers::config::format_changes(&changes, 3000),
+            )),
+        )])
+        .accent_color(0xffd43b),
+    )
+    .await?;
+
+    let container = if confirmed {
+        let new_entry = handlers::config::save(
+            ctx.http(),
+            &**storage,
+            guild_id,
+            ctx.author().id,
+            &entry.config,
+        )
+        .await?;
+
+        CreateContainer::new(vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(new_entry.map_or_else(
+                || format!("### Rolled back configuration\nConfiguration already matches version {version}"),
+                |new_entry| {
+                    format!(
+                        "### Rolled back configuration\nRestored version {version} as version {}",
+                        new_entry.version
+                    )
+                },
+            )),
+        )])
+        .accent_color(0x4ade80)
+    } else {
+        CreateContainer::new(vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new("### Rollback cancelled"),
+        )])
+        .accent_color(0xff6b6b)
+    };
+
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .flags(MessageFlags::IS_COMPONENTS_V2)
+                .components(vec![CreateComponent::Container(container)]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Export all guild data as an archive
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn export(
+    ctx: Context<'_>,
+    #[description = "Format of the archive (default: JSON)"] format: Option<ArchiveFormat>,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let format = format.unwrap_or(ArchiveFormat::Json);
+    let archive = GuildArchive::collect(&**storage, guild_id).await?;
+
+    ctx.send(CreateReply::default().attachment(CreateAttachment::bytes(
+        archive.serialize(format)?.into_bytes(),
+        format!("valfisk-{guild_id}.{}", format.extension()),
+    )))
+    .await?;
+
+    Ok(())
+}
+
+/// Import guild data from an archive
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    install_context = "Guild",
+    interaction_context = "Guild",
+    default_member_permissions = "MANAGE_GUILD"
+)]
+async fn import(
+    ctx: Context<'_>,
+    #[description = "Archive created by /config export"] archive: Attachment,
+) -> Result<()> {
+    ctx.defer_ephemeral().await?;
+
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let data = ctx.data();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let Some(format) = ArchiveFormat::from_filename(&archive.filename) else {
+        bail!("archive must be a `.json` or `.toml` file");
+    };
+
+    if archive.size > MAX_ARCHIVE_SIZE {
+        bail!(
+            "archive is too large ({} > {})",
+            utils::format_bytes(archive.size.into()),
+            utils::format_bytes(MAX_ARCHIVE_SIZE.into())
+        );
+    }
+
+    let contents = HTTP
+        .get(archive.url.as_str())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    let archive = GuildArchive::parse(&contents, format)?;
+    let diff = archive.diff(&**storage, guild_id).await?;
+
+    let (confirmed, reply) = utils::serenity::interaction_confirm(
+        &ctx,
+        CreateContainer::new(vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new(format!(
+                "### Import guild data\n**Source guild**: `{}`\n**Exported**: {}\n{}\n**Autoreply entries**: {} \u{2192} {}\n**Warns**: {} \u{2192} {}\n**Cases**: {} \u{2192} {}\n**Automod rules**: {} \u{2192} {}\n**Notes**: {} \u{2192} {}\n**Temporary bans**: {} \u{2192} {}\n**Quarantines**: {} \u{2192} {}\n**Ban appeals**: {} \u{2192} {}\n-# Existing autoreply entries, warns, cases, automod rules, notes, temporary bans, quarantines, and ban appeals will be replaced.",
+                archive.guild,
+                FormattedTimestamp::new(archive.exported_at.into(), None),
+                handlers::config::format_changes(&diff.config, 3000),
+                diff.autoreply.0,
+                diff.autoreply.1,
+                diff.warns.0,
+                diff.warns.1,
+                diff.cases.0,
+                diff.cases.1,
+                diff.automod_rules.0,
+                diff.automod_rules.1,
+                diff.notes.0,
+                diff.notes.1,
+                diff.temp_bans.0,
+                diff.temp_bans.1,
+                diff.quarantines.0,
+                diff.quarantines.1,
+                diff.appeals.0,
+                diff.appeals.1,
+            )),
+        )])
+        .accent_color(0xffd43b),
+    )
+    .await?;
+
+    let container = if confirmed {
+        if let Some(entry) = archive.apply(&**storage, guild_id, ctx.author().id).await? {
+            handlers::config::log_change(ctx.http(), &**storage, guild_id, &entry).await?;
+        }
+
+        CreateContainer::new(vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new("### Imported guild data"),
+        )])
+        .accent_color(0x4ade80)
+    } else {
+        CreateContainer::new(vec![CreateContainerComponent::TextDisplay(
+            CreateTextDisplay::new("### Import cancelled"),
+        )])
+        .accent_color(0xff6b6b)
+    };
+
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .flags(MessageFlags::IS_COMPONENTS_V2)
+                .components(vec![CreateComponent::Container(container)]),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -4,6 +4,7 @@
 
 pub mod config;
 pub mod ping;
+pub mod privacy;
 pub mod rotate_color_roles;
 pub mod template_channel;
 pub mod version;
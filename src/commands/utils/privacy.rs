@@ -0,0 +1,61 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::{
+    CreateReply,
+    serenity_prelude::{
+        CreateComponent, CreateContainer, CreateContainerComponent, CreateTextDisplay, MessageFlags,
+    },
+};
+
+use crate::{
+    Context,
+    config::{CONFIG, GuildConfig},
+};
+
+/// Get information on how Valfisk handles your data
+#[tracing::instrument(skip(ctx), fields(ctx.channel = ctx.channel_id().get(), ctx.author = ctx.author().id.get()))]
+#[poise::command(
+    slash_command,
+    install_context = "Guild | User",
+    interaction_context = "Guild | BotDm | PrivateChannel"
+)]
+pub async fn privacy(ctx: Context<'_>) -> Result<()> {
+    let limit = CONFIG.get().message_log_retention_limit();
+
+    let retention = if let Some(guild_id) = ctx.guild_id()
+        && let Some(storage) = &ctx.data().storage
+    {
+        let guild_config = storage.get_config(guild_id).await?;
+
+        format!(
+            "Messages sent in this server have their content, author, channel, and attachments stored for **{}** before they are deleted.",
+            humantime::format_duration(guild_config.message_log_ttl(limit))
+        )
+    } else {
+        format!(
+            "Messages sent in servers have their content, author, channel, and attachments stored for **{}** by default before they are deleted. Servers can configure a period of up to **{}**.",
+            humantime::format_duration(GuildConfig::default().message_log_ttl(limit)),
+            humantime::format_duration(limit)
+        )
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .flags(MessageFlags::IS_COMPONENTS_V2)
+            .components(&[CreateComponent::Container(CreateContainer::new(&[
+                CreateContainerComponent::TextDisplay(CreateTextDisplay::new("## Privacy")),
+                CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
+                    "{retention} While stored, messages can be searched by the server's moderators, and edits and deletes are logged to a channel configured by the server."
+                ))),
+                CreateContainerComponent::TextDisplay(CreateTextDisplay::new(
+                    "-# Read the full privacy policy at https://github.com/ryanccn/valfisk/blob/main/PRIVACY.md",
+                )),
+            ]))]),
+    )
+    .await?;
+
+    Ok(())
+}
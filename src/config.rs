@@ -2,15 +2,36 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
+use arc_swap::ArcSwap;
+use eyre::{Result, bail};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, sync::LazyLock};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt, fs,
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+    time::{Duration, SystemTime},
+};
 
 use poise::serenity_prelude::{GenericChannelId, GuildId, RoleId, UserId};
 
-#[derive(Deserialize, Debug)]
+/// How long messages are kept for logs when neither the guild nor the operator configure it.
+pub const DEFAULT_MESSAGE_LOG_RETENTION: Duration = Duration::from_hours(24);
+
+/// The longest that Discord allows members to be timed out for.
+pub const MAX_TIMEOUT: Duration = Duration::from_hours(28 * 24);
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
 pub struct EnvConfig {
     pub discord_token: String,
     pub redis_url: Option<String>,
+    pub sqlite_path: Option<String>,
+    pub attachment_cache_path: Option<String>,
+    /// The longest that guilds can choose to keep message logs for.
+    #[serde(with = "humantime_duration::option", default)]
+    pub max_message_log_retention: Option<Duration>,
+    #[serde(default)]
+    pub migrations_dry_run: bool,
 
     pub allowed_guilds: Option<HashSet<GuildId>>,
 
@@ -53,9 +74,24 @@ pub struct GuildConfig {
     pub message_logs_channel: Option<GenericChannelId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub member_logs_channel: Option<GenericChannelId>,
+    /// Member updates that are not posted to the member logs.
+    #[serde(skip_serializing_if = "HashSet::is_empty", default)]
+    pub member_logs_ignored: HashSet<MemberChange>,
+    /// Channel for changes to channels, roles, emojis, stickers, and guild settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_logs_channel: Option<GenericChannelId>,
+    /// How long messages are kept for edit and delete logs, capped by the operator maximum.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "humantime_duration::option",
+        default
+    )]
+    pub message_log_retention: Option<Duration>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub moderator_role: Option<RoleId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quarantine_role: Option<RoleId>,
     #[serde(skip_serializing_if = "HashSet::is_empty", default)]
     pub logs_excluded_channels: HashSet<GenericChannelId>,
 
@@ -68,7 +104,738 @@ pub struct GuildConfig {
     pub moderation_extra_message_kick: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub moderation_extra_message_timeout: Option<String>,
+    /// Channel to invite users back to when their temporary ban expires.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unban_invite_channel: Option<GenericChannelId>,
+
+    /// Channel that ban appeals are sent to; users cannot appeal if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appeals_channel: Option<GenericChannelId>,
+    /// How long users have to wait between ban appeals.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "humantime_duration::option",
+        default
+    )]
+    pub appeal_cooldown: Option<Duration>,
+
+    /// Join-rate raid detection; disabled if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raid_protection: Option<RaidProtection>,
+    /// Message spam detection; disabled if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spam_protection: Option<SpamProtection>,
+    /// Discord invite filtering; disabled if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_filter: Option<InviteFilter>,
+    /// Re-uploading attachments of deleted messages to the message logs; disabled if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment_logs: Option<AttachmentLogs>,
+
+    /// Actions applied automatically when a user reaches a number of active warns.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub warn_escalations: BTreeMap<u64, WarnEscalation>,
+    /// How long warns stay active for; warns never expire if unset.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "humantime_duration::option",
+        default
+    )]
+    pub warn_expiry: Option<Duration>,
+
+    #[serde(skip_serializing_if = "GuildFeatures::is_empty", default)]
+    pub features: GuildFeatures,
+}
+
+/// A passive subsystem that can be toggled per guild and per channel.
+#[derive(
+    Serialize, Deserialize, poise::ChoiceParameter, Debug, Clone, Copy, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    #[name = "Code expansion"]
+    CodeExpansion,
+    #[name = "Safe Browsing"]
+    SafeBrowsing,
+    #[name = "Autoreply"]
+    Autoreply,
+    #[name = "Intelligence"]
+    Intelligence,
+    #[name = "Starboard"]
+    Starboard,
+    #[name = "Message logs"]
+    MessageLogs,
+    #[name = "Spam detection"]
+    SpamDetection,
+    #[name = "Invite filter"]
+    InviteFilter,
+    #[name = "Automod"]
+    Automod,
+}
+
+impl Feature {
+    pub const ALL: [Self; 9] = [
+        Self::CodeExpansion,
+        Self::SafeBrowsing,
+        Self::Autoreply,
+        Self::Intelligence,
+        Self::Starboard,
+        Self::MessageLogs,
+        Self::SpamDetection,
+        Self::InviteFilter,
+        Self::Automod,
+    ];
+
+    /// The key used for this feature in serialized configs and component IDs.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::CodeExpansion => "code_expansion",
+            Self::SafeBrowsing => "safe_browsing",
+            Self::Autoreply => "autoreply",
+            Self::Intelligence => "intelligence",
+            Self::Starboard => "starboard",
+            Self::MessageLogs => "message_logs",
+            Self::SpamDetection => "spam_detection",
+            Self::InviteFilter => "invite_filter",
+            Self::Automod => "automod",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.key() == key)
+    }
+}
+
+/// A kind of member update that can be posted to the member logs.
+#[derive(
+    Serialize, Deserialize, poise::ChoiceParameter, Debug, Clone, Copy, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberChange {
+    #[name = "Nickname"]
+    Nickname,
+    #[name = "Roles"]
+    Roles,
+    #[name = "Server avatar"]
+    Avatar,
+    #[name = "Timeout"]
+    Timeout,
+    #[name = "Membership screening"]
+    Pending,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct GuildFeatures {
+    /// Features that are disabled throughout the guild.
+    #[serde(skip_serializing_if = "HashSet::is_empty", default)]
+    pub disabled: HashSet<Feature>,
+    /// Per-channel overrides that take precedence over `disabled`.
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub channels: HashMap<GenericChannelId, HashMap<Feature, bool>>,
+}
+
+impl GuildFeatures {
+    pub fn is_empty(&self) -> bool {
+        self.disabled.is_empty() && self.channels.is_empty()
+    }
+
+    pub fn enabled(&self, feature: Feature, channel: GenericChannelId) -> bool {
+        self.channels
+            .get(&channel)
+            .and_then(|overrides| overrides.get(&feature))
+            .copied()
+            .unwrap_or_else(|| !self.disabled.contains(&feature))
+    }
+
+    /// Set or clear (with `None`) a per-channel override, dropping channels that no longer have any.
+    pub fn set_override(
+        &mut self,
+        feature: Feature,
+        channel: GenericChannelId,
+        value: Option<bool>,
+    ) {
+        let overrides = self.channels.entry(channel).or_default();
+
+        if let Some(value) = value {
+            overrides.insert(feature, value);
+        } else {
+            overrides.remove(&feature);
+        }
+
+        if overrides.is_empty() {
+            self.channels.remove(&channel);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+pub enum WarnEscalation {
+    Timeout {
+        #[serde(with = "humantime_duration")]
+        duration: Duration,
+    },
+    Kick,
+    Ban,
+}
+
+impl fmt::Display for WarnEscalation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout { duration } => {
+                write!(f, "Timeout ({})", humantime::format_duration(*duration))
+            }
+            Self::Kick => f.write_str("Kick"),
+            Self::Ban => f.write_str("Ban"),
+        }
+    }
+}
+
+/// Thresholds for detecting raids from the rate of member joins.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RaidProtection {
+    /// Join score within `window` that starts a lockdown. Every join scores 1, with an extra
+    /// point each for accounts younger than `min_account_age` and accounts without an avatar.
+    pub threshold: u64,
+    #[serde(with = "humantime_duration")]
+    pub window: Duration,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "humantime_duration::option",
+        default
+    )]
+    pub min_account_age: Option<Duration>,
+    /// How long a lockdown lasts after the last join during it.
+    #[serde(with = "humantime_duration")]
+    pub cooloff: Duration,
+    /// Whether to kick members joining during a lockdown.
+    #[serde(default)]
+    pub kick_joins: bool,
+}
+
+impl RaidProtection {
+    /// When a lockdown extended at `from` ends, or `None` if the cool-off is out of range.
+    pub fn lockdown_end(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::TimeDelta::from_std(self.cooloff)
+            .ok()
+            .and_then(|cooloff| from.checked_add_signed(cooloff))
+    }
+}
+
+impl Default for RaidProtection {
+    fn default() -> Self {
+        Self {
+            threshold: 10,
+            window: Duration::from_mins(1),
+            min_account_age: Some(Duration::from_hours(24 * 7)),
+            cooloff: Duration::from_mins(30),
+            kick_joins: false,
+        }
+    }
+}
+
+/// Per-member limits for detecting message spam. Messages exceeding any limit are spam.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SpamProtection {
+    /// Messages a member can send within `window`.
+    pub max_messages: u64,
+    /// Identical messages a member can send within `window`, across all channels.
+    pub max_duplicates: u64,
+    #[serde(with = "humantime_duration")]
+    pub window: Duration,
+    /// User, role, and `@everyone` mentions in a single message.
+    pub max_mentions: u64,
+    /// Unicode and custom emoji in a single message.
+    pub max_emoji: u64,
+    pub max_newlines: u64,
+    /// How long to time out members for spamming.
+    #[serde(with = "humantime_duration")]
+    pub timeout: Duration,
+}
+
+impl Default for SpamProtection {
+    fn default() -> Self {
+        Self {
+            max_messages: 8,
+            max_duplicates: 2,
+            window: Duration::from_secs(10),
+            max_mentions: 8,
+            max_emoji: 20,
+            max_newlines: 30,
+            timeout: Duration::from_hours(1),
+        }
+    }
+}
+
+/// Servers that members may post invites to. Invites to the guild itself are always allowed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct InviteFilter {
+    #[serde(skip_serializing_if = "HashSet::is_empty", default)]
+    pub allowed_guilds: HashSet<GuildId>,
+    /// Whether members with the moderator role can post any invite.
+    #[serde(default)]
+    pub moderator_bypass: bool,
+}
+
+/// Which attachments of deleted messages are fetched and re-uploaded to the message logs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AttachmentLogs {
+    /// The largest attachment that is fetched, in bytes.
+    pub max_size: u64,
+    /// Content types that are fetched, either exact (`image/png`) or whole types (`image/*`).
+    pub content_types: BTreeSet<String>,
+}
+
+impl AttachmentLogs {
+    pub fn allows(&self, content_type: Option<&str>, size: u64) -> bool {
+        let Some(content_type) = content_type else {
+            return false;
+        };
+
+        // Ignore parameters such as `; charset=utf-8`
+        let content_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        size <= self.max_size
+            && self.content_types.iter().any(|allowed| {
+                allowed.strip_suffix('*').map_or_else(
+                    || *allowed == content_type,
+                    |prefix| content_type.starts_with(prefix),
+                )
+            })
+    }
+}
+
+impl Default for AttachmentLogs {
+    fn default() -> Self {
+        Self {
+            max_size: 8 * 1024 * 1024,
+            content_types: ["image/*".to_owned(), "video/*".to_owned()].into(),
+        }
+    }
+}
+
+/// (De)serialize durations as human-readable strings like `1h 30m`.
+pub mod humantime_duration {
+    use serde::{Deserialize as _, Deserializer, Serializer, de::Error as _};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&humantime::format_duration(*value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        humantime::parse_duration(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+
+    pub mod option {
+        use serde::{Deserialize as _, Deserializer, Serializer, de::Error as _};
+        use std::time::Duration;
+
+        #[expect(clippy::ref_option)]
+        pub fn serialize<S: Serializer>(
+            value: &Option<Duration>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(value) => super::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Duration>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|value| humantime::parse_duration(&value).map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+}
+
+/// A change to a single top-level field of a [`GuildConfig`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChange {
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub old: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub new: Option<serde_json::Value>,
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(old) = &self.old {
+            writeln!(f, "- {}: {old}", self.field)?;
+        }
+        if let Some(new) = &self.new {
+            writeln!(f, "+ {}: {new}", self.field)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Sort arrays so that fields backed by unordered sets compare equal regardless of iteration order.
+fn normalize(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Array(values) => {
+            let mut values = values.into_iter().map(normalize).collect::<Vec<_>>();
+            values.sort_by_cached_key(ToString::to_string);
+            serde_json::Value::Array(values)
+        }
+        value => value,
+    }
+}
+
+impl GuildConfig {
+    /// How long new message logs should be kept for, within the operator maximum `limit`.
+    pub fn message_log_ttl(&self, limit: Duration) -> Duration {
+        self.message_log_retention
+            .unwrap_or(DEFAULT_MESSAGE_LOG_RETENTION)
+            .min(limit)
+    }
+
+    /// Compute the field-level changes needed to go from `self` to `new`.
+    pub fn diff(&self, new: &Self) -> serde_json::Result<Vec<ConfigChange>> {
+        let serde_json::Value::Object(mut old) = serde_json::to_value(self)? else {
+            return Ok(Vec::new());
+        };
+        let serde_json::Value::Object(mut new) = serde_json::to_value(new)? else {
+            return Ok(Vec::new());
+        };
+
+        let fields = old
+            .keys()
+            .chain(new.keys())
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        Ok(fields
+            .into_iter()
+            .filter_map(|field| {
+                let old = old.remove(&field).map(normalize);
+                let new = new.remove(&field).map(normalize);
+
+                (old != new).then_some(ConfigChange { field, old, new })
+            })
+            .collect())
+    }
 }
 
-pub static CONFIG: LazyLock<EnvConfig> =
-    LazyLock::new(|| envy::from_env().expect("could not parse config from environment"));
+/// Path to an optional TOML file that is layered under the environment.
+fn config_file() -> Option<PathBuf> {
+    std::env::var_os("VALFISK_CONFIG").map(PathBuf::from)
+}
+
+/// Flatten a TOML config file into environment-style key-value pairs, so that
+/// values from the file are parsed exactly like those from the environment.
+fn file_vars(source: &str) -> Result<Vec<(String, String)>> {
+    fn scalar(key: &str, value: &toml::Value) -> Result<String> {
+        Ok(match value {
+            toml::Value::String(s) => s.clone(),
+            toml::Value::Integer(i) => i.to_string(),
+            toml::Value::Float(f) => f.to_string(),
+            toml::Value::Boolean(b) => b.to_string(),
+            _ => bail!("unsupported value for `{key}` in config file"),
+        })
+    }
+
+    source
+        .parse::<toml::Table>()?
+        .iter()
+        .map(|(key, value)| {
+            let value = if let toml::Value::Array(values) = value {
+                values
+                    .iter()
+                    .map(|v| scalar(key, v))
+                    .collect::<Result<Vec<_>>>()?
+                    .join(",")
+            } else {
+                scalar(key, value)?
+            };
+
+            Ok((key.to_lowercase(), value))
+        })
+        .collect()
+}
+
+impl EnvConfig {
+    /// Load the config from the environment, layered over the config file if there is one.
+    pub fn load() -> Result<Self> {
+        let mut vars = HashMap::new();
+
+        if let Some(path) = config_file() {
+            vars.extend(file_vars(&fs::read_to_string(path)?)?);
+        }
+
+        vars.extend(std::env::vars().map(|(key, value)| (key.to_lowercase(), value)));
+
+        Ok(envy::from_iter(vars)?)
+    }
+
+    /// The longest that guilds can choose to keep message logs for.
+    pub fn message_log_retention_limit(&self) -> Duration {
+        self.max_message_log_retention
+            .unwrap_or(DEFAULT_MESSAGE_LOG_RETENTION)
+    }
+
+    /// Fields that are only read during startup and need a restart to change.
+    fn restart_required(&self, new: &Self) -> Vec<&'static str> {
+        [
+            ("discord_token", self.discord_token != new.discord_token),
+            ("redis_url", self.redis_url != new.redis_url),
+            ("sqlite_path", self.sqlite_path != new.sqlite_path),
+            (
+                "migrations_dry_run",
+                self.migrations_dry_run != new.migrations_dry_run,
+            ),
+            ("admin_guild_id", self.admin_guild_id != new.admin_guild_id),
+            (
+                "safe_browsing_api_key",
+                self.safe_browsing_api_key != new.safe_browsing_api_key,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+
+    /// Replace secrets contained in the config with a placeholder.
+    pub fn redact(&self, s: &str) -> String {
+        let mut s = s.replace(&self.discord_token, "<redacted>");
+
+        for secret in [
+            &self.redis_url,
+            &self.pagespeed_api_key,
+            &self.safe_browsing_api_key,
+            &self.anthropic_api_key,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            s = s.replace(secret, "<redacted>");
+        }
+
+        s
+    }
+}
+
+/// The process-wide config, which can be atomically swapped out on reload.
+#[derive(Debug)]
+pub struct SharedConfig(ArcSwap<EnvConfig>);
+
+impl SharedConfig {
+    pub fn get(&self) -> Arc<EnvConfig> {
+        self.0.load_full()
+    }
+
+    /// Reload the config, keeping the current config if the new one is invalid.
+    /// Returns whether the config changed.
+    pub fn reload(&self) -> Result<bool> {
+        let new = EnvConfig::load()?;
+        let old = self.0.load();
+
+        if **old == new {
+            return Ok(false);
+        }
+
+        let restart_required = old.restart_required(&new);
+        if !restart_required.is_empty() {
+            tracing::warn!(
+                ?restart_required,
+                "some changed config fields will only take effect after a restart"
+            );
+        }
+
+        self.0.store(Arc::new(new));
+        Ok(true)
+    }
+}
+
+/// The modification time of the config file, used to detect changes.
+pub fn config_file_modified() -> Option<SystemTime> {
+    fs::metadata(config_file()?).ok()?.modified().ok()
+}
+
+pub static CONFIG: LazyLock<SharedConfig> = LazyLock::new(|| {
+    SharedConfig(ArcSwap::from_pointee(
+        EnvConfig::load().expect("could not load config"),
+    ))
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports_changed_fields() -> serde_json::Result<()> {
+        let old = GuildConfig {
+            starboard_threshold: Some(3),
+            moderator_role: Some(RoleId::new(1)),
+            random_color_roles: [RoleId::new(1), RoleId::new(2), RoleId::new(3)].into(),
+            ..Default::default()
+        };
+
+        let new = GuildConfig {
+            starboard_threshold: Some(5),
+            starboard_emojis: Some("*".to_owned()),
+            random_color_roles: [RoleId::new(3), RoleId::new(2), RoleId::new(1)].into(),
+            ..Default::default()
+        };
+
+        let changes = old.diff(&new)?;
+
+        assert_eq!(
+            changes.iter().map(|c| c.field.as_str()).collect::<Vec<_>>(),
+            vec!["moderator_role", "starboard_emojis", "starboard_threshold"]
+        );
+        assert!(changes[0].new.is_none());
+        assert!(changes[1].old.is_none());
+        assert_eq!(
+            changes[2].to_string(),
+            "- starboard_threshold: 3\n+ starboard_threshold: 5\n"
+        );
+
+        assert!(new.diff(&new)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn file_vars_are_layered_under_env() -> Result<()> {
+        let mut vars = file_vars(
+            r#"
+            DISCORD_TOKEN = "file"
+            owners = ["1", 2]
+            migrations_dry_run = true
+            "#,
+        )?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+        vars.insert("discord_token".to_owned(), "env".to_owned());
+
+        let config: EnvConfig = envy::from_iter(vars)?;
+        assert_eq!(config.discord_token, "env");
+        assert_eq!(
+            config.message_log_retention_limit(),
+            DEFAULT_MESSAGE_LOG_RETENTION
+        );
+        assert_eq!(config.owners, Some([UserId::new(1), UserId::new(2)].into()));
+        assert!(config.migrations_dry_run);
+
+        assert!(file_vars("nested = { a = 1 }").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn feature_overrides() {
+        let (a, b) = (GenericChannelId::new(1), GenericChannelId::new(2));
+
+        let mut features = GuildFeatures {
+            disabled: [Feature::Autoreply].into(),
+            ..Default::default()
+        };
+
+        features.set_override(Feature::Autoreply, a, Some(true));
+        features.set_override(Feature::Starboard, b, Some(false));
+
+        assert!(features.enabled(Feature::Autoreply, a));
+        assert!(!features.enabled(Feature::Autoreply, b));
+        assert!(!features.enabled(Feature::Starboard, b));
+        assert!(features.enabled(Feature::Starboard, a));
+
+        features.set_override(Feature::Autoreply, a, None);
+        features.set_override(Feature::Starboard, b, None);
+        assert!(features.channels.is_empty());
+
+        assert!(Feature::ALL.into_iter().all(|f| {
+            Feature::from_key(f.key()) == Some(f)
+                && serde_json::to_value(f).ok() == Some(serde_json::Value::from(f.key()))
+        }));
+    }
+
+    #[test]
+    fn message_log_retention_is_capped() {
+        let limit = Duration::from_hours(24 * 7);
+
+        assert_eq!(
+            GuildConfig::default().message_log_ttl(limit),
+            DEFAULT_MESSAGE_LOG_RETENTION
+        );
+
+        let config = GuildConfig {
+            message_log_retention: Some(Duration::from_hours(24 * 30)),
+            ..Default::default()
+        };
+        assert_eq!(config.message_log_ttl(limit), limit);
+    }
+
+    #[test]
+    fn attachment_logs_allowlist() {
+        let logs = AttachmentLogs {
+            content_types: ["image/*".to_owned(), "text/plain".to_owned()].into(),
+            ..Default::default()
+        };
+
+        assert!(logs.allows(Some("image/png"), 1024));
+        assert!(logs.allows(Some("text/plain; charset=utf-8"), 1024));
+        assert!(!logs.allows(Some("text/html"), 1024));
+        assert!(!logs.allows(Some("image/png"), logs.max_size + 1));
+        assert!(!logs.allows(None, 1024));
+    }
+
+    #[test]
+    fn warn_escalations_roundtrip() -> Result<()> {
+        let config: GuildConfig = toml::from_str(
+            r#"
+            warn_expiry = "90days"
+
+            [warn_escalations.3]
+            action = "timeout"
+            duration = "1h"
+
+            [warn_escalations.5]
+            action = "kick"
+            "#,
+        )?;
+
+        assert_eq!(
+            config.warn_escalations.get(&3),
+            Some(&WarnEscalation::Timeout {
+                duration: Duration::from_hours(1)
+            })
+        );
+        assert_eq!(config.warn_escalations[&5].to_string(), "Kick");
+        assert_eq!(config.warn_expiry, Some(Duration::from_hours(24 * 90)));
+
+        let json = serde_json::to_value(&config)?;
+        assert_eq!(
+            json["warn_escalations"]["3"],
+            serde_json::json!({ "action": "timeout", "duration": "1h" })
+        );
+        assert_eq!(
+            serde_json::from_value::<GuildConfig>(json)?.warn_expiry,
+            config.warn_expiry
+        );
+        assert!(
+            toml::from_str::<GuildConfig>("[warn_escalations.3]\naction = \"timeout\"").is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -4,7 +4,12 @@
 
 use poise::serenity_prelude as serenity;
 
-use crate::{commands, config::CONFIG, handlers, storage::log::MessageLog};
+use crate::{
+    commands,
+    config::{CONFIG, Feature},
+    handlers,
+    storage::log::MessageLog,
+};
 
 fn validate_commands(commands: &[poise::Command<crate::Data, eyre::Report>]) {
     if !commands.iter().filter(|c| c.guild_only).all(|c| {
@@ -138,7 +143,7 @@ impl serenity::EventHandler for EventHandler {
 
                     register_globally(&ctx.http, &public_commands).await?;
 
-                    if let Some(guild) = CONFIG.admin_guild_id {
+                    if let Some(guild) = CONFIG.get().admin_guild_id {
                         register_in_guild(&ctx.http, &owner_commands, guild).await?;
                     }
 
@@ -157,6 +162,10 @@ impl serenity::EventHandler for EventHandler {
                 FullEvent::InteractionCreate { interaction, .. } => {
                     if let Some(interaction) = interaction.as_message_component() {
                         handlers::config::handle(ctx, interaction).await?;
+                        handlers::appeal::handle_component(ctx, interaction).await?;
+                        handlers::ban_group::handle_component(ctx, interaction).await?;
+                    } else if let Some(interaction) = interaction.as_modal_submit() {
+                        handlers::appeal::handle_modal(ctx, interaction).await?;
                     }
                 }
 
@@ -169,11 +178,19 @@ impl serenity::EventHandler for EventHandler {
                 }
 
                 FullEvent::MessageUpdate { event, .. } => {
-                    if event.message.guild_id.is_none() {
+                    let Some(guild_id) = event.message.guild_id else {
                         return Ok(());
-                    }
+                    };
 
-                    if let Some(storage) = &ctx.data::<crate::Data>().storage {
+                    if let Some(storage) = &ctx.data::<crate::Data>().storage
+                        && handlers::feature_enabled(
+                            ctx,
+                            event.message.guild_id,
+                            event.message.channel_id,
+                            Feature::MessageLogs,
+                        )
+                        .await?
+                    {
                         let edited_timestamp = event
                             .message
                             .edited_timestamp
@@ -184,14 +201,26 @@ impl serenity::EventHandler for EventHandler {
                         let new_content = event.message.content.as_str();
                         let attachments = event.message.attachments.to_vec();
 
+                        let ttl = storage
+                            .get_config(guild_id)
+                            .await?
+                            .message_log_ttl(CONFIG.get().message_log_retention_limit());
+
                         storage
                             .set_message_log(
+                                guild_id,
                                 event.message.id,
                                 &MessageLog {
                                     content: new_content.to_owned(),
                                     author: event.message.author.id,
+                                    channel: Some(event.message.channel_id),
                                     attachments: attachments.clone(),
+                                    cached_attachments: prev_data
+                                        .as_ref()
+                                        .map(|p| p.cached_attachments.clone())
+                                        .unwrap_or_default(),
                                 },
+                                ttl,
                             )
                             .await?;
 
@@ -338,7 +367,29 @@ impl serenity::EventHandler for EventHandler {
                 }
 
                 FullEvent::GuildMemberAddition { new_member, .. } => {
-                    handlers::log::member_join(ctx, new_member).await?;
+                    // A failure in one handler must not keep the others, such as
+                    // re-quarantining a returning member, from running
+                    let (log, raid, quarantine) = tokio::join!(
+                        handlers::log::member_join(ctx, new_member),
+                        handlers::raid::member_join(ctx, new_member),
+                        handlers::quarantine::member_join(ctx, new_member),
+                    );
+
+                    for result in [log, raid, quarantine] {
+                        if let Err(err) = result {
+                            tracing::error!("{err:?}");
+                        }
+                    }
+                }
+
+                FullEvent::GuildMemberUpdate {
+                    old_if_available,
+                    new,
+                    ..
+                } => {
+                    if let (Some(old), Some(new)) = (old_if_available, new) {
+                        handlers::log::member_update(ctx, old, new).await?;
+                    }
                 }
 
                 FullEvent::GuildMemberRemoval {
@@ -356,8 +407,77 @@ impl serenity::EventHandler for EventHandler {
                     .await?;
                 }
 
+                FullEvent::GuildAuditLogEntryCreate {
+                    entry, guild_id, ..
+                } => {
+                    // Moderation logs, ban sharing and server logs don't depend on each other
+                    let (audit_log, ban_group, server_log) = tokio::join!(
+                        handlers::audit_log::handle(ctx, entry, *guild_id),
+                        handlers::ban_group::handle_audit_log(ctx, entry, *guild_id),
+                        handlers::server_log::handle_audit_log(ctx, entry, *guild_id),
+                    );
+
+                    for result in [audit_log, ban_group, server_log] {
+                        if let Err(err) = result {
+                            tracing::error!("{err:?}");
+                        }
+                    }
+                }
+
+                FullEvent::ChannelCreate { channel, .. } => {
+                    handlers::server_log::channel_create(ctx, channel).await?;
+                }
+
+                FullEvent::ChannelUpdate {
+                    old: Some(old),
+                    new,
+                    ..
+                } => {
+                    handlers::server_log::channel_update(ctx, old, new).await?;
+                }
+
+                FullEvent::ChannelDelete { channel, .. } => {
+                    handlers::server_log::channel_delete(ctx, channel).await?;
+                }
+
+                FullEvent::GuildRoleCreate { new, .. } => {
+                    handlers::server_log::role_create(ctx, new).await?;
+                }
+
+                FullEvent::GuildRoleUpdate {
+                    old_data_if_available: Some(old),
+                    new,
+                    ..
+                } => {
+                    handlers::server_log::role_update(ctx, old, new).await?;
+                }
+
+                FullEvent::GuildRoleDelete {
+                    guild_id,
+                    removed_role_id,
+                    removed_role_data_if_available,
+                    ..
+                } => {
+                    handlers::server_log::role_delete(
+                        ctx,
+                        *guild_id,
+                        *removed_role_id,
+                        removed_role_data_if_available.as_ref(),
+                    )
+                    .await?;
+                }
+
+                FullEvent::GuildUpdate {
+                    old_data_if_available: Some(old),
+                    new_data,
+                    ..
+                } => {
+                    handlers::server_log::guild_update(ctx, old, new_data).await?;
+                }
+
                 FullEvent::GuildCreate { guild, .. } => {
                     if CONFIG
+                        .get()
                         .allowed_guilds
                         .as_ref()
                         .is_some_and(|a| !a.contains(&guild.id))
@@ -0,0 +1,415 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use crate::{
+    commands::actions,
+    storage::{
+        StorageBackend,
+        appeal::{self, Appeal, AppealStatus},
+        case::{self, CaseAction},
+    },
+    utils,
+};
+
+/// `appeal:{guild}`, attached to ban DMs
+const APPEAL_BUTTON: &str = "appeal:";
+/// `appeal-modal:{guild}`
+const APPEAL_MODAL: &str = "appeal-modal:";
+/// `appeal-accept:{user}`, attached to appeals in the appeals channel
+const ACCEPT_BUTTON: &str = "appeal-accept:";
+/// `appeal-deny:{user}`, attached to appeals in the appeals channel
+const DENY_BUTTON: &str = "appeal-deny:";
+
+/// The button that lets a banned user appeal their ban in `guild`.
+pub fn button(guild: serenity::GuildId) -> serenity::CreateContainerComponent<'static> {
+    serenity::CreateContainerComponent::ActionRow(serenity::CreateActionRow::Buttons(
+        vec![
+            serenity::CreateButton::new(format!("{APPEAL_BUTTON}{guild}"))
+                .label("Appeal")
+                .style(serenity::ButtonStyle::Secondary),
+        ]
+        .into(),
+    ))
+}
+
+fn appeal_container(user: serenity::UserId, appeal: &Appeal) -> serenity::CreateContainer<'static> {
+    let mut container = serenity::CreateContainer::new(vec![
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "### Ban appeal\n{}",
+            utils::serenity::format_mentionable(Some(user))
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "**Appeal**\n{}",
+            appeal.content
+        ))),
+    ])
+    .accent_color(0xda77f2);
+
+    if let Some(case) = appeal.case {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Case**\n{case}")),
+        ));
+    }
+
+    container
+}
+
+/// Why `user` cannot appeal their ban in `guild` right now, if they cannot.
+async fn appeal_blocked(
+    http: &serenity::Http,
+    storage: &dyn StorageBackend,
+    guild: serenity::GuildId,
+    user: serenity::UserId,
+) -> Result<Option<String>> {
+    let guild_config = storage.get_config(guild).await?;
+
+    if guild_config.appeals_channel.is_none() {
+        return Ok(Some("This server does not accept ban appeals.".to_owned()));
+    }
+
+    if guild.get_ban(http, user).await?.is_none() {
+        return Ok(Some("You are not banned from this server.".to_owned()));
+    }
+
+    let Some(previous) = storage.get_appeal(guild, user).await? else {
+        return Ok(None);
+    };
+
+    if previous.status == AppealStatus::Pending {
+        return Ok(Some(
+            "Your previous appeal is still being reviewed.".to_owned(),
+        ));
+    }
+
+    let cooldown = guild_config
+        .appeal_cooldown
+        .unwrap_or(appeal::DEFAULT_COOLDOWN);
+
+    Ok(previous.cooldown_ends(cooldown).map(|ends| {
+        format!(
+            "You can appeal again {}.",
+            serenity::FormattedTimestamp::new(
+                ends.into(),
+                Some(serenity::FormattedTimestampStyle::RelativeTime)
+            )
+        )
+    }))
+}
+
+async fn open_modal(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+    guild: serenity::GuildId,
+) -> Result<()> {
+    let storage = ctx
+        .data::<crate::Data>()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    if let Some(reason) = appeal_blocked(&ctx.http, &**storage, guild, interaction.user.id).await? {
+        interaction
+            .create_response(
+                &ctx.http,
+                serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new().content(reason),
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    interaction
+        .create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::Modal(
+                serenity::CreateModal::new(format!("{APPEAL_MODAL}{guild}"), "Ban appeal")
+                    .components(vec![serenity::CreateActionRow::InputText(
+                        serenity::CreateInputText::new(
+                            serenity::InputTextStyle::Paragraph,
+                            "Why should you be unbanned?",
+                            "content",
+                        )
+                        .max_length(2000),
+                    )]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Handle a submitted appeal modal, forwarding the appeal to the appeals channel.
+#[tracing::instrument(skip_all, fields(user = interaction.user.id.get()))]
+pub async fn handle_modal(
+    ctx: &serenity::Context,
+    interaction: &serenity::ModalInteraction,
+) -> Result<()> {
+    let Some(guild) = interaction.data.custom_id.strip_prefix(APPEAL_MODAL) else {
+        return Ok(());
+    };
+    let guild: serenity::GuildId = guild.parse()?;
+    let user = interaction.user.id;
+
+    let storage = ctx
+        .data::<crate::Data>()
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    if let Some(reason) = appeal_blocked(&ctx.http, &**storage, guild, user).await? {
+        interaction
+            .create_response(
+                &ctx.http,
+                serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new().content(reason),
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let Some(appeals_channel) = storage.get_config(guild).await?.appeals_channel else {
+        return Ok(());
+    };
+
+    let content = interaction
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            serenity::ActionRowComponent::InputText(input) => {
+                input.value.as_deref().map(str::to_owned)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| eyre!("could not obtain appeal content"))?;
+
+    let case = case::for_user(&**storage, guild, user)
+        .await?
+        .into_iter()
+        .rfind(|case| case.action == CaseAction::Ban)
+        .map(|case| case.id);
+
+    let appeal = Appeal::new(content, case);
+    storage.set_appeal(guild, user, &appeal).await?;
+
+    appeals_channel
+        .send_message(
+            &ctx.http,
+            serenity::CreateMessage::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(serenity::CreateAllowedMentions::new())
+                .components(vec![serenity::CreateComponent::Container(
+                    appeal_container(user, &appeal).add_component(
+                        serenity::CreateContainerComponent::ActionRow(
+                            serenity::CreateActionRow::Buttons(
+                                vec![
+                                    serenity::CreateButton::new(format!("{ACCEPT_BUTTON}{user}"))
+                                        .label("Accept")
+                                        .style(serenity::ButtonStyle::Success),
+                                    serenity::CreateButton::new(format!("{DENY_BUTTON}{user}"))
+                                        .label("Deny")
+                                        .style(serenity::ButtonStyle::Danger),
+                                ]
+                                .into(),
+                            ),
+                        ),
+                    ),
+                )]),
+        )
+        .await?;
+
+    interaction
+        .create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::Message(
+                serenity::CreateInteractionResponseMessage::new()
+                    .content("Your appeal has been submitted."),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn notify_denied(
+    ctx: &serenity::Context,
+    guild: &serenity::PartialGuild,
+    user: serenity::UserId,
+) -> Result<()> {
+    let dm = user.create_dm_channel(ctx).await?;
+
+    dm.id
+        .widen()
+        .send_message(
+            &ctx.http,
+            serenity::CreateMessage::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(serenity::CreateAllowedMentions::new())
+                .components(vec![serenity::CreateComponent::Container(
+                    serenity::CreateContainer::new(vec![
+                        serenity::CreateContainerComponent::TextDisplay(
+                            serenity::CreateTextDisplay::new(format!(
+                                "### Appeal denied\nYour ban appeal to **{}** has been denied.",
+                                guild.name
+                            )),
+                        ),
+                        serenity::CreateContainerComponent::TextDisplay(
+                            serenity::CreateTextDisplay::new(format!(
+                                "-# {} \u{00B7} {}",
+                                guild.name,
+                                serenity::FormattedTimestamp::now()
+                            )),
+                        ),
+                    ])
+                    .accent_color(0xff6b6b),
+                )]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn resolve(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+    user: serenity::UserId,
+    status: AppealStatus,
+) -> Result<()> {
+    let guild_id = interaction
+        .guild_id
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    let partial_guild = guild_id.to_partial_guild(&ctx).await?;
+
+    if !partial_guild
+        .member_permissions(
+            interaction
+                .member
+                .as_ref()
+                .ok_or_else(|| eyre!("could not obtain interaction member"))?,
+        )
+        .ban_members()
+    {
+        interaction
+            .create_response(
+                &ctx.http,
+                serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .content("You don't have permission to review ban appeals.")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let data = ctx.data::<crate::Data>();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let Some(mut appeal) = storage
+        .get_appeal(guild_id, user)
+        .await?
+        .filter(|appeal| appeal.status == AppealStatus::Pending)
+    else {
+        interaction.defer(&ctx.http).await?;
+        return Ok(());
+    };
+
+    match status {
+        AppealStatus::Accepted => {
+            // The appeal stays pending so that it can be accepted again or denied
+            if let Err(err) = actions::unban(
+                &ctx.http,
+                &data,
+                guild_id,
+                user,
+                "Ban appeal accepted",
+                Some(interaction.user.id),
+                appeal.case,
+            )
+            .await
+            {
+                tracing::warn!("failed to unban user of accepted appeal: {err:?}");
+
+                interaction
+                    .create_response(
+                        &ctx.http,
+                        serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new()
+                                .content(format!("Failed to unban {}: {err}", user.mention()))
+                                .allowed_mentions(serenity::CreateAllowedMentions::new())
+                                .ephemeral(true),
+                        ),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        }
+        AppealStatus::Denied => {
+            if let Err(err) = notify_denied(ctx, &partial_guild, user).await {
+                tracing::warn!("failed to notify user of denied appeal: {err:?}");
+            }
+        }
+        AppealStatus::Pending => {}
+    }
+
+    appeal.status = status;
+    storage.set_appeal(guild_id, user, &appeal).await?;
+
+    interaction
+        .create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new().components(vec![
+                    serenity::CreateComponent::Container(
+                        appeal_container(user, &appeal).add_component(
+                            serenity::CreateContainerComponent::TextDisplay(
+                                serenity::CreateTextDisplay::new(format!(
+                                    "-# {} by {} \u{00B7} {}",
+                                    appeal.status,
+                                    interaction.user.mention(),
+                                    serenity::FormattedTimestamp::now()
+                                )),
+                            ),
+                        ),
+                    ),
+                ]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Handle the appeal button on ban DMs and the accept and deny buttons in the appeals channel.
+#[tracing::instrument(skip_all, fields(user = interaction.user.id.get()))]
+pub async fn handle_component(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+) -> Result<()> {
+    let custom_id = interaction.data.custom_id.as_str();
+
+    if let Some(guild) = custom_id.strip_prefix(APPEAL_BUTTON) {
+        open_modal(ctx, interaction, guild.parse()?).await?;
+    } else if let Some(user) = custom_id.strip_prefix(ACCEPT_BUTTON) {
+        resolve(ctx, interaction, user.parse()?, AppealStatus::Accepted).await?;
+    } else if let Some(user) = custom_id.strip_prefix(DENY_BUTTON) {
+        resolve(ctx, interaction, user.parse()?, AppealStatus::Denied).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, bail};
+use poise::serenity_prelude as serenity;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::{AttachmentLogs, CONFIG},
+    http::HTTP,
+    storage::log::MessageLog,
+    utils,
+};
+
+/// The maximum number of attachments re-uploaded for a single deleted message.
+const MAX_FILES: usize = 10;
+
+/// The largest single attachment that is downloaded into the cache, regardless of guild settings.
+const MAX_CACHED_SIZE: u64 = 25 * 1024 * 1024;
+
+/// An attachment of a deleted message that is re-uploaded to the message logs.
+pub struct Reupload {
+    /// The unique filename that the upload can be referenced by with `attachment://`.
+    pub filename: String,
+    /// Whether the attachment can be shown in a media gallery.
+    pub media: bool,
+    pub file: serenity::CreateAttachment<'static>,
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    CONFIG
+        .get()
+        .attachment_cache_path
+        .as_ref()
+        .map(PathBuf::from)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, byte| {
+        let _ = write!(s, "{byte:02x}");
+        s
+    })
+}
+
+async fn download(url: &str, max_size: u64) -> Result<Vec<u8>> {
+    let resp = HTTP.get(url).send().await?.error_for_status()?;
+
+    if resp.content_length().is_some_and(|len| len > max_size) {
+        bail!(
+            "attachment is larger than {}",
+            utils::format_bytes(max_size)
+        );
+    }
+
+    let data = resp.bytes().await?;
+
+    if u64::try_from(data.len())? > max_size {
+        bail!(
+            "attachment is larger than {}",
+            utils::format_bytes(max_size)
+        );
+    }
+
+    Ok(data.to_vec())
+}
+
+async fn save(dir: &Path, url: &str, max_size: u64) -> Result<String> {
+    let data = download(url, max_size).await?;
+    let hash = hex(&utils::sha256(&data));
+
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(dir.join(&hash), data).await?;
+
+    Ok(hash)
+}
+
+/// Save the allowed attachments of a new message to the local cache, so that they are still
+/// available after Discord stops serving them. Returns the content hashes of the saved attachments.
+pub async fn store(
+    settings: &AttachmentLogs,
+    attachments: &[serenity::Attachment],
+) -> HashMap<serenity::AttachmentId, String> {
+    let mut hashes = HashMap::new();
+
+    let Some(dir) = cache_dir() else {
+        return hashes;
+    };
+
+    let max_size = settings.max_size.min(MAX_CACHED_SIZE);
+
+    for attachment in attachments.iter().filter(|att| {
+        u64::from(att.size) <= max_size
+            && settings.allows(att.content_type.as_deref(), att.size.into())
+    }) {
+        match save(&dir, &attachment.url, max_size).await {
+            Ok(hash) => {
+                hashes.insert(attachment.id, hash);
+            }
+            Err(err) => {
+                tracing::warn!(
+                    id = attachment.id.get(),
+                    "failed to cache attachment: {err:?}"
+                );
+            }
+        }
+    }
+
+    hashes
+}
+
+async fn load(
+    attachment: &serenity::Attachment,
+    log: &MessageLog,
+    max_size: u64,
+) -> Result<Vec<u8>> {
+    if let Ok(data) = download(&attachment.url, max_size).await {
+        return Ok(data);
+    }
+
+    let (Some(dir), Some(hash)) = (cache_dir(), log.cached_attachments.get(&attachment.id)) else {
+        bail!("attachment is no longer available and was not cached");
+    };
+
+    if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("invalid attachment hash `{hash}`");
+    }
+
+    Ok(tokio::fs::read(dir.join(hash)).await?)
+}
+
+/// Fetch the allowed attachments of a deleted message for re-uploading, falling back to the
+/// local cache when the originals are already gone. The total size is kept within the size cap.
+pub async fn fetch(settings: &AttachmentLogs, log: &MessageLog) -> Vec<Reupload> {
+    let mut reuploads = Vec::new();
+    let mut filenames = HashSet::new();
+    let mut total_size = 0;
+
+    for attachment in &log.attachments {
+        let size = u64::from(attachment.size);
+
+        if reuploads.len() >= MAX_FILES
+            || total_size + size > settings.max_size
+            || !settings.allows(attachment.content_type.as_deref(), size)
+        {
+            continue;
+        }
+
+        let data = match load(attachment, log, settings.max_size).await {
+            Ok(data) => data,
+            Err(err) => {
+                tracing::warn!(
+                    id = attachment.id.get(),
+                    "failed to fetch attachment: {err:?}"
+                );
+                continue;
+            }
+        };
+
+        let filename = if filenames.contains(&*attachment.filename) {
+            format!("{}_{}", attachment.id, attachment.filename)
+        } else {
+            attachment.filename.to_string()
+        };
+        filenames.insert(filename.clone());
+
+        total_size += size;
+        reuploads.push(Reupload {
+            media: attachment
+                .content_type
+                .as_ref()
+                .is_some_and(|ct| ct.starts_with("image/") || ct.starts_with("video/")),
+            file: serenity::CreateAttachment::bytes(data, filename.clone()),
+            filename,
+        });
+    }
+
+    reuploads
+}
+
+/// Delete cached attachments that are older than any message log referencing them, which are
+/// kept for at most the operator's maximum message log retention.
+pub async fn prune() -> Result<()> {
+    let Some(dir) = cache_dir() else {
+        return Ok(());
+    };
+
+    let max_age = CONFIG.get().message_log_retention_limit();
+
+    let mut entries = match tokio::fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+
+    while let Some(entry) = entries.next_entry().await? {
+        if entry
+            .metadata()
+            .await?
+            .modified()?
+            .elapsed()
+            .is_ok_and(|age| age > max_age)
+        {
+            tokio::fs::remove_file(entry.path()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_works() {
+        assert_eq!(hex(&[0x00, 0x0f, 0xab, 0xff]), "000fabff");
+        assert_eq!(hex(&utils::sha256(b"")).len(), 64);
+    }
+}
@@ -0,0 +1,188 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{
+    self as serenity, Mentionable as _,
+    audit_log::{Action, Change, MemberAction, MessageAction},
+};
+
+use crate::utils;
+
+/// A moderation action resolved from an audit log entry.
+struct AuditLogAction {
+    title: &'static str,
+    accent_color: u32,
+    fields: Vec<String>,
+}
+
+fn format_roles(roles: &[serenity::audit_log::AffectedRole]) -> String {
+    roles
+        .iter()
+        .map(|role| role.id.mention().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn resolve(entry: &serenity::AuditLogEntry, guild_id: serenity::GuildId) -> Option<AuditLogAction> {
+    match entry.action {
+        Action::Member(MemberAction::BanAdd) => Some(AuditLogAction {
+            title: "Ban",
+            accent_color: 0xda77f2,
+            fields: Vec::new(),
+        }),
+
+        Action::Member(MemberAction::BanRemove) => Some(AuditLogAction {
+            title: "Unban",
+            accent_color: 0xda77f2,
+            fields: Vec::new(),
+        }),
+
+        Action::Member(MemberAction::Kick) => Some(AuditLogAction {
+            title: "Kick",
+            accent_color: 0xf783ac,
+            fields: Vec::new(),
+        }),
+
+        Action::Member(MemberAction::Update) => {
+            let until = entry.changes.iter().find_map(|change| match change {
+                Change::CommunicationDisabledUntil { new, .. } => Some(*new),
+                _ => None,
+            })?;
+
+            Some(match until {
+                Some(until) if until > serenity::Timestamp::now() => AuditLogAction {
+                    title: "Timeout",
+                    accent_color: 0x9775fa,
+                    fields: vec![format!(
+                        "**Until**\n{}",
+                        serenity::FormattedTimestamp::new(until, None)
+                    )],
+                },
+                _ => AuditLogAction {
+                    title: "Timeout removed",
+                    accent_color: 0x9775fa,
+                    fields: Vec::new(),
+                },
+            })
+        }
+
+        Action::Member(MemberAction::RoleUpdate) => {
+            let mut fields = Vec::new();
+
+            for change in &entry.changes {
+                match change {
+                    Change::RolesAdded {
+                        new: Some(roles), ..
+                    } if !roles.is_empty() => {
+                        fields.push(format!("**Added**\n{}", format_roles(roles)));
+                    }
+                    Change::RolesRemove {
+                        new: Some(roles), ..
+                    } if !roles.is_empty() => {
+                        fields.push(format!("**Removed**\n{}", format_roles(roles)));
+                    }
+                    _ => {}
+                }
+            }
+
+            Some(AuditLogAction {
+                title: "Roles updated",
+                accent_color: 0x74c0fc,
+                fields,
+            })
+        }
+
+        Action::Message(action @ (MessageAction::Pin | MessageAction::Unpin)) => {
+            let options = entry.options.as_ref()?;
+            let (channel, message) = (options.channel_id?, options.message_id?);
+
+            Some(AuditLogAction {
+                title: if action == MessageAction::Pin {
+                    "Message pinned"
+                } else {
+                    "Message unpinned"
+                },
+                accent_color: 0xffd43b,
+                fields: vec![format!(
+                    "**Message**\n{}",
+                    message.link(channel, Some(guild_id))
+                )],
+            })
+        }
+
+        _ => None,
+    }
+}
+
+/// Mirror moderation actions from the audit log into the guild's moderation logs.
+/// Actions performed by Valfisk are skipped, since the commands log them already.
+#[tracing::instrument(skip_all, fields(id = entry.id.get(), guild = guild_id.get()))]
+pub async fn handle(
+    ctx: &serenity::Context,
+    entry: &serenity::AuditLogEntry,
+    guild_id: serenity::GuildId,
+) -> Result<()> {
+    if entry.user_id == Some(ctx.cache.current_user().id) {
+        return Ok(());
+    }
+
+    let Some(storage) = &ctx.data::<crate::Data>().storage else {
+        return Ok(());
+    };
+
+    let Some(logs_channel) = storage.get_config(guild_id).await?.moderation_logs_channel else {
+        return Ok(());
+    };
+
+    let Some(action) = resolve(entry, guild_id) else {
+        return Ok(());
+    };
+
+    let target = entry
+        .target_id
+        .map(|target| serenity::UserId::new(target.get()));
+
+    let mut container =
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "### {}\n{}",
+                action.title,
+                utils::serenity::format_mentionable(target),
+            )),
+        )])
+        .accent_color(action.accent_color);
+
+    if let Some(reason) = &entry.reason {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Reason**\n{reason}")),
+        ));
+    }
+
+    for field in action.fields {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(field),
+        ));
+    }
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!(
+            "-# {} \u{00B7} {}",
+            utils::serenity::format_mentionable(entry.user_id),
+            serenity::FormattedTimestamp::now()
+        )),
+    ));
+
+    logs_channel
+        .send_message(
+            &ctx.http,
+            serenity::CreateMessage::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(serenity::CreateAllowedMentions::new())
+                .components(vec![serenity::CreateComponent::Container(container)]),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use crate::{
+    commands::actions,
+    config,
+    storage::{
+        StorageBackend,
+        automod::{AutomodAction, AutomodRule},
+        case::{self, CaseAction, CaseLogMessage, ModerationCase},
+    },
+    utils,
+};
+
+/// Apply the action of `rule` to `message`, returning the moderation case it created, if any.
+async fn apply(
+    ctx: &serenity::Context,
+    storage: &dyn StorageBackend,
+    message: &serenity::Message,
+    guild_id: serenity::GuildId,
+    rule: &AutomodRule,
+) -> Result<Option<ModerationCase>> {
+    let reason = format!("Automod rule {}", rule.id);
+
+    if rule.action == AutomodAction::Log {
+        return Ok(None);
+    }
+
+    message.delete(&ctx.http, Some(&reason)).await?;
+
+    let moderator = actions::Moderator {
+        ctx,
+        user: ctx.cache.current_user().id,
+    };
+
+    // Timeouts are applied before the warn so that an escalation can still override them
+    let timeout_case = if let AutomodAction::Timeout { duration } = rule.action {
+        let duration = duration.min(config::MAX_TIMEOUT);
+
+        let mut member = message.member(&ctx).await?;
+        member
+            .disable_communication_until(&ctx.http, (chrono::Utc::now() + duration).into())
+            .await?;
+
+        Some(
+            case::create(
+                storage,
+                guild_id,
+                ModerationCase {
+                    reason: Some(reason.clone()),
+                    duration: Some(duration),
+                    ..ModerationCase::new(CaseAction::Timeout, message.author.id, moderator.user)
+                },
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    // Every enforcing hit is warned through the same path as `/warn` so that it counts
+    // toward escalation, but only `Warn` rules notify the user
+    let partial_guild = guild_id.to_partial_guild(&ctx).await?;
+    let (_, warn_case) = actions::warn(
+        moderator,
+        &partial_guild,
+        &message.author,
+        Some(reason),
+        rule.action == AutomodAction::Warn,
+    )
+    .await?;
+
+    Ok(timeout_case.or(warn_case))
+}
+
+/// Check a message against the guild's automod rules, applying the first rule that matches.
+/// Returns whether the message was removed.
+#[tracing::instrument(skip_all, fields(message_id = message.id.get()))]
+pub async fn handle(ctx: &serenity::Context, message: &serenity::Message) -> Result<bool> {
+    if message.author.bot() {
+        return Ok(false);
+    }
+
+    let Some(guild_id) = message.guild_id else {
+        return Ok(false);
+    };
+
+    let Some(storage) = &ctx.data::<crate::Data>().storage else {
+        return Ok(false);
+    };
+
+    let roles = message
+        .member
+        .as_ref()
+        .map(|member| member.roles.to_vec())
+        .unwrap_or_default();
+
+    let Some((rule, matched)) = storage
+        .scan_automod_rules(guild_id)
+        .await?
+        .into_iter()
+        .filter(|rule| rule.applies(message.channel_id, &roles))
+        .find_map(|rule| {
+            let matched = rule.find(&message.content)?.to_owned();
+            Some((rule, matched))
+        })
+    else {
+        return Ok(false);
+    };
+
+    tracing::info!(rule = %rule.id, "automod rule matched");
+
+    let mut case = apply(ctx, &**storage, message, guild_id, &rule).await?;
+
+    if let Some(logs_channel) = storage.get_config(guild_id).await?.moderation_logs_channel {
+        let mut container = serenity::CreateContainer::new(vec![
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "### Automod\n{}",
+                    utils::serenity::format_mentionable(Some(message.author.id))
+                ),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("**Rule**\n`{}` \u{00B7} {}", rule.id, rule.pattern),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("**Action**\n{}", rule.action),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "**Channel**\n{}",
+                    utils::serenity::format_mentionable(Some(message.channel_id))
+                ),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("**Match**\n`{}`", utils::truncate(&matched, 256)),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("**Content**\n{}", utils::truncate(&message.content, 1024)),
+            )),
+        ])
+        .accent_color(0xff6b6b);
+
+        if let Some(case) = &case {
+            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
+            ));
+        }
+
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "-# {} \u{00B7} {}",
+                ctx.cache.current_user().id.mention(),
+                serenity::FormattedTimestamp::now()
+            )),
+        ));
+
+        let log_message = logs_channel
+            .send_message(
+                &ctx.http,
+                serenity::CreateMessage::default()
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new())
+                    .components(vec![serenity::CreateComponent::Container(container)]),
+            )
+            .await?;
+
+        // Warns are linked to the log of the warn itself
+        if let Some(case) = &mut case
+            && case.log_message.is_none()
+        {
+            case.log_message = Some(CaseLogMessage {
+                channel: logs_channel,
+                message: log_message.id,
+            });
+
+            storage.set_case(guild_id, case).await?;
+        }
+    }
+
+    Ok(rule.action != AutomodAction::Log)
+}
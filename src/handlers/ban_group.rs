@@ -0,0 +1,382 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{
+    self as serenity, Mentionable as _,
+    audit_log::{Action, MemberAction},
+};
+use std::collections::HashSet;
+
+use crate::{
+    storage::{
+        StorageBackend,
+        ban_group::{BanGroupPolicy, BanProposal},
+        case::{self, CaseAction, CaseLogMessage, ModerationCase},
+    },
+    utils,
+};
+
+/// `ban-group-accept:{user}`, attached to ban proposals in moderation logs channels
+const ACCEPT_BUTTON: &str = "ban-group-accept:";
+/// `ban-group-dismiss:{user}`, attached to ban proposals in moderation logs channels
+const DISMISS_BUTTON: &str = "ban-group-dismiss:";
+
+fn proposal_container(
+    user: serenity::UserId,
+    proposal: &BanProposal,
+) -> serenity::CreateContainer<'static> {
+    let mut container = serenity::CreateContainer::new(vec![
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "### Shared ban\n{}",
+            utils::serenity::format_mentionable(Some(user))
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "**Server**\n{} (`{}`)",
+            proposal.origin_name, proposal.origin
+        ))),
+    ])
+    .accent_color(0xda77f2);
+
+    if let Some(reason) = &proposal.reason {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Reason**\n{reason}")),
+        ));
+    }
+
+    container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!(
+            "**Banned by**\n{}",
+            utils::serenity::format_mentionable(proposal.moderator)
+        )),
+    ))
+}
+
+/// Ban `user` in `guild` because of a ban elsewhere in its ban group, recording a case.
+async fn ban(
+    http: &serenity::Http,
+    storage: &dyn StorageBackend,
+    guild: serenity::GuildId,
+    user: serenity::UserId,
+    proposal: &BanProposal,
+    moderator: serenity::UserId,
+) -> Result<ModerationCase> {
+    let reason = match &proposal.reason {
+        Some(reason) => format!("Shared ban from {}: {reason}", proposal.origin_name),
+        None => format!("Shared ban from {}", proposal.origin_name),
+    };
+
+    guild.ban(http, user, 0, Some(&reason)).await?;
+
+    case::create(
+        storage,
+        guild,
+        ModerationCase {
+            reason: Some(reason),
+            ..ModerationCase::new(CaseAction::Ban, user, moderator)
+        },
+    )
+    .await
+}
+
+/// Apply a ban to another guild of its ban group according to the group's policy.
+async fn share(
+    ctx: &serenity::Context,
+    storage: &dyn StorageBackend,
+    guild: serenity::GuildId,
+    user: serenity::UserId,
+    proposal: &BanProposal,
+    policy: BanGroupPolicy,
+) -> Result<()> {
+    let logs_channel = storage.get_config(guild).await?.moderation_logs_channel;
+
+    match policy {
+        BanGroupPolicy::Automatic => {
+            let mut case = ban(
+                &ctx.http,
+                storage,
+                guild,
+                user,
+                proposal,
+                ctx.cache.current_user().id,
+            )
+            .await?;
+
+            if let Some(logs_channel) = logs_channel {
+                let container = proposal_container(user, proposal)
+                    .add_component(serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
+                    ))
+                    .add_component(serenity::CreateContainerComponent::TextDisplay(
+                        serenity::CreateTextDisplay::new(format!(
+                            "-# {} \u{00B7} {}",
+                            ctx.cache.current_user().id.mention(),
+                            serenity::FormattedTimestamp::now()
+                        )),
+                    ));
+
+                let log_message = logs_channel
+                    .send_message(
+                        &ctx.http,
+                        serenity::CreateMessage::default()
+                            .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                            .allowed_mentions(serenity::CreateAllowedMentions::new())
+                            .components(vec![serenity::CreateComponent::Container(container)]),
+                    )
+                    .await?;
+
+                case.log_message = Some(CaseLogMessage {
+                    channel: logs_channel,
+                    message: log_message.id,
+                });
+                storage.set_case(guild, &case).await?;
+            }
+        }
+
+        BanGroupPolicy::Confirm => {
+            let Some(logs_channel) = logs_channel else {
+                tracing::warn!(
+                    guild = guild.get(),
+                    "cannot propose shared ban without a moderation logs channel"
+                );
+                return Ok(());
+            };
+
+            storage.set_ban_proposal(guild, user, proposal).await?;
+
+            let container = proposal_container(user, proposal).add_component(
+                serenity::CreateContainerComponent::ActionRow(serenity::CreateActionRow::Buttons(
+                    vec![
+                        serenity::CreateButton::new(format!("{ACCEPT_BUTTON}{user}"))
+                            .label("Ban")
+                            .style(serenity::ButtonStyle::Danger),
+                        serenity::CreateButton::new(format!("{DISMISS_BUTTON}{user}"))
+                            .label("Dismiss")
+                            .style(serenity::ButtonStyle::Secondary),
+                    ]
+                    .into(),
+                )),
+            );
+
+            logs_channel
+                .send_message(
+                    &ctx.http,
+                    serenity::CreateMessage::default()
+                        .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                        .allowed_mentions(serenity::CreateAllowedMentions::new())
+                        .components(vec![serenity::CreateComponent::Container(container)]),
+                )
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Share a ban in `origin` with the other guilds of the ban groups that `origin` belongs to.
+/// Guilds in several groups with `origin` follow the policy of the first group by name.
+#[tracing::instrument(skip_all, fields(guild = origin.get(), user = user.get()))]
+pub async fn propagate(
+    ctx: &serenity::Context,
+    origin: serenity::GuildId,
+    user: serenity::UserId,
+    reason: Option<&str>,
+    moderator: Option<serenity::UserId>,
+) -> Result<()> {
+    let Some(storage) = &ctx.data::<crate::Data>().storage else {
+        return Ok(());
+    };
+
+    let groups = storage
+        .scan_ban_groups()
+        .await?
+        .into_iter()
+        .filter(|group| group.guilds.contains(&origin))
+        .collect::<Vec<_>>();
+
+    if groups.is_empty() {
+        return Ok(());
+    }
+
+    let proposal = BanProposal {
+        origin,
+        origin_name: origin.to_partial_guild(&ctx.http).await?.name.to_string(),
+        reason: reason.map(str::to_owned),
+        moderator,
+        timestamp: chrono::Utc::now(),
+    };
+
+    let mut shared = HashSet::from([origin]);
+
+    for group in &groups {
+        for &guild in &group.guilds {
+            if !shared.insert(guild) {
+                continue;
+            }
+
+            if let Err(err) = share(ctx, &**storage, guild, user, &proposal, group.policy).await {
+                tracing::warn!(guild = guild.get(), "failed to share ban: {err:?}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Share bans made outside of Valfisk that are observed through the audit log.
+#[tracing::instrument(skip_all, fields(id = entry.id.get(), guild = guild_id.get()))]
+pub async fn handle_audit_log(
+    ctx: &serenity::Context,
+    entry: &serenity::AuditLogEntry,
+    guild_id: serenity::GuildId,
+) -> Result<()> {
+    if !matches!(entry.action, Action::Member(MemberAction::BanAdd))
+        || entry.user_id == Some(ctx.cache.current_user().id)
+    {
+        return Ok(());
+    }
+
+    let Some(target) = entry.target_id else {
+        return Ok(());
+    };
+
+    propagate(
+        ctx,
+        guild_id,
+        serenity::UserId::new(target.get()),
+        entry.reason.as_deref(),
+        entry.user_id,
+    )
+    .await
+}
+
+/// Handle the ban and dismiss buttons on ban proposals.
+#[tracing::instrument(skip_all, fields(user = interaction.user.id.get()))]
+pub async fn handle_component(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+) -> Result<()> {
+    let custom_id = interaction.data.custom_id.as_str();
+
+    let (user, accept): (serenity::UserId, bool) =
+        if let Some(user) = custom_id.strip_prefix(ACCEPT_BUTTON) {
+            (user.parse()?, true)
+        } else if let Some(user) = custom_id.strip_prefix(DISMISS_BUTTON) {
+            (user.parse()?, false)
+        } else {
+            return Ok(());
+        };
+
+    let guild_id = interaction
+        .guild_id
+        .ok_or_else(|| eyre!("could not obtain guild ID"))?;
+
+    if !guild_id
+        .to_partial_guild(&ctx)
+        .await?
+        .member_permissions(
+            interaction
+                .member
+                .as_ref()
+                .ok_or_else(|| eyre!("could not obtain interaction member"))?,
+        )
+        .ban_members()
+    {
+        interaction
+            .create_response(
+                &ctx.http,
+                serenity::CreateInteractionResponse::Message(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .content("You don't have permission to review ban proposals.")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+
+        return Ok(());
+    }
+
+    let data = ctx.data::<crate::Data>();
+    let storage = data
+        .storage
+        .as_ref()
+        .ok_or_else(|| eyre!("storage is not available"))?;
+
+    let Some(proposal) = storage.get_ban_proposal(guild_id, user).await? else {
+        interaction.defer(&ctx.http).await?;
+        return Ok(());
+    };
+
+    let mut container = proposal_container(user, &proposal);
+
+    if accept {
+        let mut case = match ban(
+            &ctx.http,
+            &**storage,
+            guild_id,
+            user,
+            &proposal,
+            interaction.user.id,
+        )
+        .await
+        {
+            Ok(case) => case,
+            Err(err) => {
+                tracing::warn!(
+                    guild = guild_id.get(),
+                    "failed to apply ban proposal: {err:?}"
+                );
+
+                // The proposal is kept so that it can be accepted again once the ban can succeed
+                interaction
+                    .create_response(
+                        &ctx.http,
+                        serenity::CreateInteractionResponse::Message(
+                            serenity::CreateInteractionResponseMessage::new()
+                                .content("Failed to ban the user! Check that I have permission to ban members.")
+                                .ephemeral(true),
+                        ),
+                    )
+                    .await?;
+
+                return Ok(());
+            }
+        };
+
+        storage.del_ban_proposal(guild_id, user).await?;
+
+        case.log_message = Some(CaseLogMessage {
+            channel: interaction.channel_id,
+            message: interaction.message.id,
+        });
+        storage.set_case(guild_id, &case).await?;
+
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("**Case**\n{}", case.id)),
+        ));
+    } else {
+        storage.del_ban_proposal(guild_id, user).await?;
+    }
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(format!(
+            "-# {} by {} \u{00B7} {}",
+            if accept { "Banned" } else { "Dismissed" },
+            interaction.user.mention(),
+            serenity::FormattedTimestamp::now()
+        )),
+    ));
+
+    interaction
+        .create_response(
+            &ctx.http,
+            serenity::CreateInteractionResponse::UpdateMessage(
+                serenity::CreateInteractionResponseMessage::new()
+                    .components(vec![serenity::CreateComponent::Container(container)]),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
@@ -12,6 +12,8 @@ use std::{pin::Pin, sync::LazyLock};
 
 use crate::{
     analytics,
+    config::Feature,
+    handlers,
     http::HTTP,
     storage::code_expansion::CodeExpansionData,
     utils::{serenity::suppress_embeds, sha256, truncate},
@@ -649,7 +651,15 @@ pub async fn handle_message(ctx: &serenity::Context, message: &serenity::Message
 
 #[tracing::instrument(skip_all, fields(message = message.id.get()))]
 pub async fn handle_edit(ctx: &serenity::Context, message: &serenity::Message) -> Result<()> {
-    if message.author.id == ctx.cache.current_user().id {
+    if message.author.id == ctx.cache.current_user().id
+        || !handlers::feature_enabled(
+            ctx,
+            message.guild_id,
+            message.channel_id,
+            Feature::CodeExpansion,
+        )
+        .await?
+    {
         return Ok(());
     }
 
@@ -5,7 +5,84 @@
 use std::collections::HashSet;
 
 use eyre::{Result, bail, eyre};
-use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use crate::{
+    config::{ConfigChange, Feature, GuildConfig},
+    storage::{
+        StorageBackend,
+        config_history::{self, ConfigHistoryEntry},
+    },
+    utils,
+};
+
+/// Format configuration changes as a diff code block, truncated to roughly `max_len` characters.
+pub fn format_changes(changes: &[ConfigChange], max_len: usize) -> String {
+    if changes.is_empty() {
+        return "No configuration changes".to_owned();
+    }
+
+    let diff = changes.iter().map(ToString::to_string).collect::<String>();
+
+    format!(
+        "```diff\n{}\n```",
+        utils::truncate(&diff, max_len).trim_end()
+    )
+}
+
+/// Post a configuration history entry to the moderation logs channel.
+pub async fn log_change(
+    http: &serenity::Http,
+    storage: &dyn StorageBackend,
+    guild_id: serenity::GuildId,
+    entry: &ConfigHistoryEntry,
+) -> Result<()> {
+    let guild_config = storage.get_config(guild_id).await?;
+
+    if let Some(logs_channel) = guild_config.moderation_logs_channel {
+        logs_channel
+            .send_message(
+                http,
+                serenity::CreateMessage::default()
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new())
+                    .components(vec![serenity::CreateComponent::Container(
+                        serenity::CreateContainer::new(vec![
+                            serenity::CreateContainerComponent::TextDisplay(
+                                serenity::CreateTextDisplay::new(format!(
+                                    "### Configuration changed\n**Version**: {}\n{}\n-# {} \u{00B7} {}",
+                                    entry.version,
+                                    format_changes(&entry.changes, 3000),
+                                    entry.actor.mention(),
+                                    serenity::FormattedTimestamp::new(entry.timestamp.into(), None),
+                                )),
+                            ),
+                        ])
+                        .accent_color(0x63e6be),
+                    )]),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Save a guild configuration, recording it in the configuration history and logging the change.
+pub async fn save(
+    http: &serenity::Http,
+    storage: &dyn StorageBackend,
+    guild_id: serenity::GuildId,
+    actor: serenity::UserId,
+    config: &GuildConfig,
+) -> Result<Option<ConfigHistoryEntry>> {
+    let entry = config_history::record(storage, guild_id, actor, config).await?;
+
+    if let Some(entry) = &entry {
+        log_change(http, storage, guild_id, entry).await?;
+    }
+
+    Ok(entry)
+}
 
 pub async fn handle(
     ctx: &serenity::Context,
@@ -83,6 +160,13 @@ pub async fn handle(
                     config.member_logs_channel = values.first().map(|ch| ch.widen());
                 }
             }
+            "server_logs_channel" => {
+                if let serenity::ComponentInteractionDataKind::ChannelSelect { values } =
+                    &interaction.data.kind
+                {
+                    config.server_logs_channel = values.first().map(|ch| ch.widen());
+                }
+            }
             "moderator_role" => {
                 if let serenity::ComponentInteractionDataKind::RoleSelect { values } =
                     &interaction.data.kind
@@ -90,6 +174,13 @@ pub async fn handle(
                     config.moderator_role = values.first().copied();
                 }
             }
+            "quarantine_role" => {
+                if let serenity::ComponentInteractionDataKind::RoleSelect { values } =
+                    &interaction.data.kind
+                {
+                    config.quarantine_role = values.first().copied();
+                }
+            }
             "logs_excluded_channels" => {
                 if let serenity::ComponentInteractionDataKind::ChannelSelect { values } =
                     &interaction.data.kind
@@ -105,12 +196,27 @@ pub async fn handle(
                     config.random_color_roles = values.iter().copied().collect::<HashSet<_>>();
                 }
             }
+            "disabled_features" => {
+                if let serenity::ComponentInteractionDataKind::StringSelect { values } =
+                    &interaction.data.kind
+                {
+                    config.features.disabled =
+                        values.iter().filter_map(|v| Feature::from_key(v)).collect();
+                }
+            }
             &_ => {
                 bail!("invalid config key in interaction: {config_key}")
             }
         }
 
-        storage.set_config(guild_id, &config).await?;
+        save(
+            &ctx.http,
+            &**storage,
+            guild_id,
+            interaction.user.id,
+            &config,
+        )
+        .await?;
     }
 
     Ok(())
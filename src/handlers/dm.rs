@@ -15,7 +15,7 @@ pub async fn handle(ctx: &serenity::Context, message: &serenity::Message) -> Res
     }
 
     if message.channel(&ctx).await?.private().is_some()
-        && let Some(logs_channel) = CONFIG.dm_logs_channel
+        && let Some(logs_channel) = CONFIG.get().dm_logs_channel
     {
         let mut container =
             serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
@@ -92,12 +92,13 @@ pub async fn handle(ctx: &serenity::Context, message: &serenity::Message) -> Res
         return Ok(());
     }
 
-    if CONFIG.anthropic_api_key.is_some()
+    if CONFIG.get().anthropic_api_key.is_some()
         && let Ok(member) = message.member(&ctx).await
     {
         let self_mention = ctx.cache.current_user().mention().to_string();
 
         if !CONFIG
+            .get()
             .intelligence_allowed_roles
             .as_ref()
             .is_none_or(|h| member.roles.iter().any(|r| h.contains(r)))
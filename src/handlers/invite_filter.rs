@@ -0,0 +1,223 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude as serenity;
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::utils;
+
+/// The most invites of a single message that are resolved; any further invites are ignored.
+const MAX_CODES: usize = 5;
+
+/// How long resolved invites are cached for.
+const CACHE_TTL: Duration = Duration::from_hours(1);
+
+/// Recently resolved invites, keyed by invite code.
+static RESOLVED: LazyLock<Mutex<HashMap<String, (Instant, Option<InviteTarget>)>>> =
+    LazyLock::new(Mutex::default);
+
+static INVITE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:discord(?:app)?\.com/invite|discord\.gg)/([a-z0-9-]+)").unwrap()
+});
+
+/// Extract the codes of all Discord invites in `content`.
+pub fn invite_codes(content: &str) -> Vec<&str> {
+    INVITE_REGEX
+        .captures_iter(content)
+        .filter_map(|captures| captures.get(1))
+        .map(|code| code.as_str())
+        .collect()
+}
+
+/// The server an invite leads to.
+#[derive(Debug, Clone)]
+pub struct InviteTarget {
+    pub guild: serenity::GuildId,
+    pub name: String,
+    pub member_count: Option<u64>,
+}
+
+/// Resolve an invite code to the server it leads to.
+/// Returns `None` for unknown or expired invites and invites that do not lead to a server.
+pub async fn resolve(http: &serenity::Http, code: &str) -> Result<Option<InviteTarget>> {
+    let invite = match http.get_invite(code, true, false, None).await {
+        Ok(invite) => invite,
+        Err(serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)))
+            if response.error.code == serenity::JsonErrorCode::UnknownInvite =>
+        {
+            return Ok(None);
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(invite.guild.map(|guild| InviteTarget {
+        guild: guild.id,
+        name: guild.name.to_string(),
+        member_count: invite.approximate_member_count,
+    }))
+}
+
+/// Like [`resolve`], but reusing invites resolved within [`CACHE_TTL`].
+async fn resolve_cached(http: &serenity::Http, code: &str) -> Result<Option<InviteTarget>> {
+    let cached = RESOLVED
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .get(code)
+        .filter(|(resolved_at, _)| resolved_at.elapsed() < CACHE_TTL)
+        .map(|(_, target)| target.clone());
+
+    if let Some(target) = cached {
+        return Ok(target);
+    }
+
+    let target = resolve(http, code).await?;
+
+    let mut resolved = RESOLVED.lock().unwrap_or_else(|err| err.into_inner());
+    resolved.retain(|_, (resolved_at, _)| resolved_at.elapsed() < CACHE_TTL);
+    resolved.insert(code.to_owned(), (Instant::now(), target.clone()));
+
+    Ok(target)
+}
+
+#[tracing::instrument(skip_all, fields(message_id = message.id.get()))]
+pub async fn handle(ctx: &serenity::Context, message: &serenity::Message) -> Result<bool> {
+    if message.author.bot() {
+        return Ok(false);
+    }
+
+    let Some(guild_id) = message.guild_id else {
+        return Ok(false);
+    };
+
+    let Some(storage) = &ctx.data::<crate::Data>().storage else {
+        return Ok(false);
+    };
+
+    let guild_config = storage.get_config(guild_id).await?;
+
+    let Some(filter) = guild_config.invite_filter else {
+        return Ok(false);
+    };
+
+    if filter.moderator_bypass
+        && let Some(role) = guild_config.moderator_role
+        && message
+            .member
+            .as_ref()
+            .is_some_and(|member| member.roles.contains(&role))
+    {
+        return Ok(false);
+    }
+
+    let mut codes = invite_codes(&message.content);
+    codes.sort_unstable();
+    codes.dedup();
+
+    let mut blocked = None;
+
+    for code in codes.into_iter().take(MAX_CODES) {
+        // Invites that cannot be resolved are let through rather than holding up other handlers
+        let target = match resolve_cached(&ctx.http, code).await {
+            Ok(target) => target,
+            Err(err) => {
+                tracing::warn!(code, "failed to resolve invite: {err:?}");
+                continue;
+            }
+        };
+
+        if let Some(target) = target
+            && target.guild != guild_id
+            && !filter.allowed_guilds.contains(&target.guild)
+        {
+            blocked = Some(target);
+            break;
+        }
+    }
+
+    let Some(target) = blocked else {
+        return Ok(false);
+    };
+
+    message
+        .delete(&ctx.http, Some("Invite to a server that is not allowed"))
+        .await?;
+
+    tracing::info!(guild = target.guild.get(), "filtered invite");
+
+    if let Some(logs_channel) = guild_config.message_logs_channel {
+        logs_channel
+            .send_message(
+                &ctx.http,
+                serenity::CreateMessage::default()
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new())
+                    .components(vec![serenity::CreateComponent::Container(
+                        serenity::CreateContainer::new(vec![
+                            serenity::CreateContainerComponent::TextDisplay(
+                                serenity::CreateTextDisplay::new(format!(
+                                    "### Invite filtered\n{}",
+                                    utils::serenity::format_mentionable(Some(message.author.id))
+                                )),
+                            ),
+                            serenity::CreateContainerComponent::TextDisplay(
+                                serenity::CreateTextDisplay::new(format!(
+                                    "**Server**\n{} (`{}`) \u{00B7} {} members",
+                                    target.name,
+                                    target.guild,
+                                    target
+                                        .member_count
+                                        .map_or_else(|| "?".to_owned(), |count| count.to_string())
+                                )),
+                            ),
+                            serenity::CreateContainerComponent::TextDisplay(
+                                serenity::CreateTextDisplay::new(format!(
+                                    "**Channel**\n{}",
+                                    utils::serenity::format_mentionable(Some(message.channel_id))
+                                )),
+                            ),
+                            serenity::CreateContainerComponent::TextDisplay(
+                                serenity::CreateTextDisplay::new(format!(
+                                    "**Content**\n{}",
+                                    utils::truncate(&message.content, 1024)
+                                )),
+                            ),
+                            serenity::CreateContainerComponent::TextDisplay(
+                                serenity::CreateTextDisplay::new(format!(
+                                    "-# {}",
+                                    serenity::FormattedTimestamp::now()
+                                )),
+                            ),
+                        ])
+                        .accent_color(0xff6b6b),
+                    )]),
+            )
+            .await?;
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_invite_codes() {
+        assert_eq!(
+            invite_codes("join https://discord.gg/abc-123 or discord.com/invite/XyZ!"),
+            vec!["abc-123", "XyZ"]
+        );
+        assert_eq!(
+            invite_codes("https://discordapp.com/invite/old"),
+            vec!["old"]
+        );
+        assert!(invite_codes("https://discord.com/channels/1/2").is_empty());
+    }
+}
@@ -2,13 +2,25 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
-use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{
+    self as serenity, Mentionable as _,
+    audit_log::{Action, MemberAction},
+};
 
 use eyre::Result;
+use tokio::task;
 
-use crate::{config::GuildConfig, storage::log::MessageLog, utils};
+use crate::{
+    config::{AttachmentLogs, CONFIG, Feature, GuildConfig, MemberChange},
+    handlers::attachment_cache,
+    storage::log::MessageLog,
+    utils,
+};
+
+/// The longest rendered diff shown in edit logs, in characters.
+const MAX_DIFF_LENGTH: usize = 2048;
 
 #[derive(Debug, Clone, Copy)]
 pub struct LogMessageIds {
@@ -44,7 +56,11 @@ async fn is_excluded_message(
         return true;
     }
 
-    if guild_config.logs_excluded_channels.contains(&ids.channel) {
+    if guild_config.logs_excluded_channels.contains(&ids.channel)
+        || !guild_config
+            .features
+            .enabled(Feature::MessageLogs, ids.channel)
+    {
         return true;
     }
 
@@ -80,7 +96,64 @@ pub async fn handle_message(ctx: &serenity::Context, message: &serenity::Message
             return Ok(());
         }
 
-        storage.set_message_log(message.id, &message.into()).await?;
+        let ttl = guild_config.message_log_ttl(CONFIG.get().message_log_retention_limit());
+
+        storage
+            .set_message_log(guild_id, message.id, &MessageLog::from(message), ttl)
+            .await?;
+
+        // Downloading attachments can take a while, so do it off the gateway message path
+        if let Some(settings) = guild_config.attachment_logs
+            && !message.attachments.is_empty()
+        {
+            task::spawn({
+                let ctx = ctx.clone();
+                let message_id = message.id;
+                let attachments = message.attachments.clone();
+
+                async move {
+                    if let Err(err) =
+                        cache_attachments(&ctx, guild_id, message_id, &settings, &attachments, ttl)
+                            .await
+                    {
+                        tracing::warn!(
+                            id = message_id.get(),
+                            "failed to cache attachments: {err:?}"
+                        );
+                    }
+                }
+            });
+        }
+    }
+
+    Ok(())
+}
+
+async fn cache_attachments(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    message_id: serenity::MessageId,
+    settings: &AttachmentLogs,
+    attachments: &[serenity::Attachment],
+    ttl: Duration,
+) -> Result<()> {
+    let hashes = attachment_cache::store(settings, attachments).await;
+
+    if hashes.is_empty() {
+        return Ok(());
+    }
+
+    let data = ctx.data::<crate::Data>();
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    // The message may have been edited or deleted in the meantime
+    if let Some(mut log) = storage.get_message_log(message_id).await? {
+        log.cached_attachments.extend(hashes);
+        storage
+            .set_message_log(guild_id, message_id, &log, ttl)
+            .await?;
     }
 
     Ok(())
@@ -115,6 +188,11 @@ pub async fn edit(
         }
 
         if let Some(logs_channel) = guild_config.message_logs_channel {
+            let (changes, omitted) = utils::diff::render(
+                &utils::diff::diff(prev_content, new_content),
+                MAX_DIFF_LENGTH,
+            );
+
             let mut container = serenity::CreateContainer::new(vec![
                 serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
                     "### Message Edited",
@@ -132,17 +210,29 @@ pub async fn edit(
                     ),
                 )),
                 serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
-                    format!(
-                        "**Previous content**\n{}",
-                        utils::truncate(prev_content, 1024)
-                    ),
-                )),
-                serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
-                    format!("**New content**\n{}", utils::truncate(new_content, 1024)),
+                    format!("**Changes**\n{changes}"),
                 )),
             ])
             .accent_color(0xffd43b);
 
+            // The full contents are attached when the diff does not show all of them
+            let mut files = Vec::new();
+            if omitted {
+                for (filename, content) in
+                    [("before.txt", prev_content), ("after.txt", new_content)]
+                {
+                    files.push(serenity::CreateAttachment::bytes(
+                        content.as_bytes().to_vec(),
+                        filename,
+                    ));
+                    container = container.add_component(serenity::CreateContainerComponent::File(
+                        serenity::CreateFile::new(serenity::CreateUnfurledMediaItem::new(format!(
+                            "attachment://{filename}"
+                        ))),
+                    ));
+                }
+            }
+
             if !attachments.is_empty() {
                 container =
                     container.add_component(serenity::CreateContainerComponent::TextDisplay(
@@ -169,7 +259,8 @@ pub async fn edit(
                         .components(&[
                             serenity::CreateComponent::Container(container),
                             make_link_component(ids.link()),
-                        ]),
+                        ])
+                        .add_files(files),
                 )
                 .await?;
         }
@@ -178,6 +269,40 @@ pub async fn edit(
     Ok(())
 }
 
+/// Show re-uploaded attachments in `container`, with images and videos in a media gallery.
+fn add_reuploads<'a>(
+    mut container: serenity::CreateContainer<'a>,
+    reuploads: &[attachment_cache::Reupload],
+) -> serenity::CreateContainer<'a> {
+    let media = reuploads
+        .iter()
+        .filter(|reupload| reupload.media)
+        .map(|reupload| {
+            serenity::CreateMediaGalleryItem::new(serenity::CreateUnfurledMediaItem::new(format!(
+                "attachment://{}",
+                reupload.filename
+            )))
+        })
+        .collect::<Vec<_>>();
+
+    if !media.is_empty() {
+        container = container.add_component(serenity::CreateContainerComponent::MediaGallery(
+            serenity::CreateMediaGallery::new(media),
+        ));
+    }
+
+    for reupload in reuploads.iter().filter(|reupload| !reupload.media) {
+        container = container.add_component(serenity::CreateContainerComponent::File(
+            serenity::CreateFile::new(serenity::CreateUnfurledMediaItem::new(format!(
+                "attachment://{}",
+                reupload.filename
+            ))),
+        ));
+    }
+
+    container
+}
+
 #[tracing::instrument(skip_all, fields(id = ids.message.get()))]
 pub async fn delete(
     ctx: &serenity::Context,
@@ -227,23 +352,54 @@ pub async fn delete(
                     ));
             }
 
-            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            let reuploads = if let Some(settings) = &guild_config.attachment_logs {
+                attachment_cache::fetch(settings, log).await
+            } else {
+                Vec::new()
+            };
+
+            let footer = serenity::CreateContainerComponent::TextDisplay(
                 serenity::CreateTextDisplay::new(format!(
                     "-# {}",
                     serenity::FormattedTimestamp::new((*timestamp).into(), None),
                 )),
-            ));
+            );
+
+            let message = serenity::CreateMessage::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(serenity::CreateAllowedMentions::new());
+
+            if !reuploads.is_empty() {
+                let result = logs_channel
+                    .send_message(
+                        &ctx.http,
+                        message
+                            .clone()
+                            .components(vec![
+                                serenity::CreateComponent::Container(
+                                    add_reuploads(container.clone(), &reuploads)
+                                        .add_component(footer.clone()),
+                                ),
+                                make_link_component(ids.link()),
+                            ])
+                            .add_files(reuploads.into_iter().map(|reupload| reupload.file)),
+                    )
+                    .await;
+
+                // Fall back to a log without the files, e.g. when they exceed the upload limit
+                match result {
+                    Ok(_) => return Ok(()),
+                    Err(err) => tracing::warn!("failed to re-upload attachments: {err:?}"),
+                }
+            }
 
             logs_channel
                 .send_message(
                     &ctx.http,
-                    serenity::CreateMessage::default()
-                        .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
-                        .allowed_mentions(serenity::CreateAllowedMentions::new())
-                        .components(&[
-                            serenity::CreateComponent::Container(container),
-                            make_link_component(ids.link()),
-                        ]),
+                    message.components(vec![
+                        serenity::CreateComponent::Container(container.add_component(footer)),
+                        make_link_component(ids.link()),
+                    ]),
                 )
                 .await?;
         }
@@ -350,3 +506,195 @@ pub async fn member_leave(
 
     Ok(())
 }
+
+/// The user that performed a member update of `user` in the last few seconds, according to the
+/// audit log. Returns `None` if the audit log cannot be read or has no matching entry.
+async fn member_update_executor(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    user: serenity::UserId,
+    action: MemberAction,
+) -> Option<serenity::UserId> {
+    let audit_logs = guild_id
+        .audit_logs(&ctx.http, Some(Action::Member(action)), None, None, None)
+        .await
+        .ok()?;
+
+    let now = chrono::Utc::now();
+
+    audit_logs
+        .entries
+        .iter()
+        .find(|entry| {
+            entry
+                .target_id
+                .is_some_and(|target| target.get() == user.get())
+                && now - entry.id.created_at().to_utc() < chrono::Duration::seconds(30)
+        })
+        .and_then(|entry| entry.user_id)
+}
+
+fn format_nick(member: &serenity::Member) -> String {
+    member
+        .nick
+        .as_deref()
+        .map_or_else(|| "*None*".to_owned(), |nick| format!("`{nick}`"))
+}
+
+fn format_role_ids(roles: &[&serenity::RoleId]) -> String {
+    roles
+        .iter()
+        .map(|role| role.mention().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Describe the changes from `old` to `new` that are not ignored in `guild_config`, along with
+/// the audit log action that can attribute them to an executor.
+fn member_changes(
+    guild_config: &GuildConfig,
+    old: &serenity::Member,
+    new: &serenity::Member,
+) -> (Vec<String>, Option<MemberAction>) {
+    let logged = |change| !guild_config.member_logs_ignored.contains(&change);
+
+    let mut fields = Vec::new();
+    let mut action = None;
+
+    if logged(MemberChange::Nickname) && old.nick != new.nick {
+        fields.push(format!(
+            "**Nickname**\n{} → {}",
+            format_nick(old),
+            format_nick(new)
+        ));
+        action = Some(MemberAction::Update);
+    }
+
+    if logged(MemberChange::Roles) {
+        let added = new
+            .roles
+            .iter()
+            .filter(|role| !old.roles.contains(role))
+            .collect::<Vec<_>>();
+        let removed = old
+            .roles
+            .iter()
+            .filter(|role| !new.roles.contains(role))
+            .collect::<Vec<_>>();
+
+        if !added.is_empty() {
+            fields.push(format!("**Roles added**\n{}", format_role_ids(&added)));
+        }
+        if !removed.is_empty() {
+            fields.push(format!("**Roles removed**\n{}", format_role_ids(&removed)));
+        }
+
+        if !added.is_empty() || !removed.is_empty() {
+            action = Some(MemberAction::RoleUpdate);
+        }
+    }
+
+    if logged(MemberChange::Avatar) && old.avatar != new.avatar {
+        fields.push(format!(
+            "**Server avatar**\n{} → {}",
+            old.avatar_url().unwrap_or_else(|| "*None*".to_owned()),
+            new.avatar_url().unwrap_or_else(|| "*None*".to_owned())
+        ));
+    }
+
+    if logged(MemberChange::Timeout)
+        && old.communication_disabled_until != new.communication_disabled_until
+    {
+        fields.push(match new.communication_disabled_until {
+            Some(until) if until > serenity::Timestamp::now() => format!(
+                "**Timeout**\nUntil {}",
+                serenity::FormattedTimestamp::new(until, None)
+            ),
+            _ => "**Timeout**\nRemoved".to_owned(),
+        });
+        if action.is_none() {
+            action = Some(MemberAction::Update);
+        }
+    }
+
+    if logged(MemberChange::Pending) && old.pending() != new.pending() {
+        fields.push(format!(
+            "**Membership screening**\n{}",
+            if new.pending() {
+                "Pending"
+            } else {
+                "Completed"
+            }
+        ));
+    }
+
+    (fields, action)
+}
+
+#[tracing::instrument(skip_all, fields(id = new.user.id.get()))]
+pub async fn member_update(
+    ctx: &serenity::Context,
+    old: &serenity::Member,
+    new: &serenity::Member,
+) -> Result<()> {
+    let Some(storage) = &ctx.data::<crate::Data>().storage else {
+        return Ok(());
+    };
+
+    let guild_config = storage.get_config(new.guild_id).await?;
+
+    let Some(logs_channel) = guild_config.member_logs_channel else {
+        return Ok(());
+    };
+
+    let (fields, action) = member_changes(&guild_config, old, new);
+
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    let executor = if let Some(action) = action {
+        member_update_executor(ctx, new.guild_id, new.user.id, action).await
+    } else {
+        None
+    };
+
+    let mut container =
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!(
+                "### Member updated\n{}",
+                utils::serenity::format_mentionable(Some(new.user.id))
+            )),
+        )])
+        .accent_color(0x74c0fc);
+
+    for field in fields {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(field),
+        ));
+    }
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(if let Some(executor) = executor {
+            format!(
+                "-# {} \u{00B7} {}",
+                executor.mention(),
+                serenity::FormattedTimestamp::now()
+            )
+        } else {
+            format!("-# {}", serenity::FormattedTimestamp::now())
+        }),
+    ));
+
+    logs_channel
+        .send_message(
+            &ctx.http,
+            serenity::CreateMessage::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(serenity::CreateAllowedMentions::new())
+                .components(&[serenity::CreateComponent::Container(container)]),
+        )
+        .await?;
+
+    Ok(())
+}
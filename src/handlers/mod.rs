@@ -3,33 +3,204 @@
 // SPDX-License-Identifier: AGPL-3.0-only
 
 use eyre::Result;
-use poise::serenity_prelude as serenity;
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
 
-use crate::analytics;
+use crate::{
+    analytics,
+    config::{Feature, GuildFeatures},
+    utils,
+};
 
+pub mod appeal;
+pub mod attachment_cache;
+pub mod audit_log;
+mod automod;
 mod autoreply;
+pub mod ban_group;
 pub mod code_expansion;
 pub mod config;
 mod dm;
 mod error;
 pub mod intelligence;
+pub mod invite_filter;
 pub mod log;
+pub mod quarantine;
+pub mod raid;
 mod safe_browsing;
+pub mod server_log;
+pub mod spam;
 pub mod starboard;
 
 pub use error::error;
 
+/// Check whether a passive feature is enabled in a channel.
+/// Features are always enabled outside of guilds or when storage is unavailable.
+pub async fn feature_enabled(
+    ctx: &serenity::Context,
+    guild_id: Option<serenity::GuildId>,
+    channel_id: serenity::GenericChannelId,
+    feature: Feature,
+) -> Result<bool> {
+    if let Some(guild_id) = guild_id
+        && let Some(storage) = &ctx.data::<crate::Data>().storage
+    {
+        let guild_config = storage.get_config(guild_id).await?;
+        return Ok(guild_config.features.enabled(feature, channel_id));
+    }
+
+    Ok(true)
+}
+
+/// Alert moderators in the message logs channel about a message that was removed automatically.
+async fn send_alert(
+    ctx: &serenity::Context,
+    message: &serenity::Message,
+    summary: &str,
+    timed_out: bool,
+) -> Result<()> {
+    let Some(guild_id) = message.guild_id else {
+        return Ok(());
+    };
+    let Some(storage) = &ctx.data::<crate::Data>().storage else {
+        return Ok(());
+    };
+
+    let guild_config = storage.get_config(guild_id).await?;
+
+    let Some(logs_channel) = guild_config.message_logs_channel else {
+        return Ok(());
+    };
+
+    let mut components = vec![];
+
+    if let Some(role) = guild_config.moderator_role {
+        components.push(serenity::CreateComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(role.mention().to_string()),
+        ));
+    }
+
+    components.push(serenity::CreateComponent::Container(
+        serenity::CreateContainer::new(vec![
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                summary,
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "**Author**\n{} (*{}*)",
+                    utils::serenity::format_mentionable(Some(message.author.id)),
+                    if timed_out {
+                        "timed out"
+                    } else {
+                        "timeout failed"
+                    }
+                ),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "**Channel**\n{}",
+                    utils::serenity::format_mentionable(Some(message.channel_id))
+                ),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("**Content**\n{}", utils::truncate(&message.content, 1024)),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("-# {}", serenity::FormattedTimestamp::now()),
+            )),
+        ])
+        .accent_color(0xff6b6b),
+    ));
+
+    logs_channel
+        .send_message(
+            &ctx.http,
+            serenity::CreateMessage::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(
+                    serenity::CreateAllowedMentions::new().roles(
+                        guild_config
+                            .moderator_role
+                            .iter()
+                            .copied()
+                            .collect::<Vec<_>>(),
+                    ),
+                )
+                .components(&components),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn run_if(enabled: bool, fut: impl Future<Output = Result<()>>) -> Result<()> {
+    if enabled { fut.await } else { Ok(()) }
+}
+
+/// Run a pre-filter if it is enabled, returning whether it removed the message.
+/// Errors are logged rather than propagated so that the rest of the pipeline still runs.
+async fn filter_if(enabled: bool, fut: impl Future<Output = Result<bool>>) -> bool {
+    if !enabled {
+        return false;
+    }
+
+    fut.await.unwrap_or_else(|err| {
+        tracing::error!("{err:?}");
+        false
+    })
+}
+
 #[tracing::instrument(skip_all, fields(id = message.id.get()))]
 pub async fn message_guild(ctx: &serenity::Context, message: &serenity::Message) -> Result<()> {
-    if safe_browsing::handle(ctx, message).await? {
+    let features = if let Some(guild_id) = message.guild_id
+        && let Some(storage) = &ctx.data::<crate::Data>().storage
+    {
+        storage.get_config(guild_id).await?.features
+    } else {
+        GuildFeatures::default()
+    };
+
+    let enabled = |feature| features.enabled(feature, message.channel_id);
+
+    if filter_if(
+        enabled(Feature::SafeBrowsing),
+        safe_browsing::handle(ctx, message),
+    )
+    .await
+    {
+        return Ok(());
+    }
+
+    if filter_if(enabled(Feature::SpamDetection), spam::handle(ctx, message)).await {
+        return Ok(());
+    }
+
+    if filter_if(
+        enabled(Feature::InviteFilter),
+        invite_filter::handle(ctx, message),
+    )
+    .await
+    {
+        return Ok(());
+    }
+
+    if filter_if(enabled(Feature::Automod), automod::handle(ctx, message)).await {
         return Ok(());
     }
 
     tokio::try_join!(
-        log::handle_message(ctx, message),
-        autoreply::handle(ctx, message),
-        code_expansion::handle_message(ctx, message),
-        intelligence::handle(ctx, message),
+        run_if(
+            enabled(Feature::MessageLogs),
+            log::handle_message(ctx, message)
+        ),
+        run_if(enabled(Feature::Autoreply), autoreply::handle(ctx, message)),
+        run_if(
+            enabled(Feature::CodeExpansion),
+            code_expansion::handle_message(ctx, message)
+        ),
+        run_if(
+            enabled(Feature::Intelligence),
+            intelligence::handle(ctx, message)
+        ),
     )?;
 
     analytics::send_message(message.guild_id).await;
@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+
+use crate::{storage::quarantine::Quarantine, utils};
+
+/// The roles in `roles` that are managed by an integration and cannot be added or removed.
+pub fn managed_roles(
+    guild: &serenity::PartialGuild,
+    roles: &[serenity::RoleId],
+) -> Vec<serenity::RoleId> {
+    roles
+        .iter()
+        .filter(|role| guild.roles.get(role).is_some_and(serenity::Role::managed))
+        .copied()
+        .collect()
+}
+
+/// End the quarantine of `user` in `guild`, restoring the roles saved when it started.
+/// Roles deleted in the meantime are skipped. Returns `None` if the user is not quarantined.
+pub async fn release(
+    http: &serenity::Http,
+    data: &crate::Data,
+    guild: serenity::GuildId,
+    user: serenity::UserId,
+    reason: &str,
+    moderator: Option<serenity::UserId>,
+) -> Result<Option<Quarantine>> {
+    let Some(storage) = &data.storage else {
+        return Ok(None);
+    };
+
+    let Some(quarantine) = storage.get_quarantine(guild, user).await? else {
+        return Ok(None);
+    };
+
+    // Members that left are released without touching their roles
+    if let Ok(member) = guild.member(http, user).await {
+        let partial_guild = guild.to_partial_guild(http).await?;
+
+        let managed = managed_roles(&partial_guild, &member.roles);
+        let roles = quarantine
+            .roles
+            .iter()
+            .filter(|role| partial_guild.roles.contains_key(role) && !managed.contains(role))
+            .chain(&managed)
+            .copied()
+            .collect::<Vec<_>>();
+
+        guild
+            .edit_member(
+                http,
+                user,
+                serenity::EditMember::default()
+                    .roles(roles)
+                    .audit_log_reason(reason),
+            )
+            .await?;
+    }
+
+    storage.del_quarantine(guild, user).await?;
+
+    tracing::info!(guild = guild.get(), user = user.get(), "ended quarantine");
+
+    if let Some(logs_channel) = storage.get_config(guild).await?.moderation_logs_channel {
+        let mut container = serenity::CreateContainer::new(vec![
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!(
+                    "### Unquarantine\n{}",
+                    utils::serenity::format_mentionable(Some(user))
+                ),
+            )),
+            serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+                format!("**Reason**\n{reason}"),
+            )),
+        ])
+        .accent_color(0x9775fa);
+
+        if let Some(case) = quarantine.case {
+            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!("**Case**\n{case}")),
+            ));
+        }
+
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(if let Some(moderator) = moderator {
+                format!(
+                    "-# {} \u{00B7} {}",
+                    moderator.mention(),
+                    serenity::FormattedTimestamp::now()
+                )
+            } else {
+                format!("-# {}", serenity::FormattedTimestamp::now())
+            }),
+        ));
+
+        logs_channel
+            .send_message(
+                http,
+                serenity::CreateMessage::default()
+                    .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                    .allowed_mentions(serenity::CreateAllowedMentions::new())
+                    .components(vec![serenity::CreateComponent::Container(container)]),
+            )
+            .await?;
+    }
+
+    Ok(Some(quarantine))
+}
+
+/// Release quarantined members whose quarantine has expired.
+pub async fn release_expired(http: &serenity::Http, data: &crate::Data) -> Result<()> {
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    let now = chrono::Utc::now();
+
+    for quarantine in storage.scan_quarantines().await? {
+        if quarantine
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= now)
+            && let Err(err) = release(
+                http,
+                data,
+                quarantine.guild,
+                quarantine.user,
+                "Quarantine expired",
+                None,
+            )
+            .await
+        {
+            tracing::error!("{err:?}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Give the quarantine role back to quarantined members who leave and rejoin.
+#[tracing::instrument(skip_all, fields(id = member.user.id.get()))]
+pub async fn member_join(ctx: &serenity::Context, member: &serenity::Member) -> Result<()> {
+    let Some(storage) = &ctx.data::<crate::Data>().storage else {
+        return Ok(());
+    };
+
+    if storage
+        .get_quarantine(member.guild_id, member.user.id)
+        .await?
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    if let Some(role) = storage.get_config(member.guild_id).await?.quarantine_role {
+        member
+            .add_role(&ctx.http, role, Some("Rejoined while quarantined"))
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,332 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{self as serenity, Mentionable as _};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::{config::RaidProtection, storage::lockdown::Lockdown};
+
+/// Recent joins of each guild with their scores, kept in memory for raid detection.
+#[derive(Debug, Default)]
+pub struct JoinTracker {
+    joins: Mutex<HashMap<serenity::GuildId, VecDeque<(chrono::DateTime<chrono::Utc>, u64)>>>,
+}
+
+impl JoinTracker {
+    /// Record a join and return the total score of joins within `window` of it.
+    pub fn record(
+        &self,
+        guild: serenity::GuildId,
+        at: chrono::DateTime<chrono::Utc>,
+        score: u64,
+        window: Duration,
+    ) -> u64 {
+        let mut joins = self.joins.lock().unwrap_or_else(|err| err.into_inner());
+        let guild_joins = joins.entry(guild).or_default();
+
+        let window = chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX);
+        while guild_joins
+            .front()
+            .is_some_and(|(joined_at, _)| *joined_at + window < at)
+        {
+            guild_joins.pop_front();
+        }
+
+        guild_joins.push_back((at, score));
+        guild_joins.iter().map(|(_, score)| score).sum()
+    }
+
+    pub fn clear(&self, guild: serenity::GuildId) {
+        self.joins
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(&guild);
+    }
+}
+
+/// Score a join for raid detection, with suspicious accounts scoring higher.
+fn join_score(user: &serenity::User, min_account_age: Option<Duration>) -> u64 {
+    let account_age = chrono::Utc::now().timestamp() - user.id.created_at().timestamp();
+
+    let mut score = 1;
+
+    if min_account_age.is_some_and(|min| account_age < min.as_secs().cast_signed()) {
+        score += 1;
+    }
+
+    if user.avatar.is_none() {
+        score += 1;
+    }
+
+    score
+}
+
+async fn send_log(
+    http: &serenity::Http,
+    data: &crate::Data,
+    guild: serenity::GuildId,
+    container: serenity::CreateContainer<'_>,
+    ping_moderators: bool,
+) -> Result<()> {
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    let guild_config = storage.get_config(guild).await?;
+
+    let Some(logs_channel) = guild_config.member_logs_channel else {
+        return Ok(());
+    };
+
+    let mut components = Vec::new();
+    let mut allowed_mentions = serenity::CreateAllowedMentions::new();
+
+    if ping_moderators && let Some(role) = guild_config.moderator_role {
+        components.push(serenity::CreateComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(role.mention().to_string()),
+        ));
+        allowed_mentions = allowed_mentions.roles(vec![role]);
+    }
+
+    components.push(serenity::CreateComponent::Container(container));
+
+    logs_channel
+        .send_message(
+            http,
+            serenity::CreateMessage::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(allowed_mentions)
+                .components(components),
+        )
+        .await?;
+
+    Ok(())
+}
+
+async fn start_lockdown(
+    http: &serenity::Http,
+    data: &crate::Data,
+    guild: serenity::GuildId,
+    protection: &RaidProtection,
+    score: u64,
+) -> Result<()> {
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    let now = chrono::Utc::now();
+    let ends_at = protection
+        .lockdown_end(now)
+        .ok_or_else(|| eyre!("raid cool-off is out of range"))?;
+
+    let partial_guild = guild.to_partial_guild(http).await?;
+
+    let lockdown = Lockdown {
+        guild,
+        started_at: now,
+        ends_at,
+        previous_verification_level: partial_guild.verification_level,
+    };
+
+    storage.set_lockdown(&lockdown).await?;
+
+    guild
+        .edit(
+            http,
+            serenity::EditGuild::new()
+                .verification_level(serenity::VerificationLevel::Higher)
+                .audit_log_reason("Raid detected"),
+        )
+        .await?;
+
+    tracing::warn!(guild = guild.get(), score, "started lockdown");
+
+    let container = serenity::CreateContainer::new(vec![
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "### Lockdown\nJoin score reached {score} within {}.",
+            humantime::format_duration(protection.window)
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+            "**Verification level**\nRaised to highest",
+        )),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "**New joins**\n{}",
+            if protection.kick_joins {
+                "Kicked"
+            } else {
+                "Allowed"
+            }
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "**Ends**\n{} unless more members join, or with `/lockdown end`",
+            serenity::FormattedTimestamp::new(
+                lockdown.ends_at.into(),
+                Some(serenity::FormattedTimestampStyle::RelativeTime)
+            )
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "-# {}",
+            serenity::FormattedTimestamp::now()
+        ))),
+    ])
+    .accent_color(0xff6b6b);
+
+    send_log(http, data, guild, container, true).await?;
+
+    Ok(())
+}
+
+/// End the lockdown of `guild`, restoring its previous verification level.
+/// Returns `false` if the guild is not locked down.
+pub async fn end_lockdown(
+    http: &serenity::Http,
+    data: &crate::Data,
+    guild: serenity::GuildId,
+    moderator: Option<serenity::UserId>,
+) -> Result<bool> {
+    let Some(storage) = &data.storage else {
+        return Ok(false);
+    };
+
+    let Some(lockdown) = storage.get_lockdown(guild).await? else {
+        return Ok(false);
+    };
+
+    guild
+        .edit(
+            http,
+            serenity::EditGuild::new()
+                .verification_level(lockdown.previous_verification_level)
+                .audit_log_reason("Lockdown ended"),
+        )
+        .await?;
+
+    storage.del_lockdown(guild).await?;
+    data.join_tracker.clear(guild);
+
+    tracing::info!(guild = guild.get(), "ended lockdown");
+
+    let container = serenity::CreateContainer::new(vec![
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(format!(
+            "### Lockdown ended\nStarted {}",
+            serenity::FormattedTimestamp::new(
+                lockdown.started_at.into(),
+                Some(serenity::FormattedTimestampStyle::RelativeTime)
+            )
+        ))),
+        serenity::CreateContainerComponent::TextDisplay(serenity::CreateTextDisplay::new(
+            if let Some(moderator) = moderator {
+                format!(
+                    "-# {} \u{00B7} {}",
+                    moderator.mention(),
+                    serenity::FormattedTimestamp::now()
+                )
+            } else {
+                format!("-# {}", serenity::FormattedTimestamp::now())
+            },
+        )),
+    ])
+    .accent_color(0x69db7c);
+
+    send_log(http, data, guild, container, false).await?;
+
+    Ok(true)
+}
+
+/// End lockdowns whose cool-off has passed.
+pub async fn end_expired_lockdowns(http: &serenity::Http, data: &crate::Data) -> Result<()> {
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    let now = chrono::Utc::now();
+
+    for lockdown in storage.scan_lockdowns().await? {
+        if lockdown.ends_at <= now
+            && let Err(err) = end_lockdown(http, data, lockdown.guild, None).await
+        {
+            tracing::error!("{err:?}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Track a member join, starting a lockdown if the guild's raid threshold is reached.
+#[tracing::instrument(skip_all, fields(id = member.user.id.get()))]
+pub async fn member_join(ctx: &serenity::Context, member: &serenity::Member) -> Result<()> {
+    let data = ctx.data::<crate::Data>();
+
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    let Some(protection) = storage.get_config(member.guild_id).await?.raid_protection else {
+        return Ok(());
+    };
+
+    if let Some(mut lockdown) = storage.get_lockdown(member.guild_id).await? {
+        if let Some(ends_at) = protection.lockdown_end(chrono::Utc::now()) {
+            lockdown.ends_at = lockdown.ends_at.max(ends_at);
+            storage.set_lockdown(&lockdown).await?;
+        }
+
+        if protection.kick_joins {
+            member
+                .kick(&ctx.http, Some("Joined during a lockdown"))
+                .await?;
+        }
+
+        return Ok(());
+    }
+
+    let score = data.join_tracker.record(
+        member.guild_id,
+        chrono::Utc::now(),
+        join_score(&member.user, protection.min_account_age),
+        protection.window,
+    );
+
+    if score >= protection.threshold {
+        start_lockdown(&ctx.http, &data, member.guild_id, &protection, score).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_scores_expire_after_window() {
+        let tracker = JoinTracker::default();
+        let guild = serenity::GuildId::new(1);
+        let window = Duration::from_mins(1);
+        let start = chrono::Utc::now();
+
+        assert_eq!(tracker.record(guild, start, 1, window), 1);
+        assert_eq!(
+            tracker.record(guild, start + chrono::Duration::seconds(30), 3, window),
+            4
+        );
+        assert_eq!(
+            tracker.record(guild, start + chrono::Duration::seconds(90), 1, window),
+            4
+        );
+
+        assert_eq!(
+            tracker.record(serenity::GuildId::new(2), start, 1, window),
+            1
+        );
+
+        tracker.clear(guild);
+        assert_eq!(tracker.record(guild, start, 1, window), 1);
+    }
+}
@@ -0,0 +1,559 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{
+    self as serenity, Mentionable as _,
+    audit_log::{Action, Change, EmojiAction, StickerAction},
+};
+
+use crate::utils;
+
+/// Turn a permission flag name like `VIEW_CHANNEL` into `View channel`.
+fn humanize(name: &str) -> String {
+    let name = name.replace('_', " ").to_lowercase();
+    let mut chars = name.chars();
+
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+fn format_permissions(permissions: serenity::Permissions) -> String {
+    if permissions.is_empty() {
+        return "*None*".to_owned();
+    }
+
+    permissions
+        .iter_names()
+        .map(|(name, _)| humanize(name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_text(value: Option<&str>) -> String {
+    value.map_or_else(|| "*None*".to_owned(), |value| format!("`{value}`"))
+}
+
+fn format_channel(channel: Option<serenity::GenericChannelId>) -> String {
+    channel.map_or_else(
+        || "*None*".to_owned(),
+        |channel| channel.mention().to_string(),
+    )
+}
+
+/// Push a `**name**` field showing the old and new value to `fields` if the value changed.
+fn diff_field<T: PartialEq>(
+    fields: &mut Vec<String>,
+    name: &str,
+    old: &T,
+    new: &T,
+    format: impl Fn(&T) -> String,
+) {
+    if old != new {
+        fields.push(format!("**{name}**\n{} → {}", format(old), format(new)));
+    }
+}
+
+fn format_overwrite_target(kind: serenity::PermissionOverwriteType) -> Option<String> {
+    match kind {
+        serenity::PermissionOverwriteType::Member(user) => Some(user.mention().to_string()),
+        serenity::PermissionOverwriteType::Role(role) => Some(role.mention().to_string()),
+        _ => None,
+    }
+}
+
+fn overwrite_state(
+    overwrite: Option<&serenity::PermissionOverwrite>,
+    flag: serenity::Permissions,
+) -> &'static str {
+    match overwrite {
+        Some(overwrite) if overwrite.allow.contains(flag) => "allow",
+        Some(overwrite) if overwrite.deny.contains(flag) => "deny",
+        _ => "inherit",
+    }
+}
+
+/// Describe the permission overwrites that differ between `old` and `new`, one field per
+/// role or member, listing each changed permission as a transition between allow, deny,
+/// and inherit.
+fn overwrite_changes(
+    old: &[serenity::PermissionOverwrite],
+    new: &[serenity::PermissionOverwrite],
+) -> Vec<String> {
+    let mut seen = Vec::new();
+    let mut fields = Vec::new();
+
+    for kind in old.iter().chain(new).map(|o| o.kind) {
+        if seen.contains(&kind) {
+            continue;
+        }
+        seen.push(kind);
+
+        let Some(target) = format_overwrite_target(kind) else {
+            continue;
+        };
+
+        let old = old.iter().find(|o| o.kind == kind);
+        let new = new.iter().find(|o| o.kind == kind);
+
+        let flags = [old, new]
+            .into_iter()
+            .flatten()
+            .fold(serenity::Permissions::empty(), |acc, o| {
+                acc | o.allow | o.deny
+            });
+
+        let lines = flags
+            .iter_names()
+            .filter_map(|(name, flag)| {
+                let (before, after) = (overwrite_state(old, flag), overwrite_state(new, flag));
+                (before != after).then(|| format!("`{}`: {before} → {after}", humanize(name)))
+            })
+            .collect::<Vec<_>>();
+
+        if !lines.is_empty() {
+            fields.push(format!(
+                "**Permissions for {target}**\n{}",
+                lines.join("\n")
+            ));
+        }
+    }
+
+    fields
+}
+
+/// Post an entry to the server logs channel of `guild_id`, if one is configured.
+async fn send(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    title: &str,
+    subject: &str,
+    accent_color: u32,
+    fields: Vec<String>,
+    executor: Option<serenity::UserId>,
+) -> Result<()> {
+    let Some(storage) = &ctx.data::<crate::Data>().storage else {
+        return Ok(());
+    };
+
+    let Some(logs_channel) = storage.get_config(guild_id).await?.server_logs_channel else {
+        return Ok(());
+    };
+
+    let mut container =
+        serenity::CreateContainer::new(vec![serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(format!("### {title}\n{subject}")),
+        )])
+        .accent_color(accent_color);
+
+    for field in fields {
+        container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+            serenity::CreateTextDisplay::new(field),
+        ));
+    }
+
+    container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+        serenity::CreateTextDisplay::new(if let Some(executor) = executor {
+            format!(
+                "-# {} \u{00B7} {}",
+                executor.mention(),
+                serenity::FormattedTimestamp::now()
+            )
+        } else {
+            format!("-# {}", serenity::FormattedTimestamp::now())
+        }),
+    ));
+
+    logs_channel
+        .send_message(
+            &ctx.http,
+            serenity::CreateMessage::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(serenity::CreateAllowedMentions::new())
+                .components(vec![serenity::CreateComponent::Container(container)]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all, fields(id = channel.id.get()))]
+pub async fn channel_create(
+    ctx: &serenity::Context,
+    channel: &serenity::GuildChannel,
+) -> Result<()> {
+    let mut fields = vec![format!(
+        "**Name**\n{}",
+        format_text(Some(&*channel.base.name))
+    )];
+
+    if let Some(parent) = channel.parent_id {
+        fields.push(format!("**Category**\n{}", parent.mention()));
+    }
+
+    fields.extend(overwrite_changes(&[], &channel.permission_overwrites));
+
+    send(
+        ctx,
+        channel.guild_id,
+        "Channel created",
+        &utils::serenity::format_mentionable(Some(channel.id.widen())),
+        0x69db7c,
+        fields,
+        None,
+    )
+    .await
+}
+
+/// Log the changes to a channel. Position changes are skipped, since reordering one channel
+/// shifts the positions of many others.
+#[tracing::instrument(skip_all, fields(id = new.id.get()))]
+pub async fn channel_update(
+    ctx: &serenity::Context,
+    old: &serenity::GuildChannel,
+    new: &serenity::GuildChannel,
+) -> Result<()> {
+    let mut fields = Vec::new();
+
+    diff_field(
+        &mut fields,
+        "Name",
+        &old.base.name,
+        &new.base.name,
+        |name| format_text(Some(&**name)),
+    );
+    diff_field(&mut fields, "Topic", &old.topic, &new.topic, |topic| {
+        format_text(topic.as_deref())
+    });
+    diff_field(
+        &mut fields,
+        "Category",
+        &old.parent_id,
+        &new.parent_id,
+        |parent| format_channel(parent.map(|parent| parent.widen())),
+    );
+    diff_field(
+        &mut fields,
+        "Age-restricted",
+        &old.nsfw,
+        &new.nsfw,
+        |nsfw| nsfw.to_string(),
+    );
+
+    fields.extend(overwrite_changes(
+        &old.permission_overwrites,
+        &new.permission_overwrites,
+    ));
+
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    send(
+        ctx,
+        new.guild_id,
+        "Channel updated",
+        &utils::serenity::format_mentionable(Some(new.id.widen())),
+        0xffd43b,
+        fields,
+        None,
+    )
+    .await
+}
+
+#[tracing::instrument(skip_all, fields(id = channel.id.get()))]
+pub async fn channel_delete(
+    ctx: &serenity::Context,
+    channel: &serenity::GuildChannel,
+) -> Result<()> {
+    let mut fields = vec![format!(
+        "**Name**\n{}",
+        format_text(Some(&*channel.base.name))
+    )];
+
+    if let Some(parent) = channel.parent_id {
+        fields.push(format!("**Category**\n{}", parent.mention()));
+    }
+
+    send(
+        ctx,
+        channel.guild_id,
+        "Channel deleted",
+        &format!("`{}`", channel.id),
+        0xff6b6b,
+        fields,
+        None,
+    )
+    .await
+}
+
+#[tracing::instrument(skip_all, fields(id = role.id.get()))]
+pub async fn role_create(ctx: &serenity::Context, role: &serenity::Role) -> Result<()> {
+    send(
+        ctx,
+        role.guild_id,
+        "Role created",
+        &utils::serenity::format_mentionable(Some(role.id)),
+        0x69db7c,
+        vec![
+            format!("**Name**\n{}", format_text(Some(&*role.name))),
+            format!("**Permissions**\n{}", format_permissions(role.permissions)),
+        ],
+        None,
+    )
+    .await
+}
+
+/// Log the changes to a role. Position changes are skipped, like for channels.
+#[tracing::instrument(skip_all, fields(id = new.id.get()))]
+pub async fn role_update(
+    ctx: &serenity::Context,
+    old: &serenity::Role,
+    new: &serenity::Role,
+) -> Result<()> {
+    let mut fields = Vec::new();
+
+    diff_field(&mut fields, "Name", &old.name, &new.name, |name| {
+        format_text(Some(&**name))
+    });
+    diff_field(&mut fields, "Color", &old.colour, &new.colour, |colour| {
+        format!("`#{}`", colour.hex())
+    });
+    diff_field(
+        &mut fields,
+        "Hoisted",
+        &old.hoist(),
+        &new.hoist(),
+        ToString::to_string,
+    );
+    diff_field(
+        &mut fields,
+        "Mentionable",
+        &old.mentionable(),
+        &new.mentionable(),
+        ToString::to_string,
+    );
+
+    let granted = new.permissions - old.permissions;
+    let revoked = old.permissions - new.permissions;
+
+    if !granted.is_empty() {
+        fields.push(format!(
+            "**Permissions granted**\n{}",
+            format_permissions(granted)
+        ));
+    }
+    if !revoked.is_empty() {
+        fields.push(format!(
+            "**Permissions revoked**\n{}",
+            format_permissions(revoked)
+        ));
+    }
+
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    send(
+        ctx,
+        new.guild_id,
+        "Role updated",
+        &utils::serenity::format_mentionable(Some(new.id)),
+        0xffd43b,
+        fields,
+        None,
+    )
+    .await
+}
+
+#[tracing::instrument(skip_all, fields(id = role_id.get()))]
+pub async fn role_delete(
+    ctx: &serenity::Context,
+    guild_id: serenity::GuildId,
+    role_id: serenity::RoleId,
+    role: Option<&serenity::Role>,
+) -> Result<()> {
+    let mut fields = Vec::new();
+
+    if let Some(role) = role {
+        fields.push(format!("**Name**\n{}", format_text(Some(&*role.name))));
+        fields.push(format!(
+            "**Permissions**\n{}",
+            format_permissions(role.permissions)
+        ));
+    }
+
+    send(
+        ctx,
+        guild_id,
+        "Role deleted",
+        &format!("`{role_id}`"),
+        0xff6b6b,
+        fields,
+        None,
+    )
+    .await
+}
+
+#[tracing::instrument(skip_all, fields(id = new.id.get()))]
+pub async fn guild_update(
+    ctx: &serenity::Context,
+    old: &serenity::Guild,
+    new: &serenity::PartialGuild,
+) -> Result<()> {
+    let mut fields = Vec::new();
+
+    diff_field(&mut fields, "Name", &old.name, &new.name, |name| {
+        format_text(Some(&**name))
+    });
+    diff_field(
+        &mut fields,
+        "Description",
+        &old.description,
+        &new.description,
+        |description| format_text(description.as_deref()),
+    );
+    diff_field(
+        &mut fields,
+        "Icon",
+        &old.icon_url(),
+        &new.icon_url(),
+        |icon| icon.clone().unwrap_or_else(|| "*None*".to_owned()),
+    );
+    diff_field(
+        &mut fields,
+        "Owner",
+        &old.owner_id,
+        &new.owner_id,
+        |owner| utils::serenity::format_mentionable(Some(*owner)),
+    );
+    diff_field(
+        &mut fields,
+        "Verification level",
+        &old.verification_level,
+        &new.verification_level,
+        |level| format!("{level:?}"),
+    );
+    diff_field(
+        &mut fields,
+        "Explicit content filter",
+        &old.explicit_content_filter,
+        &new.explicit_content_filter,
+        |filter| format!("{filter:?}"),
+    );
+    diff_field(
+        &mut fields,
+        "Default notifications",
+        &old.default_message_notifications,
+        &new.default_message_notifications,
+        |notifications| format!("{notifications:?}"),
+    );
+    diff_field(
+        &mut fields,
+        "2FA requirement",
+        &old.mfa_level,
+        &new.mfa_level,
+        |level| format!("{level:?}"),
+    );
+    diff_field(
+        &mut fields,
+        "System channel",
+        &old.system_channel_id,
+        &new.system_channel_id,
+        |channel| format_channel(channel.map(|channel| channel.widen())),
+    );
+    diff_field(
+        &mut fields,
+        "Rules channel",
+        &old.rules_channel_id,
+        &new.rules_channel_id,
+        |channel| format_channel(channel.map(|channel| channel.widen())),
+    );
+    diff_field(
+        &mut fields,
+        "Vanity URL",
+        &old.vanity_url_code,
+        &new.vanity_url_code,
+        |code| format_text(code.as_deref()),
+    );
+
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    send(
+        ctx,
+        new.id,
+        "Server updated",
+        &format!("`{}`", new.id),
+        0xffd43b,
+        fields,
+        None,
+    )
+    .await
+}
+
+/// Log emoji and sticker changes from the audit log, which unlike the gateway events for them
+/// include the previous names and who made the change.
+#[tracing::instrument(skip_all, fields(id = entry.id.get(), guild = guild_id.get()))]
+pub async fn handle_audit_log(
+    ctx: &serenity::Context,
+    entry: &serenity::AuditLogEntry,
+    guild_id: serenity::GuildId,
+) -> Result<()> {
+    let (title, accent_color) = match entry.action {
+        Action::Emoji(EmojiAction::Create) => ("Emoji created", 0x69db7c),
+        Action::Emoji(EmojiAction::Update) => ("Emoji updated", 0xffd43b),
+        Action::Emoji(EmojiAction::Delete) => ("Emoji deleted", 0xff6b6b),
+        Action::Sticker(StickerAction::Create) => ("Sticker created", 0x69db7c),
+        Action::Sticker(StickerAction::Update) => ("Sticker updated", 0xffd43b),
+        Action::Sticker(StickerAction::Delete) => ("Sticker deleted", 0xff6b6b),
+        _ => return Ok(()),
+    };
+
+    let fields = entry
+        .changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::Name { old, new } => Some(match (old, new) {
+                (Some(old), Some(new)) => format!(
+                    "**Name**\n{} → {}",
+                    format_text(Some(&**old)),
+                    format_text(Some(&**new))
+                ),
+                (old, new) => format!(
+                    "**Name**\n{}",
+                    format_text(new.as_deref().or(old.as_deref()))
+                ),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    send(
+        ctx,
+        guild_id,
+        title,
+        &entry
+            .target_id
+            .map_or_else(|| "*Unknown*".to_owned(), |target| format!("`{target}`")),
+        accent_color,
+        fields,
+        entry.user_id,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn humanize_works() {
+        assert_eq!(humanize("VIEW_CHANNEL"), "View channel");
+        assert_eq!(humanize("ADMINISTRATOR"), "Administrator");
+        assert_eq!(humanize(""), "");
+    }
+}
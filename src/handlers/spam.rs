@@ -0,0 +1,399 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude as serenity;
+use regex::Regex;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    hash::{DefaultHasher, Hash as _, Hasher as _},
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use crate::config::{self, SpamProtection};
+
+/// How often members without recent messages are dropped from the [`SpamTracker`].
+const SWEEP_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+static CUSTOM_EMOJI_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<a?:\w+:\d+>").unwrap());
+
+/// A message recently sent by a member, kept in memory for spam detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecentMessage {
+    pub id: serenity::MessageId,
+    pub channel: serenity::GenericChannelId,
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// A hash of the content, or `None` if the message has no text.
+    pub content: Option<u64>,
+}
+
+impl RecentMessage {
+    fn new(message: &serenity::Message) -> Self {
+        let content = message.content.trim();
+
+        Self {
+            id: message.id,
+            channel: message.channel_id,
+            at: *message.timestamp,
+            content: (!content.is_empty()).then(|| {
+                let mut hasher = DefaultHasher::new();
+                content.hash(&mut hasher);
+                hasher.finish()
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MemberMessages {
+    window: chrono::TimeDelta,
+    messages: VecDeque<RecentMessage>,
+}
+
+impl MemberMessages {
+    fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.messages
+            .back()
+            .is_none_or(|recent| recent.at + self.window < now)
+    }
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    members: HashMap<(serenity::GuildId, serenity::UserId), MemberMessages>,
+    last_sweep: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Recent messages of each member, kept in memory for spam detection.
+#[derive(Debug, Default)]
+pub struct SpamTracker {
+    state: Mutex<TrackerState>,
+}
+
+impl SpamTracker {
+    /// Record a message and return the member's messages within `window` of it, including itself.
+    /// Members whose messages have all left their window are dropped periodically.
+    pub fn record(
+        &self,
+        guild: serenity::GuildId,
+        user: serenity::UserId,
+        message: RecentMessage,
+        window: Duration,
+    ) -> Vec<RecentMessage> {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+
+        if state
+            .last_sweep
+            .is_none_or(|last_sweep| message.at - last_sweep >= SWEEP_INTERVAL)
+        {
+            state
+                .members
+                .retain(|_, member| !member.is_expired(message.at));
+            state.last_sweep = Some(message.at);
+        }
+
+        let member = state.members.entry((guild, user)).or_default();
+
+        member.window = chrono::TimeDelta::from_std(window).unwrap_or(chrono::TimeDelta::MAX);
+        while member
+            .messages
+            .front()
+            .is_some_and(|recent| recent.at + member.window < message.at)
+        {
+            member.messages.pop_front();
+        }
+
+        member.messages.push_back(message);
+        member.messages.iter().copied().collect()
+    }
+
+    pub fn clear(&self, guild: serenity::GuildId, user: serenity::UserId) {
+        self.state
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .members
+            .remove(&(guild, user));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpamKind {
+    Rate,
+    Duplicates,
+    Mentions,
+    Emoji,
+    Newlines,
+}
+
+impl fmt::Display for SpamKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Rate => "Message rate",
+            Self::Duplicates => "Repeated messages",
+            Self::Mentions => "Mass mentions",
+            Self::Emoji => "Excessive emoji",
+            Self::Newlines => "Excessive newlines",
+        })
+    }
+}
+
+fn count_emoji(content: &str) -> usize {
+    let unicode = content
+        .chars()
+        .filter(|c| matches!(u32::from(*c), 0x1f000..=0x1faff | 0x2600..=0x27bf))
+        .count();
+
+    unicode + CUSTOM_EMOJI_REGEX.find_iter(content).count()
+}
+
+/// Check the latest of `recent` (whose text is `content`) against the limits of `protection`.
+fn detect(
+    protection: &SpamProtection,
+    content: &str,
+    mentions: usize,
+    recent: &[RecentMessage],
+) -> Option<SpamKind> {
+    let exceeds = |count: usize, max: u64| u64::try_from(count).is_ok_and(|count| count > max);
+
+    let latest = recent.last()?;
+
+    if exceeds(mentions, protection.max_mentions) {
+        Some(SpamKind::Mentions)
+    } else if exceeds(count_emoji(content), protection.max_emoji) {
+        Some(SpamKind::Emoji)
+    } else if exceeds(content.matches('\n').count(), protection.max_newlines) {
+        Some(SpamKind::Newlines)
+    } else if latest.content.is_some()
+        && exceeds(
+            recent
+                .iter()
+                .filter(|m| m.content == latest.content)
+                .count(),
+            protection.max_duplicates,
+        )
+    {
+        Some(SpamKind::Duplicates)
+    } else if exceeds(recent.len(), protection.max_messages) {
+        Some(SpamKind::Rate)
+    } else {
+        None
+    }
+}
+
+/// The messages to delete for spam of `kind`, grouped by channel.
+fn offending(
+    kind: SpamKind,
+    recent: &[RecentMessage],
+) -> HashMap<serenity::GenericChannelId, Vec<serenity::MessageId>> {
+    let Some(latest) = recent.last() else {
+        return HashMap::new();
+    };
+
+    let mut channels: HashMap<_, Vec<_>> = HashMap::new();
+
+    for message in recent.iter().filter(|m| match kind {
+        SpamKind::Rate => true,
+        SpamKind::Duplicates => m.content == latest.content,
+        SpamKind::Mentions | SpamKind::Emoji | SpamKind::Newlines => m.id == latest.id,
+    }) {
+        channels
+            .entry(message.channel)
+            .or_default()
+            .push(message.id);
+    }
+
+    channels
+}
+
+#[tracing::instrument(skip_all, fields(message_id = message.id.get()))]
+pub async fn handle(ctx: &serenity::Context, message: &serenity::Message) -> Result<bool> {
+    if message.author.bot() {
+        return Ok(false);
+    }
+
+    let Some(guild_id) = message.guild_id else {
+        return Ok(false);
+    };
+
+    let data = ctx.data::<crate::Data>();
+    let Some(storage) = &data.storage else {
+        return Ok(false);
+    };
+
+    let guild_config = storage.get_config(guild_id).await?;
+
+    let Some(protection) = guild_config.spam_protection else {
+        return Ok(false);
+    };
+
+    if let Some(role) = guild_config.moderator_role
+        && message
+            .member
+            .as_ref()
+            .is_some_and(|member| member.roles.contains(&role))
+    {
+        return Ok(false);
+    }
+
+    let recent = data.spam_tracker.record(
+        guild_id,
+        message.author.id,
+        RecentMessage::new(message),
+        protection.window,
+    );
+
+    let mentions = message.mentions.len()
+        + message.mention_roles.len()
+        + usize::from(message.mention_everyone);
+
+    let Some(kind) = detect(&protection, &message.content, mentions, &recent) else {
+        return Ok(false);
+    };
+
+    data.spam_tracker.clear(guild_id, message.author.id);
+
+    tracing::info!(user = message.author.id.get(), %kind, "detected spam");
+
+    let reason = format!("Spam detected ({kind})");
+
+    for (channel, messages) in offending(kind, &recent) {
+        // Bulk deletes take at most 100 messages at a time
+        for chunk in messages.chunks(100) {
+            let result = if let [id] = chunk {
+                channel.delete_message(&ctx.http, *id, Some(&reason)).await
+            } else {
+                channel
+                    .delete_messages(&ctx.http, chunk, Some(&reason))
+                    .await
+            };
+
+            if let Err(err) = result {
+                tracing::warn!("failed to delete spam messages: {err:?}");
+            }
+        }
+    }
+
+    let timeout = chrono::TimeDelta::from_std(protection.timeout.min(config::MAX_TIMEOUT))?;
+
+    let timed_out = if let Ok(mut member) = message.member(&ctx).await {
+        member
+            .disable_communication_until(&ctx.http, (chrono::Utc::now() + timeout).into())
+            .await
+            .is_ok()
+    } else {
+        false
+    };
+
+    super::send_alert(ctx, message, &format!("### Spam\n{kind}"), timed_out).await?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recent(id: u64, channel: u64, seconds: i64, content: Option<u64>) -> RecentMessage {
+        RecentMessage {
+            id: serenity::MessageId::new(id),
+            channel: serenity::GenericChannelId::new(channel),
+            at: chrono::DateTime::UNIX_EPOCH + chrono::Duration::seconds(seconds),
+            content,
+        }
+    }
+
+    #[test]
+    fn detects_spam() {
+        let protection = SpamProtection::default();
+
+        let single = [recent(1, 1, 0, Some(1))];
+        assert_eq!(detect(&protection, "hello", 0, &single), None);
+        assert_eq!(
+            detect(&protection, "hello", 9, &single),
+            Some(SpamKind::Mentions)
+        );
+        assert_eq!(
+            detect(&protection, &"\u{1f41f}".repeat(21), 0, &single),
+            Some(SpamKind::Emoji)
+        );
+        assert_eq!(
+            detect(&protection, &"a\n".repeat(31), 0, &single),
+            Some(SpamKind::Newlines)
+        );
+
+        let duplicates = [
+            recent(1, 1, 0, Some(1)),
+            recent(2, 2, 1, Some(1)),
+            recent(3, 3, 2, Some(1)),
+        ];
+        assert_eq!(
+            detect(&protection, "hello", 0, &duplicates),
+            Some(SpamKind::Duplicates)
+        );
+        assert_eq!(offending(SpamKind::Duplicates, &duplicates).len(), 3);
+
+        let rate = (0..9)
+            .map(|i| recent(i, 1, i.cast_signed(), Some(i)))
+            .collect::<Vec<_>>();
+        assert_eq!(detect(&protection, "hello", 0, &rate), Some(SpamKind::Rate));
+        assert_eq!(
+            offending(SpamKind::Rate, &rate)[&serenity::GenericChannelId::new(1)].len(),
+            9
+        );
+    }
+
+    #[test]
+    fn messages_expire_after_window() {
+        let tracker = SpamTracker::default();
+        let (guild, user) = (serenity::GuildId::new(1), serenity::UserId::new(1));
+        let window = Duration::from_secs(10);
+
+        assert_eq!(
+            tracker
+                .record(guild, user, recent(1, 1, 0, None), window)
+                .len(),
+            1
+        );
+        assert_eq!(
+            tracker
+                .record(guild, user, recent(2, 1, 5, None), window)
+                .len(),
+            2
+        );
+        assert_eq!(
+            tracker
+                .record(guild, user, recent(3, 1, 15, None), window)
+                .len(),
+            2
+        );
+
+        tracker.clear(guild, user);
+        assert_eq!(
+            tracker
+                .record(guild, user, recent(4, 1, 15, None), window)
+                .len(),
+            1
+        );
+
+        // Other members are dropped once their messages have left the window
+        tracker.record(
+            guild,
+            serenity::UserId::new(2),
+            recent(5, 1, 120, None),
+            window,
+        );
+        assert_eq!(
+            tracker
+                .state
+                .lock()
+                .unwrap()
+                .members
+                .keys()
+                .collect::<Vec<_>>(),
+            vec![&(guild, serenity::UserId::new(2))]
+        );
+    }
+}
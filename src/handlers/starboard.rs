@@ -8,7 +8,7 @@ use chrono::TimeDelta;
 use eyre::{Result, eyre};
 use poise::serenity_prelude::{self as serenity, Mentionable as _};
 
-use crate::config::GuildConfig;
+use crate::config::{Feature, GuildConfig};
 
 async fn get_starboard_channel(
     ctx: &serenity::Context,
@@ -254,6 +254,13 @@ pub async fn handle(
     {
         let guild_config = storage.get_config(guild_id).await?;
 
+        if !guild_config
+            .features
+            .enabled(Feature::Starboard, message.channel_id)
+        {
+            return Ok(());
+        }
+
         if let Some(starboard) =
             get_starboard_channel(ctx, &guild_config, message.channel_id, guild_id).await?
         {
@@ -31,20 +31,33 @@ mod utils;
 pub struct Data {
     storage: Option<Storage>,
     safe_browsing: Option<SafeBrowsing>,
+    join_tracker: handlers::raid::JoinTracker,
+    spam_tracker: handlers::spam::SpamTracker,
 }
 
 impl Data {
     async fn new() -> Result<Self> {
-        let storage = if let Some(url) = &CONFIG.redis_url {
+        let config = CONFIG.get();
+
+        let storage = if let Some(url) = &config.redis_url {
             let client = redis::Client::open(url.clone())?;
             let storage = Storage::redis(client).await?;
             Some(storage)
+        } else if let Some(path) = &config.sqlite_path {
+            let storage = Storage::sqlite(path)?;
+            Some(storage)
         } else {
-            tracing::warn!("REDIS_URL is not configured, some features will be disabled");
+            tracing::warn!(
+                "neither REDIS_URL nor SQLITE_PATH is configured, some features will be disabled"
+            );
             None
         };
 
-        let safe_browsing = CONFIG.safe_browsing_api_key.as_ref().map_or_else(
+        if let Some(storage) = &storage {
+            storage::migrations::run(&**storage, config.migrations_dry_run).await?;
+        }
+
+        let safe_browsing = config.safe_browsing_api_key.as_ref().map_or_else(
             || {
                 tracing::warn!(
                     "SAFE_BROWSING_API_KEY is not configured, Safe Browsing will be disabled"
@@ -57,12 +70,40 @@ impl Data {
         Ok(Self {
             storage,
             safe_browsing,
+            join_tracker: handlers::raid::JoinTracker::default(),
+            spam_tracker: handlers::spam::SpamTracker::default(),
         })
     }
 }
 
 pub type Context<'a> = poise::Context<'a, Data, Report>;
 
+/// Check owners against the live config rather than the set captured by the framework on startup,
+/// so that changes to `OWNERS` take effect when the config is reloaded.
+fn owner_check(ctx: Context<'_>) -> poise::BoxFuture<'_, Result<bool>> {
+    Box::pin(async move {
+        let user = ctx.author().id;
+
+        Ok(ctx.framework().options().owners.contains(&user)
+            || CONFIG
+                .get()
+                .owners
+                .as_ref()
+                .is_some_and(|owners| owners.contains(&user)))
+    })
+}
+
+fn use_live_owner_checks(commands: &mut [poise::Command<Data, Report>]) {
+    for command in commands {
+        if command.owners_only {
+            command.owners_only = false;
+            command.checks.push(owner_check);
+        }
+
+        use_live_owner_checks(&mut command.subcommands);
+    }
+}
+
 async fn shutdown() {
     let (shutdown_tx, mut shutdown_rx) = mpsc::unbounded_channel();
 
@@ -128,18 +169,20 @@ async fn main() -> Result<()> {
         safe_browsing.update().await?;
     }
 
+    let mut commands = commands::all();
+    use_live_owner_checks(&mut commands);
+
     let mut client = serenity::Client::builder(
-        CONFIG.discord_token.parse()?,
+        CONFIG.get().discord_token.parse()?,
         serenity::GatewayIntents::non_privileged()
             | serenity::GatewayIntents::GUILD_MEMBERS
             | serenity::GatewayIntents::MESSAGE_CONTENT,
     )
     .event_handler(Arc::new(EventHandler))
     .framework(Box::new(Framework::new(FrameworkOptions {
-        commands: commands::all(),
+        commands,
         post_command: |ctx| Box::pin(analytics::send_command(ctx)),
         on_error: |err| Box::pin(handlers::error(err)),
-        owners: CONFIG.owners.clone().unwrap_or_default(),
         allowed_mentions: Some(serenity::CreateAllowedMentions::new().replied_user(true)),
         prefix_options: PrefixFrameworkOptions {
             mention_as_prefix: false,
@@ -2,7 +2,10 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
-use poise::serenity_prelude::{EditRole, GuildId, GuildPagination, Http, RoleId};
+use poise::serenity_prelude::{
+    CreateComponent, CreateContainer, CreateContainerComponent, CreateMessage, CreateTextDisplay,
+    EditRole, GuildId, GuildPagination, Http, HttpError, JsonErrorCode, MessageFlags, RoleId,
+};
 
 use std::{collections::HashSet, sync::Arc, time::Duration};
 use tokio::{task::JoinSet, time};
@@ -11,7 +14,13 @@ use chrono::{NaiveTime, TimeDelta, Timelike, Utc};
 use eyre::{Result, eyre};
 use tracing::Instrument as _;
 
-use crate::Data;
+use crate::{
+    Data,
+    commands::actions,
+    config::{self, CONFIG},
+    handlers,
+    storage::temp_ban::{self, TempBan},
+};
 
 pub async fn rotate_color_role(
     http: &Http,
@@ -77,6 +86,90 @@ pub async fn rotate_color_roles_global(http: &Http, data: &Data) -> Result<()> {
     Ok(())
 }
 
+/// Lift a temporary ban, removing it from the schedule once the user is unbanned or was already
+/// unbanned. Other failures keep it scheduled so that it is retried.
+async fn lift_temp_ban(http: &Http, data: &Data, temp_ban: &TempBan) -> Result<()> {
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    if let Err(err) = actions::unban(
+        http,
+        data,
+        temp_ban.guild,
+        temp_ban.user,
+        "Temporary ban expired",
+        None,
+        temp_ban.case,
+    )
+    .await
+        && !matches!(
+            err.root_cause().downcast_ref::<HttpError>(),
+            Some(HttpError::UnsuccessfulRequest(response))
+                if response.error.code == JsonErrorCode::UnknownBan
+        )
+    {
+        tracing::warn!(
+            guild = ?temp_ban.guild,
+            user = ?temp_ban.user,
+            "failed to lift temporary ban, retrying later: {err:?}"
+        );
+
+        return Ok(());
+    }
+
+    storage.del_temp_ban(temp_ban).await?;
+
+    Ok(())
+}
+
+pub async fn lift_temp_bans(http: &Http, data: &Data) -> Result<()> {
+    let Some(storage) = &data.storage else {
+        return Ok(());
+    };
+
+    for temp_ban in temp_ban::expired(&**storage).await? {
+        if let Err(err) = lift_temp_ban(http, data, &temp_ban).await {
+            tracing::error!("{err:?}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn reload_config(http: &Http) {
+    match CONFIG.reload() {
+        Ok(true) => tracing::info!("reloaded config"),
+        Ok(false) => tracing::debug!("config is unchanged"),
+        Err(err) => {
+            tracing::error!("failed to reload config, keeping previous config: {err:?}");
+
+            let config = CONFIG.get();
+
+            if let Some(channel) = config.error_logs_channel
+                && let Err(err) = channel
+                    .send_message(
+                        http,
+                        CreateMessage::default()
+                            .flags(MessageFlags::IS_COMPONENTS_V2)
+                            .components(&[CreateComponent::Container(
+                                CreateContainer::new(&[CreateContainerComponent::TextDisplay(
+                                    CreateTextDisplay::new(format!(
+                                        "### Failed to reload config\n```\n{}\n```\n-# The previous config will continue to be used.",
+                                        config.redact(&err.to_string())
+                                    )),
+                                )])
+                                .accent_color(0xff6b6b),
+                            )]),
+                    )
+                    .await
+            {
+                tracing::error!("{err:?}");
+            }
+        }
+    }
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn run(http: Arc<Http>, data: Arc<Data>) -> Result<()> {
     let mut tasks: JoinSet<Result<()>> = JoinSet::new();
@@ -137,6 +230,101 @@ pub async fn run(http: Arc<Http>, data: Arc<Data>) -> Result<()> {
         .instrument(tracing::trace_span!("safe_browsing"))
     });
 
+    tasks.spawn({
+        let http = Arc::clone(&http);
+        let data = Arc::clone(&data);
+
+        async move {
+            loop {
+                if let Err(err) = lift_temp_bans(&http, &data).await {
+                    tracing::error!("{err:?}");
+                }
+
+                time::sleep(Duration::from_secs(30)).await;
+            }
+        }
+        .instrument(tracing::trace_span!("lift_temp_bans"))
+    });
+
+    tasks.spawn({
+        let http = Arc::clone(&http);
+        let data = Arc::clone(&data);
+
+        async move {
+            loop {
+                if let Err(err) = handlers::raid::end_expired_lockdowns(&http, &data).await {
+                    tracing::error!("{err:?}");
+                }
+
+                time::sleep(Duration::from_secs(30)).await;
+            }
+        }
+        .instrument(tracing::trace_span!("end_lockdowns"))
+    });
+
+    tasks.spawn({
+        let http = Arc::clone(&http);
+        let data = Arc::clone(&data);
+
+        async move {
+            loop {
+                if let Err(err) = handlers::quarantine::release_expired(&http, &data).await {
+                    tracing::error!("{err:?}");
+                }
+
+                time::sleep(Duration::from_secs(30)).await;
+            }
+        }
+        .instrument(tracing::trace_span!("release_quarantines"))
+    });
+
+    tasks.spawn(
+        async move {
+            loop {
+                if let Err(err) = handlers::attachment_cache::prune().await {
+                    tracing::error!("{err:?}");
+                }
+
+                time::sleep(Duration::from_hours(1)).await;
+            }
+        }
+        .instrument(tracing::trace_span!("prune_attachment_cache")),
+    );
+
+    tasks.spawn({
+        let http = Arc::clone(&http);
+
+        async move {
+            #[cfg(unix)]
+            let mut hangup =
+                tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+
+            let mut modified = config::config_file_modified();
+
+            loop {
+                #[cfg(unix)]
+                let signal = hangup.recv();
+                #[cfg(not(unix))]
+                let signal = std::future::pending::<Option<()>>();
+
+                tokio::select! {
+                    Some(()) = signal => {
+                        tracing::debug!("received SIGHUP");
+                    }
+                    () = time::sleep(Duration::from_secs(5)) => {
+                        if config::config_file_modified() == modified {
+                            continue;
+                        }
+                    }
+                }
+
+                modified = config::config_file_modified();
+                reload_config(&http).await;
+            }
+        }
+        .instrument(tracing::trace_span!("reload_config"))
+    });
+
     while let Some(result) = tasks.join_next().await {
         () = result??;
     }
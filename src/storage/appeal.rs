@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, time::Duration};
+
+/// The time a user has to wait between ban appeals if the guild does not configure one.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_hours(24 * 7);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AppealStatus {
+    #[default]
+    Pending,
+    Accepted,
+    Denied,
+}
+
+impl fmt::Display for AppealStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pending => "Pending",
+            Self::Accepted => "Accepted",
+            Self::Denied => "Denied",
+        })
+    }
+}
+
+/// The latest ban appeal of a user in a guild.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Appeal {
+    pub content: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub status: AppealStatus,
+    /// The moderation case of the appealed ban.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub case: Option<u64>,
+}
+
+impl Appeal {
+    pub fn new(content: String, case: Option<u64>) -> Self {
+        Self {
+            content,
+            timestamp: chrono::Utc::now(),
+            status: AppealStatus::Pending,
+            case,
+        }
+    }
+
+    /// When the user may submit another appeal, or `None` if they already can.
+    /// Pending appeals are handled separately, since they block new appeals until resolved.
+    pub fn cooldown_ends(&self, cooldown: Duration) -> Option<chrono::DateTime<chrono::Utc>> {
+        let ends = self.timestamp + chrono::Duration::from_std(cooldown).ok()?;
+        (chrono::Utc::now() < ends).then_some(ends)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cooldown_ends() {
+        let cooldown = Duration::from_hours(24);
+
+        let recent = Appeal {
+            status: AppealStatus::Denied,
+            ..Appeal::new("please".to_owned(), Some(1))
+        };
+        assert!(recent.cooldown_ends(cooldown).is_some());
+
+        let old = Appeal {
+            timestamp: chrono::Utc::now() - chrono::Duration::days(2),
+            ..recent
+        };
+        assert_eq!(old.cooldown_ends(cooldown), None);
+    }
+}
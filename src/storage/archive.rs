@@ -0,0 +1,806 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, bail};
+use poise::serenity_prelude::{GuildId, RoleId, UserId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{
+    StorageBackend,
+    appeal::{Appeal, AppealStatus},
+    automod::AutomodRule,
+    case::ModerationCase,
+    config_history::{self, ConfigHistoryEntry},
+    keys,
+    note::Note,
+    quarantine::Quarantine,
+    temp_ban::TempBan,
+    warn::Warn,
+};
+use crate::config::{ConfigChange, GuildConfig};
+
+/// The current version of the guild archive format.
+pub const ARCHIVE_VERSION: u64 = 5;
+
+/// A portable snapshot of all guild-scoped data.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GuildArchive {
+    pub version: u64,
+    pub guild: GuildId,
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+
+    pub config: GuildConfig,
+    #[serde(default)]
+    pub autoreply: BTreeMap<String, String>,
+    #[serde(default)]
+    pub warns: Vec<ArchivedWarn>,
+    #[serde(default)]
+    pub cases: Vec<ModerationCase>,
+    /// The last case ID assigned in the guild, which can be higher than that of any case that
+    /// still exists.
+    #[serde(default)]
+    pub case_id: u64,
+    #[serde(default)]
+    pub automod_rules: Vec<AutomodRule>,
+    #[serde(default)]
+    pub notes: Vec<ArchivedNote>,
+    #[serde(default)]
+    pub temp_bans: Vec<ArchivedTempBan>,
+    #[serde(default)]
+    pub quarantines: Vec<ArchivedQuarantine>,
+    /// The latest ban appeal of each user, which also determines their appeal cooldown.
+    #[serde(default)]
+    pub appeals: Vec<ArchivedAppeal>,
+
+    /// Warn counters from version 1 archives, converted to `warns` when parsing.
+    #[serde(default, skip_serializing)]
+    warn_counts: Vec<WarnCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ArchivedWarn {
+    pub user: UserId,
+    pub id: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub case: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ArchivedNote {
+    pub user: UserId,
+    pub id: String,
+    pub author: UserId,
+    pub content: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ArchivedTempBan {
+    pub user: UserId,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub case: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ArchivedQuarantine {
+    pub user: UserId,
+    pub roles: Vec<RoleId>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub case: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ArchivedAppeal {
+    pub user: UserId,
+    pub content: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub status: AppealStatus,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub case: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct WarnCount {
+    user: UserId,
+    count: u64,
+}
+
+#[derive(poise::ChoiceParameter, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ArchiveFormat {
+    #[name = "JSON"]
+    Json,
+    #[name = "TOML"]
+    Toml,
+}
+
+impl ArchiveFormat {
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let extension = filename.rsplit_once('.')?.1;
+
+        if extension.eq_ignore_ascii_case("json") {
+            Some(Self::Json)
+        } else if extension.eq_ignore_ascii_case("toml") {
+            Some(Self::Toml)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+        }
+    }
+}
+
+/// The differences between the stored guild data and an archive.
+#[derive(Debug, Clone)]
+pub struct ArchiveDiff {
+    pub config: Vec<ConfigChange>,
+    pub autoreply: (usize, usize),
+    pub warns: (usize, usize),
+    pub cases: (usize, usize),
+    pub automod_rules: (usize, usize),
+    pub notes: (usize, usize),
+    pub temp_bans: (usize, usize),
+    pub quarantines: (usize, usize),
+    pub appeals: (usize, usize),
+}
+
+impl GuildArchive {
+    /// Collect all guild-scoped data for `guild` from storage.
+    pub async fn collect(storage: &dyn StorageBackend, guild: GuildId) -> Result<Self> {
+        let mut warns = Vec::new();
+
+        for user in storage.scan_warned_users(guild).await? {
+            warns.extend(
+                storage
+                    .scan_warns(user, guild)
+                    .await?
+                    .into_iter()
+                    .map(|warn| ArchivedWarn {
+                        user,
+                        id: warn.id,
+                        timestamp: warn.timestamp,
+                        case: warn.case,
+                    }),
+            );
+        }
+
+        warns.sort_by_key(|warn| (warn.timestamp, warn.user));
+
+        let mut notes = Vec::new();
+
+        for user in storage.scan_noted_users(guild).await? {
+            notes.extend(
+                storage
+                    .scan_notes(user, guild)
+                    .await?
+                    .into_iter()
+                    .map(|note| ArchivedNote {
+                        user,
+                        id: note.id,
+                        author: note.author,
+                        content: note.content,
+                        timestamp: note.timestamp,
+                    }),
+            );
+        }
+
+        notes.sort_by_key(|note| (note.timestamp, note.user));
+
+        let mut cases = storage.scan_cases(guild).await?;
+        cases.sort_by_key(|case| case.id);
+
+        let temp_bans = scan_temp_bans(storage, guild)
+            .await?
+            .into_iter()
+            .map(|temp_ban| ArchivedTempBan {
+                user: temp_ban.user,
+                expires_at: temp_ban.expires_at,
+                case: temp_ban.case,
+            })
+            .collect();
+
+        let mut quarantines = scan_quarantines(storage, guild)
+            .await?
+            .into_iter()
+            .map(|quarantine| ArchivedQuarantine {
+                user: quarantine.user,
+                roles: quarantine.roles,
+                started_at: quarantine.started_at,
+                expires_at: quarantine.expires_at,
+                case: quarantine.case,
+            })
+            .collect::<Vec<_>>();
+        quarantines.sort_by_key(|quarantine| (quarantine.started_at, quarantine.user));
+
+        let mut appeals = scan_appeals(storage, guild)
+            .await?
+            .into_iter()
+            .map(|(user, appeal)| ArchivedAppeal {
+                user,
+                content: appeal.content,
+                timestamp: appeal.timestamp,
+                status: appeal.status,
+                case: appeal.case,
+            })
+            .collect::<Vec<_>>();
+        appeals.sort_by_key(|appeal| (appeal.timestamp, appeal.user));
+
+        Ok(Self {
+            version: ARCHIVE_VERSION,
+            guild,
+            exported_at: chrono::Utc::now(),
+            config: storage.get_config(guild).await?,
+            autoreply: storage.scan_autoreply(guild).await?.into_iter().collect(),
+            warns,
+            cases,
+            case_id: storage.get_case_id(guild).await?,
+            automod_rules: storage.scan_automod_rules(guild).await?,
+            notes,
+            temp_bans,
+            quarantines,
+            appeals,
+            warn_counts: Vec::new(),
+        })
+    }
+
+    pub fn serialize(&self, format: ArchiveFormat) -> Result<String> {
+        Ok(match format {
+            ArchiveFormat::Json => serde_json::to_string_pretty(self)?,
+            ArchiveFormat::Toml => toml::to_string_pretty(self)?,
+        })
+    }
+
+    /// Parse and validate an archive, upgrading older versions to the current one.
+    /// Unknown fields anywhere in the archive are rejected.
+    pub fn parse(data: &str, format: ArchiveFormat) -> Result<Self> {
+        let mut archive: Self = match format {
+            ArchiveFormat::Json => serde_json::from_str(data)?,
+            ArchiveFormat::Toml => toml::from_str(data)?,
+        };
+
+        match archive.version {
+            // Version 1 only stored warn counters, so they are converted into warns issued at export time
+            1 => {
+                let exported_at = archive.exported_at;
+
+                archive.warns = archive
+                    .warn_counts
+                    .drain(..)
+                    .flat_map(|WarnCount { user, count }| {
+                        (0..count).map(move |idx| ArchivedWarn {
+                            user,
+                            id: format!("legacy-{idx}"),
+                            timestamp: exported_at,
+                            case: None,
+                        })
+                    })
+                    .collect();
+
+                archive.version = ARCHIVE_VERSION;
+            }
+            // Later versions only add fields, which default to being empty
+            2..ARCHIVE_VERSION if archive.warn_counts.is_empty() => {
+                archive.version = ARCHIVE_VERSION;
+            }
+            ARCHIVE_VERSION if archive.warn_counts.is_empty() => {}
+            2..=ARCHIVE_VERSION => bail!("`warn_counts` is only supported in version 1 archives"),
+            version => bail!("unsupported archive version {version} (expected {ARCHIVE_VERSION})"),
+        }
+
+        if let Some(protection) = &archive.config.raid_protection
+            && protection.lockdown_end(chrono::Utc::now()).is_none()
+        {
+            bail!("raid protection cool-off is out of range");
+        }
+
+        Ok(archive)
+    }
+
+    pub async fn diff(&self, storage: &dyn StorageBackend, guild: GuildId) -> Result<ArchiveDiff> {
+        let current = storage.get_config(guild).await?;
+
+        Ok(ArchiveDiff {
+            config: current.diff(&self.config)?,
+            autoreply: (
+                storage.scan_autoreply(guild).await?.len(),
+                self.autoreply.len(),
+            ),
+            warns: (count_warns(storage, guild).await?, self.warns.len()),
+            cases: (storage.scan_cases(guild).await?.len(), self.cases.len()),
+            automod_rules: (
+                storage.scan_automod_rules(guild).await?.len(),
+                self.automod_rules.len(),
+            ),
+            notes: (count_notes(storage, guild).await?, self.notes.len()),
+            temp_bans: (
+                scan_temp_bans(storage, guild).await?.len(),
+                self.temp_bans.len(),
+            ),
+            quarantines: (
+                scan_quarantines(storage, guild).await?.len(),
+                self.quarantines.len(),
+            ),
+            appeals: (
+                scan_appeals(storage, guild).await?.len(),
+                self.appeals.len(),
+            ),
+        })
+    }
+
+    /// Replace all guild-scoped data for `guild` with the contents of this archive,
+    /// returning the configuration history entry if the configuration changed.
+    pub async fn apply(
+        &self,
+        storage: &dyn StorageBackend,
+        guild: GuildId,
+        actor: UserId,
+    ) -> Result<Option<ConfigHistoryEntry>> {
+        let entry = config_history::record(storage, guild, actor, &self.config).await?;
+
+        storage.delall_autoreply(guild).await?;
+        for (keyword, reply) in &self.autoreply {
+            storage.add_autoreply(guild, keyword, reply).await?;
+        }
+
+        for user in storage.scan_warned_users(guild).await? {
+            storage.del_warns(user, guild).await?;
+        }
+        for warn in &self.warns {
+            storage
+                .add_warn(
+                    warn.user,
+                    guild,
+                    &Warn {
+                        id: warn.id.clone(),
+                        timestamp: warn.timestamp,
+                        case: warn.case,
+                    },
+                )
+                .await?;
+        }
+
+        for case in storage.scan_cases(guild).await? {
+            storage.del_case(guild, case.id).await?;
+        }
+        for case in &self.cases {
+            storage.set_case(guild, case).await?;
+        }
+
+        // New cases must not reuse the ID of an imported case or one referenced by a warn
+        let case_id = self
+            .cases
+            .iter()
+            .map(|case| case.id)
+            .chain(self.warns.iter().filter_map(|warn| warn.case))
+            .fold(self.case_id, u64::max);
+        storage.set_case_id(guild, case_id).await?;
+
+        for rule in storage.scan_automod_rules(guild).await? {
+            storage.del_automod_rule(guild, &rule.id).await?;
+        }
+        for rule in &self.automod_rules {
+            storage.set_automod_rule(guild, rule).await?;
+        }
+
+        for user in storage.scan_noted_users(guild).await? {
+            for note in storage.scan_notes(user, guild).await? {
+                storage.del_note(user, guild, &note.id).await?;
+            }
+        }
+        for note in &self.notes {
+            storage
+                .add_note(
+                    note.user,
+                    guild,
+                    &Note {
+                        id: note.id.clone(),
+                        author: note.author,
+                        content: note.content.clone(),
+                        timestamp: note.timestamp,
+                    },
+                )
+                .await?;
+        }
+
+        for temp_ban in scan_temp_bans(storage, guild).await? {
+            storage.del_temp_ban(&temp_ban).await?;
+        }
+        for temp_ban in &self.temp_bans {
+            storage
+                .add_temp_ban(&TempBan {
+                    guild,
+                    user: temp_ban.user,
+                    expires_at: temp_ban.expires_at,
+                    case: temp_ban.case,
+                })
+                .await?;
+        }
+
+        for quarantine in scan_quarantines(storage, guild).await? {
+            storage.del_quarantine(guild, quarantine.user).await?;
+        }
+        for quarantine in &self.quarantines {
+            storage
+                .set_quarantine(&Quarantine {
+                    guild,
+                    user: quarantine.user,
+                    roles: quarantine.roles.clone(),
+                    started_at: quarantine.started_at,
+                    expires_at: quarantine.expires_at,
+                    case: quarantine.case,
+                })
+                .await?;
+        }
+
+        storage
+            .del_keys(&storage.scan_prefix(&appeal_prefix(guild)).await?)
+            .await?;
+        for appeal in &self.appeals {
+            storage
+                .set_appeal(
+                    guild,
+                    appeal.user,
+                    &Appeal {
+                        content: appeal.content.clone(),
+                        timestamp: appeal.timestamp,
+                        status: appeal.status,
+                        case: appeal.case,
+                    },
+                )
+                .await?;
+        }
+
+        Ok(entry)
+    }
+}
+
+/// The prefix of the keys of all ban appeals in `guild`, which are followed by the user ID.
+fn appeal_prefix(guild: GuildId) -> String {
+    format!("{}:u", keys::APPEALS.guild(guild))
+}
+
+async fn scan_appeals(
+    storage: &dyn StorageBackend,
+    guild: GuildId,
+) -> Result<Vec<(UserId, Appeal)>> {
+    let prefix = appeal_prefix(guild);
+    let mut appeals = Vec::new();
+
+    for key in storage.scan_prefix(&prefix).await? {
+        let Some(user) = key.strip_prefix(&prefix).and_then(|user| user.parse().ok()) else {
+            continue;
+        };
+
+        if let Some(appeal) = storage.get_appeal(guild, user).await? {
+            appeals.push((user, appeal));
+        }
+    }
+
+    Ok(appeals)
+}
+
+async fn scan_quarantines(storage: &dyn StorageBackend, guild: GuildId) -> Result<Vec<Quarantine>> {
+    Ok(storage
+        .scan_quarantines()
+        .await?
+        .into_iter()
+        .filter(|quarantine| quarantine.guild == guild)
+        .collect())
+}
+
+async fn scan_temp_bans(storage: &dyn StorageBackend, guild: GuildId) -> Result<Vec<TempBan>> {
+    Ok(storage
+        .scan_temp_bans()
+        .await?
+        .into_iter()
+        .filter(|temp_ban| temp_ban.guild == guild)
+        .collect())
+}
+
+async fn count_warns(storage: &dyn StorageBackend, guild: GuildId) -> Result<usize> {
+    let mut count = 0;
+
+    for user in storage.scan_warned_users(guild).await? {
+        count += storage.scan_warns(user, guild).await?.len();
+    }
+
+    Ok(count)
+}
+
+async fn count_notes(storage: &dyn StorageBackend, guild: GuildId) -> Result<usize> {
+    let mut count = 0;
+
+    for user in storage.scan_noted_users(guild).await? {
+        count += storage.scan_notes(user, guild).await?.len();
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        SqliteBackend,
+        automod::{AutomodAction, AutomodPattern},
+        case::{self, CaseAction},
+    };
+
+    #[tokio::test]
+    async fn archive_roundtrip() -> Result<()> {
+        let source = SqliteBackend::memory()?;
+        let (from, to) = (GuildId::new(1), GuildId::new(2));
+
+        source
+            .set_config(
+                from,
+                &GuildConfig {
+                    starboard_threshold: Some(5),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        source.add_autoreply(from, "hello", "world").await?;
+        source
+            .add_warn(UserId::new(3), from, &Warn::new(Some(1)))
+            .await?;
+        case::create(
+            &source,
+            from,
+            ModerationCase::new(CaseAction::Warn, UserId::new(3), UserId::new(5)),
+        )
+        .await?;
+        source.set_case_id(from, 7).await?;
+        source
+            .add_note(
+                UserId::new(3),
+                from,
+                &Note::new(UserId::new(5), "keep an eye on them".to_owned()),
+            )
+            .await?;
+        source
+            .set_automod_rule(
+                from,
+                &AutomodRule::new(
+                    AutomodPattern::Literal("spam".to_owned()),
+                    AutomodAction::Delete,
+                ),
+            )
+            .await?;
+
+        let archive = GuildArchive::collect(&source, from).await?;
+
+        for format in [ArchiveFormat::Json, ArchiveFormat::Toml] {
+            let target = SqliteBackend::memory()?;
+            target.add_autoreply(to, "stale", "entry").await?;
+            target
+                .add_warn(UserId::new(4), to, &Warn::new(None))
+                .await?;
+            target.set_case_id(to, 20).await?;
+            target
+                .add_note(
+                    UserId::new(4),
+                    to,
+                    &Note::new(UserId::new(5), "stale".to_owned()),
+                )
+                .await?;
+            target
+                .set_automod_rule(
+                    to,
+                    &AutomodRule::new(
+                        AutomodPattern::Literal("old".to_owned()),
+                        AutomodAction::Log,
+                    ),
+                )
+                .await?;
+
+            let parsed = GuildArchive::parse(&archive.serialize(format)?, format)?;
+
+            let diff = parsed.diff(&target, to).await?;
+            assert_eq!(diff.config.len(), 1);
+            assert_eq!(diff.autoreply, (1, 1));
+            assert_eq!(diff.warns, (1, 1));
+            assert_eq!(diff.cases, (0, 1));
+            assert_eq!(diff.automod_rules, (1, 1));
+            assert_eq!(diff.notes, (1, 1));
+
+            let entry = parsed.apply(&target, to, UserId::new(5)).await?;
+            assert_eq!(entry.map(|e| e.version), Some(1));
+
+            assert_eq!(target.get_config(to).await?.starboard_threshold, Some(5));
+            assert_eq!(
+                target.scan_autoreply(to).await?,
+                vec![("hello".to_owned(), "world".to_owned())]
+            );
+            assert_eq!(target.scan_warned_users(to).await?, vec![UserId::new(3)]);
+            assert_eq!(
+                target.scan_warns(UserId::new(3), to).await?[0].case,
+                Some(1)
+            );
+            assert_eq!(target.scan_cases(to).await?.len(), 1);
+            assert_eq!(target.incr_case_id(to).await?, 8);
+            assert_eq!(target.scan_automod_rules(to).await?, archive.automod_rules);
+            assert_eq!(target.scan_noted_users(to).await?, vec![UserId::new(3)]);
+            assert_eq!(
+                target.scan_notes(UserId::new(3), to).await?[0].content,
+                "keep an eye on them"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn archive_keeps_pending_actions() -> Result<()> {
+        let (source, target) = (SqliteBackend::memory()?, SqliteBackend::memory()?);
+        let (from, to) = (GuildId::new(1), GuildId::new(2));
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(1);
+
+        source
+            .add_temp_ban(&TempBan {
+                guild: from,
+                user: UserId::new(3),
+                expires_at,
+                case: Some(1),
+            })
+            .await?;
+        source
+            .set_quarantine(&Quarantine {
+                guild: from,
+                user: UserId::new(4),
+                roles: vec![RoleId::new(5)],
+                started_at: chrono::Utc::now(),
+                expires_at: None,
+                case: Some(2),
+            })
+            .await?;
+        source
+            .set_appeal(
+                from,
+                UserId::new(3),
+                &Appeal::new("please".to_owned(), Some(1)),
+            )
+            .await?;
+
+        target
+            .add_temp_ban(&TempBan {
+                guild: to,
+                user: UserId::new(6),
+                expires_at,
+                case: None,
+            })
+            .await?;
+
+        let format = ArchiveFormat::Toml;
+        let archive = GuildArchive::parse(
+            &GuildArchive::collect(&source, from)
+                .await?
+                .serialize(format)?,
+            format,
+        )?;
+
+        let diff = archive.diff(&target, to).await?;
+        assert_eq!(diff.temp_bans, (1, 1));
+        assert_eq!(diff.quarantines, (0, 1));
+        assert_eq!(diff.appeals, (0, 1));
+
+        archive.apply(&target, to, UserId::new(7)).await?;
+
+        assert_eq!(
+            target.scan_temp_bans().await?,
+            vec![TempBan {
+                guild: to,
+                user: UserId::new(3),
+                expires_at,
+                case: Some(1),
+            }]
+        );
+        assert_eq!(
+            target
+                .get_quarantine(to, UserId::new(4))
+                .await?
+                .map(|quarantine| quarantine.roles),
+            Some(vec![RoleId::new(5)])
+        );
+        assert_eq!(
+            target
+                .get_appeal(to, UserId::new(3))
+                .await?
+                .map(|appeal| appeal.content),
+            Some("please".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_upgrades_warn_counts() -> Result<()> {
+        let archive = GuildArchive::parse(
+            r#"{
+                "version": 1,
+                "guild": "1",
+                "exported_at": "2026-01-01T00:00:00Z",
+                "config": {},
+                "warn_counts": [{ "user": "2", "count": 3 }]
+            }"#,
+            ArchiveFormat::Json,
+        )?;
+
+        assert_eq!(archive.version, ARCHIVE_VERSION);
+        assert_eq!(archive.warns.len(), 3);
+        assert!(archive.warns.iter().all(|warn| warn.user == UserId::new(2)));
+
+        let serialized = archive.serialize(ArchiveFormat::Json)?;
+        assert!(!serialized.contains("warn_counts"));
+        assert!(
+            GuildArchive::parse(
+                &serialized.replace(
+                    r#""warns": ["#,
+                    r#""warn_counts": [{ "user": "2", "count": 3 }], "warns": ["#
+                ),
+                ArchiveFormat::Json
+            )
+            .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_rejects_out_of_range_cooloff() {
+        let archive = r#"{
+            "version": 4,
+            "guild": "1",
+            "exported_at": "2026-01-01T00:00:00Z",
+            "config": {
+                "raid_protection": { "threshold": 10, "window": "1m", "cooloff": "500000000y" }
+            }
+        }"#;
+
+        assert!(
+            GuildArchive::parse(archive, ArchiveFormat::Json)
+                .is_err_and(|err| err.to_string().contains("cool-off"))
+        );
+        assert!(
+            GuildArchive::parse(&archive.replace("500000000y", "30m"), ArchiveFormat::Json).is_ok()
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_fields() {
+        let archive = r#"{
+            "version": 2,
+            "guild": "1",
+            "exported_at": "2026-01-01T00:00:00Z",
+            "config": { "starboard_threshold": 5, "not_a_field": true }
+        }"#;
+
+        assert!(GuildArchive::parse(archive, ArchiveFormat::Json).is_err());
+        assert!(
+            GuildArchive::parse(
+                &archive.replace(r#", "not_a_field": true"#, ""),
+                ArchiveFormat::Json
+            )
+            .is_ok()
+        );
+    }
+}
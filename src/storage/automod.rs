@@ -0,0 +1,191 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use poise::serenity_prelude::{GenericChannelId, RoleId};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use crate::utils;
+
+/// The most compiled patterns kept in [`REGEXES`] before it is cleared.
+const MAX_CACHED_REGEXES: usize = 1024;
+
+/// Compiled patterns of rules, which are otherwise loaded from storage for every message.
+/// Invalid patterns are cached as `None`.
+static REGEXES: LazyLock<Mutex<HashMap<AutomodPattern, Option<Regex>>>> =
+    LazyLock::new(Mutex::default);
+
+/// What a rule matches message content against.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum AutomodPattern {
+    Regex(String),
+    /// A case-insensitive substring.
+    Literal(String),
+    /// Case-insensitive whole words.
+    Words(Vec<String>),
+}
+
+impl AutomodPattern {
+    pub fn regex(&self) -> Result<Regex, regex::Error> {
+        match self {
+            Self::Regex(pattern) => RegexBuilder::new(pattern).multi_line(true).build(),
+            Self::Literal(literal) => RegexBuilder::new(&regex::escape(literal))
+                .case_insensitive(true)
+                .build(),
+            Self::Words(words) => RegexBuilder::new(&format!(
+                r"\b(?:{})\b",
+                words
+                    .iter()
+                    .map(|word| regex::escape(word))
+                    .collect::<Vec<_>>()
+                    .join("|")
+            ))
+            .case_insensitive(true)
+            .build(),
+        }
+    }
+
+    /// The compiled pattern, or `None` if it is invalid. Compiled patterns are cached.
+    fn cached_regex(&self) -> Option<Regex> {
+        let mut regexes = REGEXES.lock().unwrap_or_else(|err| err.into_inner());
+
+        if let Some(regex) = regexes.get(self) {
+            return regex.clone();
+        }
+
+        if regexes.len() >= MAX_CACHED_REGEXES {
+            regexes.clear();
+        }
+
+        let regex = self.regex().ok();
+        regexes.insert(self.clone(), regex.clone());
+        regex
+    }
+}
+
+impl fmt::Display for AutomodPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Regex(pattern) => write!(f, "Regex `{pattern}`"),
+            Self::Literal(literal) => write!(f, "Literal `{literal}`"),
+            Self::Words(words) => write!(f, "Words `{}`", words.join(", ")),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AutomodAction {
+    /// Delete the message and silently warn its author.
+    Delete,
+    /// Delete the message and warn its author, notifying them.
+    Warn,
+    /// Delete the message, time out its author, and silently warn them.
+    Timeout {
+        #[serde(with = "crate::config::humantime_duration")]
+        duration: Duration,
+    },
+    Log,
+}
+
+impl fmt::Display for AutomodAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Delete => f.write_str("Delete"),
+            Self::Warn => f.write_str("Warn"),
+            Self::Timeout { duration } => {
+                write!(f, "Timeout ({})", humantime::format_duration(*duration))
+            }
+            Self::Log => f.write_str("Log only"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AutomodRule {
+    pub id: String,
+    pub pattern: AutomodPattern,
+    pub action: AutomodAction,
+    /// Channels the rule applies to; it applies everywhere if empty.
+    #[serde(skip_serializing_if = "HashSet::is_empty", default)]
+    pub channels: HashSet<GenericChannelId>,
+    #[serde(skip_serializing_if = "HashSet::is_empty", default)]
+    pub exempt_roles: HashSet<RoleId>,
+}
+
+impl AutomodRule {
+    pub fn new(pattern: AutomodPattern, action: AutomodAction) -> Self {
+        Self {
+            id: utils::nanoid(8),
+            pattern,
+            action,
+            channels: HashSet::new(),
+            exempt_roles: HashSet::new(),
+        }
+    }
+
+    /// Whether the rule applies to a message in `channel` by a member with `roles`.
+    pub fn applies(&self, channel: GenericChannelId, roles: &[RoleId]) -> bool {
+        (self.channels.is_empty() || self.channels.contains(&channel))
+            && !roles.iter().any(|role| self.exempt_roles.contains(role))
+    }
+
+    /// The part of `content` matched by the rule, if any.
+    /// Invalid regexes and empty matches never count as a hit.
+    pub fn find<'a>(&self, content: &'a str) -> Option<&'a str> {
+        self.pattern
+            .cached_regex()?
+            .find_iter(content)
+            .find(|found| !found.is_empty())
+            .map(|found| found.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_match_and_scope() {
+        let words = AutomodRule::new(
+            AutomodPattern::Words(vec!["foo".to_owned(), "b.r".to_owned()]),
+            AutomodAction::Delete,
+        );
+        assert_eq!(words.find("FOO fighters"), Some("FOO"));
+        assert_eq!(words.find("food"), None);
+        assert_eq!(words.find("bar"), None);
+        assert_eq!(words.find("b.r"), Some("b.r"));
+
+        let literal = AutomodRule::new(
+            AutomodPattern::Literal("discord.gg".to_owned()),
+            AutomodAction::Log,
+        );
+        assert_eq!(literal.find("join DISCORD.GG/x"), Some("DISCORD.GG"));
+        assert_eq!(literal.find("discordxgg"), None);
+
+        let mut regex = AutomodRule::new(
+            AutomodPattern::Regex(r"^\d{4,}$".to_owned()),
+            AutomodAction::Warn,
+        );
+        assert_eq!(regex.find("hi\n12345"), Some("12345"));
+
+        let empty = AutomodRule::new(AutomodPattern::Regex("a*".to_owned()), AutomodAction::Log);
+        assert_eq!(empty.find("bbb"), None);
+
+        let (a, b) = (GenericChannelId::new(1), GenericChannelId::new(2));
+        regex.channels.insert(a);
+        regex.exempt_roles.insert(RoleId::new(1));
+        assert!(regex.applies(a, &[RoleId::new(2)]));
+        assert!(!regex.applies(b, &[]));
+        assert!(!regex.applies(a, &[RoleId::new(1)]));
+    }
+}
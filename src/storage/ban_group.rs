@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use poise::serenity_prelude::{GuildId, UserId};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
+
+/// How bans in one guild of a ban group are applied to the other guilds.
+#[derive(
+    poise::ChoiceParameter, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BanGroupPolicy {
+    /// Ban the user in the other guilds immediately.
+    Automatic,
+    /// Propose the ban to moderators of the other guilds in their moderation logs channels.
+    #[default]
+    Confirm,
+}
+
+impl fmt::Display for BanGroupPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Automatic => "Automatic",
+            Self::Confirm => "Confirm",
+        })
+    }
+}
+
+/// A set of guilds that share their bans.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BanGroup {
+    pub name: String,
+    pub guilds: HashSet<GuildId>,
+    #[serde(default)]
+    pub policy: BanGroupPolicy,
+}
+
+impl BanGroup {
+    pub fn new(name: String, policy: BanGroupPolicy) -> Self {
+        Self {
+            name,
+            guilds: HashSet::new(),
+            policy,
+        }
+    }
+}
+
+/// A ban from another guild of a ban group that awaits confirmation by moderators.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BanProposal {
+    pub origin: GuildId,
+    pub origin_name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub moderator: Option<UserId>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
@@ -0,0 +1,196 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{GenericChannelId, GuildId, MessageId, UserId};
+use serde::{Deserialize, Serialize};
+use std::{fmt, time::Duration};
+
+use super::StorageBackend;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseAction {
+    Warn,
+    Ban,
+    Kick,
+    Timeout,
+    Purge,
+    Quarantine,
+}
+
+impl fmt::Display for CaseAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warn => "Warn",
+            Self::Ban => "Ban",
+            Self::Kick => "Kick",
+            Self::Timeout => "Timeout",
+            Self::Purge => "Purge",
+            Self::Quarantine => "Quarantine",
+        })
+    }
+}
+
+/// Whether the target of a moderation action was notified with a direct message.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DmStatus {
+    Yes,
+    Failed,
+    #[default]
+    No,
+}
+
+impl fmt::Display for DmStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Yes => "Yes",
+            Self::Failed => "Failed",
+            Self::No => "No",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CaseLogMessage {
+    pub channel: GenericChannelId,
+    pub message: MessageId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ModerationCase {
+    pub id: u64,
+    pub action: CaseAction,
+    pub target: UserId,
+    pub moderator: UserId,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub duration: Option<Duration>,
+    #[serde(default)]
+    pub dm: DmStatus,
+    #[serde(default)]
+    pub log_message: Option<CaseLogMessage>,
+}
+
+impl ModerationCase {
+    /// Create a case that has not been assigned an ID yet.
+    pub fn new(action: CaseAction, target: UserId, moderator: UserId) -> Self {
+        Self {
+            id: 0,
+            action,
+            target,
+            moderator,
+            timestamp: chrono::Utc::now(),
+            reason: None,
+            duration: None,
+            dm: DmStatus::default(),
+            log_message: None,
+        }
+    }
+
+    pub fn log_link(&self, guild: GuildId) -> Option<String> {
+        self.log_message
+            .map(|log| log.message.link(log.channel, Some(guild)).to_string())
+    }
+}
+
+/// Assign the next case ID in `guild` to `case` and save it.
+pub async fn create(
+    storage: &dyn StorageBackend,
+    guild: GuildId,
+    mut case: ModerationCase,
+) -> Result<ModerationCase> {
+    case.id = storage.incr_case_id(guild).await?;
+    storage.set_case(guild, &case).await?;
+
+    Ok(case)
+}
+
+/// All cases in `guild` targeting `user`, ordered by case ID.
+pub async fn for_user(
+    storage: &dyn StorageBackend,
+    guild: GuildId,
+    user: UserId,
+) -> Result<Vec<ModerationCase>> {
+    let mut cases = storage
+        .scan_cases(guild)
+        .await?
+        .into_iter()
+        .filter(|case| case.target == user)
+        .collect::<Vec<_>>();
+
+    cases.sort_by_key(|case| case.id);
+
+    Ok(cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SqliteBackend;
+
+    #[tokio::test]
+    async fn cases_are_numbered_per_guild() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (guild, moderator) = (GuildId::new(1), UserId::new(2));
+        let (alice, bob) = (UserId::new(3), UserId::new(4));
+
+        let first = create(
+            &storage,
+            guild,
+            ModerationCase::new(CaseAction::Warn, alice, moderator),
+        )
+        .await?;
+        let second = create(
+            &storage,
+            guild,
+            ModerationCase {
+                duration: Some(Duration::from_hours(1)),
+                ..ModerationCase::new(CaseAction::Timeout, bob, moderator)
+            },
+        )
+        .await?;
+        let third = create(
+            &storage,
+            guild,
+            ModerationCase::new(CaseAction::Ban, alice, moderator),
+        )
+        .await?;
+        let other = create(
+            &storage,
+            GuildId::new(5),
+            ModerationCase::new(CaseAction::Kick, alice, moderator),
+        )
+        .await?;
+
+        assert_eq!((first.id, second.id, third.id, other.id), (1, 2, 3, 1));
+        assert_eq!(storage.get_case(guild, 2).await?, Some(second));
+
+        storage.del_case(guild, 3).await?;
+        assert_eq!(
+            for_user(&storage, guild, alice)
+                .await?
+                .iter()
+                .map(|case| case.id)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        let fourth = create(
+            &storage,
+            guild,
+            ModerationCase::new(CaseAction::Warn, alice, moderator),
+        )
+        .await?;
+        assert_eq!(fourth.id, 4);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{GuildId, UserId};
+use serde::{Deserialize, Serialize};
+
+use super::StorageBackend;
+use crate::config::{ConfigChange, GuildConfig};
+
+/// The number of most recent history entries retained per guild.
+pub const MAX_ENTRIES: u64 = 50;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigHistoryEntry {
+    pub version: u64,
+    pub actor: UserId,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub changes: Vec<ConfigChange>,
+    /// The full configuration after this change was applied.
+    pub config: GuildConfig,
+}
+
+/// Save `config` as the configuration of `guild`, appending an entry to the configuration history.
+/// Returns `None` without writing anything if the configuration is unchanged.
+pub async fn record(
+    storage: &dyn StorageBackend,
+    guild: GuildId,
+    actor: UserId,
+    config: &GuildConfig,
+) -> Result<Option<ConfigHistoryEntry>> {
+    let changes = storage.get_config(guild).await?.diff(config)?;
+
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    if GuildConfig::default().diff(config)?.is_empty() {
+        storage.del_config(guild).await?;
+    } else {
+        storage.set_config(guild, config).await?;
+    }
+
+    // Concurrent saves must not be assigned the same version
+    let version = storage.incr_config_version(guild).await?;
+
+    let entry = ConfigHistoryEntry {
+        version,
+        actor,
+        timestamp: chrono::Utc::now(),
+        changes,
+        config: config.clone(),
+    };
+
+    storage.add_config_history(guild, &entry).await?;
+
+    Ok(Some(entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SqliteBackend;
+
+    #[tokio::test]
+    async fn record_appends_versions() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (guild, actor) = (GuildId::new(1), UserId::new(2));
+
+        let config = GuildConfig {
+            starboard_threshold: Some(3),
+            ..Default::default()
+        };
+
+        let first = record(&storage, guild, actor, &config).await?;
+        assert_eq!(first.map(|e| e.version), Some(1));
+        assert!(record(&storage, guild, actor, &config).await?.is_none());
+
+        let second = record(&storage, guild, actor, &GuildConfig::default()).await?;
+        assert_eq!(second.map(|e| e.changes.len()), Some(1));
+        // Resetting to the default removes the configuration, leaving the history and its counter
+        assert_eq!(storage.size().await?, 2);
+
+        let history = storage.get_config_history(guild).await?;
+        assert_eq!(
+            history.iter().map(|e| e.version).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(history[0].config.starboard_threshold, Some(3));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn history_is_trimmed() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (guild, actor) = (GuildId::new(1), UserId::new(2));
+
+        for threshold in 0..MAX_ENTRIES + 5 {
+            let config = GuildConfig {
+                starboard_threshold: Some(threshold),
+                ..Default::default()
+            };
+
+            record(&storage, guild, actor, &config).await?;
+        }
+
+        let history = storage.get_config_history(guild).await?;
+        assert_eq!(history.len() as u64, MAX_ENTRIES);
+        assert_eq!(history.first().map(|e| e.version), Some(6));
+
+        Ok(())
+    }
+}
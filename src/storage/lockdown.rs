@@ -0,0 +1,17 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use poise::serenity_prelude::{GuildId, VerificationLevel};
+use serde::{Deserialize, Serialize};
+
+/// A guild that is locked down because of a suspected raid.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Lockdown {
+    pub guild: GuildId,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// When the lockdown ends automatically; extended by joins during the lockdown.
+    pub ends_at: chrono::DateTime<chrono::Utc>,
+    /// The verification level to restore when the lockdown ends.
+    pub previous_verification_level: VerificationLevel,
+}
@@ -2,16 +2,34 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
-use poise::serenity_prelude::{Attachment, Message, UserId};
+use poise::serenity_prelude::{
+    Attachment, AttachmentId, GenericChannelId, Message, MessageId, UserId,
+};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Discord's epoch, the first second of 2015, in milliseconds since the Unix epoch.
+const DISCORD_EPOCH: u64 = 1_420_070_400_000;
+
+/// The creation time of a message in milliseconds since the Unix epoch, which message logs
+/// are indexed by.
+pub fn timestamp_millis(message: MessageId) -> i64 {
+    i64::try_from((message.get() >> 22) + DISCORD_EPOCH).unwrap_or(i64::MAX)
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct MessageLog {
     pub content: String,
     pub author: UserId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<GenericChannelId>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub attachments: Vec<Attachment>,
+    /// Content hashes of attachments saved to the local attachment cache.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub cached_attachments: HashMap<AttachmentId, String>,
 }
 
 impl From<&Message> for MessageLog {
@@ -19,7 +37,50 @@ impl From<&Message> for MessageLog {
         Self {
             content: value.content.to_string(),
             author: value.author.id,
+            channel: Some(value.channel_id),
             attachments: value.attachments.to_vec(),
+            cached_attachments: HashMap::new(),
         }
     }
 }
+
+/// Filters for searching the message logs of a guild.
+#[derive(Clone, Debug)]
+pub struct MessageLogQuery {
+    pub after: DateTime<Utc>,
+    pub before: DateTime<Utc>,
+    pub author: Option<UserId>,
+    pub channel: Option<GenericChannelId>,
+    /// Text that the content must contain, ignoring case.
+    pub contains: Option<String>,
+    /// The maximum number of results to return.
+    pub limit: usize,
+}
+
+impl MessageLogQuery {
+    /// Whether a message log matches the author, channel and content filters.
+    pub fn matches(&self, log: &MessageLog) -> bool {
+        self.author.is_none_or(|author| log.author == author)
+            && self
+                .channel
+                .is_none_or(|channel| log.channel == Some(channel))
+            && self.contains.as_ref().is_none_or(|contains| {
+                log.content
+                    .to_lowercase()
+                    .contains(&contains.to_lowercase())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_millis_works() {
+        assert_eq!(
+            timestamp_millis(MessageId::new(175928847299117063)),
+            1462015105796
+        );
+    }
+}
@@ -0,0 +1,269 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, bail};
+use std::pin::Pin;
+
+use super::{StorageBackend, keys, warn::Warn};
+
+type MigrationFuture<'a> = Pin<Box<dyn Future<Output = Result<u64>> + Send + 'a>>;
+
+/// A storage migration. Migrations must be idempotent, since a migration may be
+/// run again if the process exits before the schema version is recorded.
+pub struct Migration {
+    /// The schema version that storage will be at after this migration is applied.
+    pub version: u64,
+    /// A short human-readable name for reporting.
+    pub name: &'static str,
+    /// Run the migration, returning the number of affected keys. When `dry_run` is
+    /// set, nothing may be written and the number of keys that would be affected is returned.
+    run: for<'a> fn(&'a dyn StorageBackend, bool) -> MigrationFuture<'a>,
+}
+
+/// All migrations, in ascending order of version.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "remove-superseded-ephemeral-keys",
+        run: |storage, dry_run| Box::pin(remove_superseded_ephemeral_keys(storage, dry_run)),
+    },
+    Migration {
+        version: 2,
+        name: "split-warn-counts",
+        run: |storage, dry_run| Box::pin(split_warn_counts(storage, dry_run)),
+    },
+    Migration {
+        version: 3,
+        name: "seed-config-versions",
+        run: |storage, dry_run| Box::pin(seed_config_versions(storage, dry_run)),
+    },
+];
+
+async fn remove_superseded_ephemeral_keys(
+    storage: &dyn StorageBackend,
+    dry_run: bool,
+) -> Result<u64> {
+    let mut affected = 0;
+
+    for prefix in [
+        "starboard-v1:",
+        "message-log-v1:",
+        "intelligence-context-v1:",
+        "code-expansion-v1:",
+    ] {
+        let keys = storage.scan_prefix(prefix).await?;
+        affected += keys.len() as u64;
+
+        if !dry_run {
+            storage.del_keys(&keys).await?;
+        }
+    }
+
+    Ok(affected)
+}
+
+/// Replace `warn-count-v1` counters with individual warns. The original timestamps are unknown,
+/// so migrated warns are timestamped at the time of the migration.
+async fn split_warn_counts(storage: &dyn StorageBackend, dry_run: bool) -> Result<u64> {
+    let counters = storage
+        .scan_prefix(&format!("{}:", keys::WARN_COUNT))
+        .await?;
+
+    if dry_run {
+        return Ok(counters.len() as u64);
+    }
+
+    let timestamp = chrono::Utc::now();
+
+    for key in &counters {
+        let Some((user, guild)) = keys::user_guild(&keys::WARN_COUNT, key) else {
+            continue;
+        };
+
+        let count = storage
+            .get_warn_count(user, guild)
+            .await?
+            .unwrap_or_default();
+
+        // Deterministic IDs keep this idempotent if it is interrupted before the counters are deleted
+        for idx in 0..count {
+            let warn = Warn {
+                id: format!("legacy-{idx}"),
+                timestamp,
+                case: None,
+            };
+
+            storage.add_warn(user, guild, &warn).await?;
+        }
+    }
+
+    storage.del_keys(&counters).await?;
+
+    Ok(counters.len() as u64)
+}
+
+/// Start the configuration version counter of each guild after its latest history entry,
+/// which versions were previously derived from.
+async fn seed_config_versions(storage: &dyn StorageBackend, dry_run: bool) -> Result<u64> {
+    let prefix = format!("{}:g", keys::CONFIG_HISTORY);
+    let mut affected = 0;
+
+    for key in storage.scan_prefix(&prefix).await? {
+        let Some(guild) = key
+            .strip_prefix(&prefix)
+            .and_then(|guild| guild.parse().ok())
+        else {
+            continue;
+        };
+
+        let latest = storage
+            .get_config_history(guild)
+            .await?
+            .last()
+            .map_or(0, |entry| entry.version);
+
+        if storage.get_config_version(guild).await? < latest {
+            affected += 1;
+
+            if !dry_run {
+                storage.set_config_version(guild, latest).await?;
+            }
+        }
+    }
+
+    Ok(affected)
+}
+
+/// Apply all pending migrations in order, recording the schema version after each one.
+/// In a dry run, pending migrations are only reported and then returned as an error.
+#[tracing::instrument(skip(storage))]
+pub async fn run(storage: &dyn StorageBackend, dry_run: bool) -> Result<()> {
+    let current = storage.get_schema_version().await?;
+
+    let pending = MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current)
+        .collect::<Vec<_>>();
+
+    if pending.is_empty() {
+        tracing::debug!(version = current, "storage schema is up to date");
+        return Ok(());
+    }
+
+    let count = pending.len();
+
+    for migration in pending {
+        let affected = (migration.run)(storage, dry_run).await?;
+
+        if dry_run {
+            tracing::info!(
+                version = migration.version,
+                name = migration.name,
+                affected,
+                "would apply storage migration (dry run)"
+            );
+        } else {
+            storage.set_schema_version(migration.version).await?;
+
+            tracing::info!(
+                version = migration.version,
+                name = migration.name,
+                affected,
+                "applied storage migration"
+            );
+        }
+    }
+
+    // Starting on an unmigrated schema would read and write keys the migrations would move
+    if dry_run {
+        bail!("{count} storage migration(s) are pending, unset MIGRATIONS_DRY_RUN to apply them");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::GuildConfig,
+        storage::{
+            SqliteBackend,
+            config_history::{self, ConfigHistoryEntry},
+        },
+    };
+    use poise::serenity_prelude::{GuildId, UserId};
+
+    #[test]
+    fn migrations_are_ordered() {
+        assert!(MIGRATIONS.windows(2).all(|w| w[0].version < w[1].version));
+        assert!(MIGRATIONS.first().is_none_or(|m| m.version > 0));
+    }
+
+    #[tokio::test]
+    async fn dry_run_does_not_write() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+
+        assert!(run(&storage, true).await.is_err());
+        assert_eq!(storage.get_schema_version().await?, 0);
+
+        run(&storage, false).await?;
+        run(&storage, true).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn config_versions_continue_after_history() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (guild, actor) = (GuildId::new(1), UserId::new(2));
+
+        storage
+            .add_config_history(
+                guild,
+                &ConfigHistoryEntry {
+                    version: 5,
+                    actor,
+                    timestamp: chrono::Utc::now(),
+                    changes: Vec::new(),
+                    config: GuildConfig::default(),
+                },
+            )
+            .await?;
+
+        run(&storage, false).await?;
+
+        let entry = config_history::record(
+            &storage,
+            guild,
+            actor,
+            &GuildConfig {
+                starboard_threshold: Some(3),
+                ..Default::default()
+            },
+        )
+        .await?;
+        assert_eq!(entry.map(|e| e.version), Some(6));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn migrations_are_idempotent() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let latest = MIGRATIONS.last().map_or(0, |m| m.version);
+
+        run(&storage, false).await?;
+        assert_eq!(storage.get_schema_version().await?, latest);
+
+        for migration in MIGRATIONS {
+            assert_eq!((migration.run)(&storage, false).await?, 0);
+        }
+
+        run(&storage, false).await?;
+        assert_eq!(storage.get_schema_version().await?, latest);
+
+        Ok(())
+    }
+}
@@ -2,28 +2,185 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
-use poise::serenity_prelude::{GenericChannelId, GuildId, MessageId, UserId};
-use redis::{
-    AsyncCommands as _, RedisResult,
-    aio::{ConnectionManager, ConnectionManagerConfig},
-};
-use std::{fmt::Debug, time::Duration};
-
+use eyre::Result;
+use poise::serenity_prelude::{self as serenity, GenericChannelId, GuildId, MessageId, UserId};
+use std::{fmt::Debug, ops::Deref, path::Path, time::Duration};
+
+use appeal::Appeal;
+use automod::AutomodRule;
+use ban_group::{BanGroup, BanProposal};
+use case::ModerationCase;
 use code_expansion::CodeExpansionData;
-use log::MessageLog;
+use config_history::ConfigHistoryEntry;
+use lockdown::Lockdown;
+use log::{MessageLog, MessageLogQuery};
+use note::Note;
+use presence::PresenceData;
+use quarantine::Quarantine;
 use reminder::ReminderData;
+use temp_ban::TempBan;
+use warn::Warn;
 
 use crate::{config::GuildConfig, handlers::intelligence::IntelligenceMessages};
 
+pub mod appeal;
+pub mod archive;
+pub mod automod;
+pub mod ban_group;
+pub mod case;
 pub mod code_expansion;
+pub mod config_history;
+pub mod lockdown;
 pub mod log;
+pub mod migrations;
+pub mod note;
 pub mod presence;
+pub mod quarantine;
+mod redis_backend;
 mod redis_util;
 pub mod reminder;
+mod sqlite_backend;
+pub mod temp_ban;
+pub mod warn;
+
+pub use redis_backend::RedisBackend;
+pub use sqlite_backend::SqliteBackend;
+
+/// The operations that every storage backend must support.
+#[serenity::async_trait]
+pub trait StorageBackend: Send + Sync {
+    async fn size(&self) -> Result<u64>;
+
+    async fn get_schema_version(&self) -> Result<u64>;
+    async fn set_schema_version(&self, version: u64) -> Result<()>;
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<String>>;
+    async fn del_keys(&self, keys: &[String]) -> Result<()>;
+
+    async fn get_config(&self, guild_id: GuildId) -> Result<GuildConfig>;
+    async fn set_config(&self, guild_id: GuildId, value: &GuildConfig) -> Result<()>;
+    async fn del_config(&self, guild_id: GuildId) -> Result<()>;
+
+    async fn get_config_history(&self, guild_id: GuildId) -> Result<Vec<ConfigHistoryEntry>>;
+    async fn add_config_history(&self, guild_id: GuildId, entry: &ConfigHistoryEntry)
+    -> Result<()>;
+    async fn incr_config_version(&self, guild_id: GuildId) -> Result<u64>;
+    /// The last configuration history version assigned in `guild_id`, or 0 if none has been.
+    async fn get_config_version(&self, guild_id: GuildId) -> Result<u64>;
+    async fn set_config_version(&self, guild_id: GuildId, version: u64) -> Result<()>;
+
+    async fn get_presence(&self) -> Result<Option<PresenceData>>;
+    async fn set_presence(&self, value: &PresenceData) -> Result<()>;
+    async fn del_presence(&self) -> Result<()>;
+
+    async fn get_starboard(&self, message_id: MessageId) -> Result<Option<u64>>;
+    async fn set_starboard(&self, message_id: MessageId, value: &u64) -> Result<()>;
+    async fn del_starboard(&self, message_id: MessageId) -> Result<()>;
+
+    async fn get_message_log(&self, message_id: MessageId) -> Result<Option<MessageLog>>;
+    async fn set_message_log(
+        &self,
+        guild_id: GuildId,
+        message_id: MessageId,
+        value: &MessageLog,
+        ttl: Duration,
+    ) -> Result<()>;
+    async fn del_message_log(&self, message_id: MessageId) -> Result<()>;
+    /// Message logs of messages in `guild_id` that match `query`, newest first.
+    async fn scan_message_logs(
+        &self,
+        guild_id: GuildId,
+        query: &MessageLogQuery,
+    ) -> Result<Vec<(MessageId, MessageLog)>>;
+
+    async fn scan_reminders(&self) -> Result<Vec<ReminderData>>;
+    async fn add_reminders(&self, value: &ReminderData) -> Result<()>;
+    async fn clean_reminders(&self) -> Result<()>;
+
+    async fn scan_temp_bans(&self) -> Result<Vec<TempBan>>;
+    async fn add_temp_ban(&self, value: &TempBan) -> Result<()>;
+    async fn del_temp_ban(&self, value: &TempBan) -> Result<()>;
+
+    async fn scan_autoreply(&self, guild_id: GuildId) -> Result<Vec<(String, String)>>;
+    async fn add_autoreply(&self, guild_id: GuildId, f: &str, v: &str) -> Result<()>;
+    async fn del_autoreply(&self, guild_id: GuildId, f: &str) -> Result<()>;
+    async fn delall_autoreply(&self, guild_id: GuildId) -> Result<()>;
+
+    async fn scan_automod_rules(&self, guild: GuildId) -> Result<Vec<AutomodRule>>;
+    async fn set_automod_rule(&self, guild: GuildId, rule: &AutomodRule) -> Result<()>;
+    async fn del_automod_rule(&self, guild: GuildId, id: &str) -> Result<()>;
+
+    async fn get_intelligence_consent(&self, user_id: UserId) -> Result<bool>;
+    async fn add_intelligence_consent(&self, user_id: UserId) -> Result<()>;
+
+    async fn get_intelligence_context(
+        &self,
+        user: UserId,
+        channel: GenericChannelId,
+    ) -> Result<Option<IntelligenceMessages>>;
+    async fn set_intelligence_context(
+        &self,
+        user: UserId,
+        channel: GenericChannelId,
+        context: &IntelligenceMessages,
+    ) -> Result<()>;
+
+    /// Read a legacy `warn-count-v1` counter, only used for migrating to individual warns.
+    async fn get_warn_count(&self, user: UserId, guild: GuildId) -> Result<Option<u64>>;
+
+    async fn scan_warns(&self, user: UserId, guild: GuildId) -> Result<Vec<Warn>>;
+    async fn scan_warned_users(&self, guild: GuildId) -> Result<Vec<UserId>>;
+    async fn add_warn(&self, user: UserId, guild: GuildId, warn: &Warn) -> Result<()>;
+    async fn del_warn(&self, user: UserId, guild: GuildId, id: &str) -> Result<()>;
+    async fn del_warns(&self, user: UserId, guild: GuildId) -> Result<()>;
+
+    async fn scan_notes(&self, user: UserId, guild: GuildId) -> Result<Vec<Note>>;
+    async fn scan_noted_users(&self, guild: GuildId) -> Result<Vec<UserId>>;
+    async fn add_note(&self, user: UserId, guild: GuildId, note: &Note) -> Result<()>;
+    async fn del_note(&self, user: UserId, guild: GuildId, id: &str) -> Result<()>;
+
+    async fn get_appeal(&self, guild: GuildId, user: UserId) -> Result<Option<Appeal>>;
+    async fn set_appeal(&self, guild: GuildId, user: UserId, appeal: &Appeal) -> Result<()>;
+
+    async fn scan_ban_groups(&self) -> Result<Vec<BanGroup>>;
+    async fn set_ban_group(&self, group: &BanGroup) -> Result<()>;
+    async fn del_ban_group(&self, name: &str) -> Result<()>;
+
+    async fn get_ban_proposal(&self, guild: GuildId, user: UserId) -> Result<Option<BanProposal>>;
+    async fn set_ban_proposal(
+        &self,
+        guild: GuildId,
+        user: UserId,
+        proposal: &BanProposal,
+    ) -> Result<()>;
+    async fn del_ban_proposal(&self, guild: GuildId, user: UserId) -> Result<()>;
+
+    async fn get_lockdown(&self, guild: GuildId) -> Result<Option<Lockdown>>;
+    async fn set_lockdown(&self, lockdown: &Lockdown) -> Result<()>;
+    async fn del_lockdown(&self, guild: GuildId) -> Result<()>;
+    async fn scan_lockdowns(&self) -> Result<Vec<Lockdown>>;
+
+    async fn get_quarantine(&self, guild: GuildId, user: UserId) -> Result<Option<Quarantine>>;
+    async fn set_quarantine(&self, quarantine: &Quarantine) -> Result<()>;
+    async fn del_quarantine(&self, guild: GuildId, user: UserId) -> Result<()>;
+    async fn scan_quarantines(&self) -> Result<Vec<Quarantine>>;
+
+    async fn incr_case_id(&self, guild: GuildId) -> Result<u64>;
+    /// The last case ID assigned in `guild`, or 0 if none has been.
+    async fn get_case_id(&self, guild: GuildId) -> Result<u64>;
+    async fn set_case_id(&self, guild: GuildId, id: u64) -> Result<()>;
+    async fn get_case(&self, guild: GuildId, id: u64) -> Result<Option<ModerationCase>>;
+    async fn set_case(&self, guild: GuildId, case: &ModerationCase) -> Result<()>;
+    async fn del_case(&self, guild: GuildId, id: u64) -> Result<()>;
+    async fn scan_cases(&self, guild: GuildId) -> Result<Vec<ModerationCase>>;
+
+    async fn get_code_expansion(&self, message: MessageId) -> Result<Option<CodeExpansionData>>;
+    async fn set_code_expansion(&self, message: MessageId, data: CodeExpansionData) -> Result<()>;
+    async fn del_code_expansion(&self, original: MessageId) -> Result<()>;
+}
 
 #[non_exhaustive]
 pub struct Storage {
-    conn: ConnectionManager,
+    backend: Box<dyn StorageBackend>,
 }
 
 impl Debug for Storage {
@@ -33,28 +190,35 @@ impl Debug for Storage {
 }
 
 impl Storage {
-    pub async fn redis(redis: redis::Client) -> RedisResult<Self> {
-        let conn = ConnectionManager::new_with_config(
-            redis,
-            ConnectionManagerConfig::new()
-                .set_connection_timeout(Some(Duration::from_secs(10)))
-                .set_response_timeout(Some(Duration::from_mins(1)))
-                .set_number_of_retries(3),
-        )
-        .await?;
-
-        Ok(Self { conn })
+    pub async fn redis(redis: redis::Client) -> Result<Self> {
+        Ok(Self {
+            backend: Box::new(RedisBackend::new(redis).await?),
+        })
+    }
+
+    pub fn sqlite(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            backend: Box::new(SqliteBackend::open(path)?),
+        })
     }
 }
 
-impl Storage {
-    pub async fn size(&self) -> RedisResult<u64> {
-        let mut conn = self.conn.clone();
-        let keys: u64 = redis::cmd("DBSIZE").query_async(&mut conn).await?;
-        Ok(keys)
+impl Deref for Storage {
+    type Target = dyn StorageBackend;
+
+    fn deref(&self) -> &Self::Target {
+        self.backend.as_ref()
     }
 }
 
+mod ttl {
+    use std::time::Duration;
+
+    pub const STARBOARD: Duration = Duration::from_hours(336);
+    pub const INTELLIGENCE_CONTEXT: Duration = Duration::from_mins(5);
+    pub const CODE_EXPANSION: Duration = Duration::from_mins(5);
+}
+
 mod keys {
     use std::{borrow::Cow, fmt};
 
@@ -110,6 +274,16 @@ mod keys {
         }
     }
 
+    /// Extract the user and guild IDs from a `{base}:u{user}:g{guild}` key.
+    pub fn user_guild(base: &StorageKey, key: &str) -> Option<(UserId, GuildId)> {
+        let (user, guild) = key
+            .strip_prefix(base.base)?
+            .strip_prefix(":u")?
+            .split_once(":g")?;
+
+        Some((user.parse().ok()?, guild.parse().ok()?))
+    }
+
     impl redis::ToRedisArgs for StorageKey {
         fn write_redis_args<W>(&self, out: &mut W)
         where
@@ -121,290 +295,29 @@ mod keys {
 
     impl redis::ToSingleRedisArg for StorageKey {}
 
+    pub const SCHEMA_VERSION: StorageKey = StorageKey::new("schema-version");
     pub const GUILD_CONFIG: StorageKey = StorageKey::new("guild-config-v1");
+    pub const CONFIG_HISTORY: StorageKey = StorageKey::new("config-history-v1");
+    pub const CONFIG_VERSION: StorageKey = StorageKey::new("config-version-v1");
     pub const PRESENCE: StorageKey = StorageKey::new("presence-v1");
     pub const STARBOARD: StorageKey = StorageKey::new("starboard-v2");
     pub const MESSAGE_LOG: StorageKey = StorageKey::new("message-log-v2");
+    pub const MESSAGE_LOG_INDEX: StorageKey = StorageKey::new("message-log-index-v1");
     pub const REMINDERS: StorageKey = StorageKey::new("reminders-v1");
+    pub const TEMP_BANS: StorageKey = StorageKey::new("temp-bans-v1");
     pub const AUTOREPLY: StorageKey = StorageKey::new("autoreply-v2");
+    pub const AUTOMOD_RULES: StorageKey = StorageKey::new("automod-rules-v1");
     pub const INTELLIGENCE_CONSENT: StorageKey = StorageKey::new("intelligence-consent-v1");
     pub const INTELLIGENCE_CONTEXT: StorageKey = StorageKey::new("intelligence-context-v2");
     pub const WARN_COUNT: StorageKey = StorageKey::new("warn-count-v1");
+    pub const WARNS: StorageKey = StorageKey::new("warns-v1");
+    pub const NOTES: StorageKey = StorageKey::new("notes-v1");
+    pub const CASE_ID: StorageKey = StorageKey::new("case-id-v1");
+    pub const CASES: StorageKey = StorageKey::new("cases-v1");
+    pub const LOCKDOWNS: StorageKey = StorageKey::new("lockdowns-v1");
+    pub const QUARANTINES: StorageKey = StorageKey::new("quarantines-v1");
+    pub const APPEALS: StorageKey = StorageKey::new("appeals-v1");
+    pub const BAN_GROUPS: StorageKey = StorageKey::new("ban-groups-v1");
+    pub const BAN_PROPOSALS: StorageKey = StorageKey::new("ban-proposals-v1");
     pub const CODE_EXPANSION: StorageKey = StorageKey::new("code-expansion-v2");
 }
-
-impl Storage {
-    pub async fn get_config(&self, guild_id: GuildId) -> RedisResult<GuildConfig> {
-        let mut conn = self.conn.clone();
-        let ret: Option<GuildConfig> = conn.get(keys::GUILD_CONFIG.guild(guild_id)).await?;
-        Ok(ret.unwrap_or_default())
-    }
-
-    pub async fn set_config(&self, guild_id: GuildId, value: &GuildConfig) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.set(keys::GUILD_CONFIG.guild(guild_id), value).await?;
-        Ok(())
-    }
-
-    pub async fn del_config(&self, guild_id: GuildId) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.del(keys::GUILD_CONFIG.guild(guild_id)).await?;
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn get_presence(&self) -> RedisResult<Option<presence::PresenceData>> {
-        let mut conn = self.conn.clone();
-        let ret: Option<presence::PresenceData> = conn.get(keys::PRESENCE).await?;
-        Ok(ret)
-    }
-
-    pub async fn set_presence(&self, value: &presence::PresenceData) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.set(keys::PRESENCE, value).await?;
-        Ok(())
-    }
-
-    pub async fn del_presence(&self) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.del(keys::PRESENCE).await?;
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn get_starboard(&self, message_id: MessageId) -> RedisResult<Option<u64>> {
-        let mut conn = self.conn.clone();
-        let ret: Option<u64> = conn.get(keys::STARBOARD.message(message_id)).await?;
-        Ok(ret)
-    }
-
-    pub async fn set_starboard(&self, message_id: MessageId, value: &u64) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn
-            .set_options(
-                keys::STARBOARD.message(message_id),
-                value,
-                redis::SetOptions::default().with_expiration(redis::SetExpiry::EX(1209600)),
-            )
-            .await?;
-        Ok(())
-    }
-
-    pub async fn del_starboard(&self, message_id: MessageId) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.del(keys::STARBOARD.message(message_id)).await?;
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn get_message_log(&self, message_id: MessageId) -> RedisResult<Option<MessageLog>> {
-        let mut conn = self.conn.clone();
-        let ret: Option<MessageLog> = conn.get(keys::MESSAGE_LOG.message(message_id)).await?;
-        Ok(ret)
-    }
-
-    pub async fn set_message_log(
-        &self,
-        message_id: MessageId,
-        value: &MessageLog,
-    ) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn
-            .set_options(
-                keys::MESSAGE_LOG.message(message_id),
-                value,
-                redis::SetOptions::default().with_expiration(redis::SetExpiry::EX(86400)),
-            )
-            .await?;
-        Ok(())
-    }
-
-    pub async fn del_message_log(&self, message_id: MessageId) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.del(keys::MESSAGE_LOG.message(message_id)).await?;
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn scan_reminders(&self) -> RedisResult<Vec<ReminderData>> {
-        use futures_util::StreamExt as _;
-
-        let mut conn = self.conn.clone();
-
-        let mut cmd = redis::cmd("ZSCAN");
-        cmd.arg(keys::REMINDERS).cursor_arg(0).arg("NOSCORES");
-
-        let values: Vec<ReminderData> = cmd
-            .iter_async::<ReminderData>(&mut conn)
-            .await?
-            .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .collect::<RedisResult<Vec<_>>>()?;
-
-        Ok(values)
-    }
-
-    pub async fn add_reminders(&self, value: &ReminderData) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn
-            .zadd(keys::REMINDERS, value, value.timestamp.timestamp())
-            .await?;
-
-        Ok(())
-    }
-
-    pub async fn clean_reminders(&self) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn
-            .zrembyscore(keys::REMINDERS, 0, chrono::Utc::now().timestamp() - 1)
-            .await?;
-
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn scan_autoreply(&self, guild_id: GuildId) -> RedisResult<Vec<(String, String)>> {
-        use futures_util::StreamExt as _;
-
-        let mut conn = self.conn.clone();
-        let values: Vec<(String, String)> = conn
-            .hscan(keys::AUTOREPLY.guild(guild_id))
-            .await?
-            .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .collect::<RedisResult<Vec<_>>>()?;
-
-        Ok(values)
-    }
-
-    pub async fn add_autoreply(&self, guild_id: GuildId, f: &str, v: &str) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.hset(keys::AUTOREPLY.guild(guild_id), f, v).await?;
-        Ok(())
-    }
-
-    pub async fn del_autoreply(&self, guild_id: GuildId, f: &str) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.hdel(keys::AUTOREPLY.guild(guild_id), f).await?;
-        Ok(())
-    }
-
-    pub async fn delall_autoreply(&self, guild_id: GuildId) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.del(keys::AUTOREPLY.guild(guild_id)).await?;
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn get_intelligence_consent(&self, user_id: UserId) -> RedisResult<bool> {
-        let mut conn = self.conn.clone();
-        let value: bool = conn
-            .sismember(keys::INTELLIGENCE_CONSENT, user_id.get())
-            .await?;
-
-        Ok(value)
-    }
-
-    pub async fn add_intelligence_consent(&self, user_id: UserId) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.sadd(keys::INTELLIGENCE_CONSENT, user_id.get()).await?;
-
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn get_intelligence_context(
-        &self,
-        user: UserId,
-        channel: GenericChannelId,
-    ) -> RedisResult<Option<IntelligenceMessages>> {
-        let mut conn = self.conn.clone();
-        let value: Option<IntelligenceMessages> = conn
-            .get(keys::INTELLIGENCE_CONTEXT.user(user).channel(channel))
-            .await?;
-
-        Ok(value)
-    }
-
-    pub async fn set_intelligence_context(
-        &self,
-        user: UserId,
-        channel: GenericChannelId,
-        context: &IntelligenceMessages,
-    ) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn
-            .set_options(
-                keys::INTELLIGENCE_CONTEXT.user(user).channel(channel),
-                context,
-                redis::SetOptions::default().with_expiration(redis::SetExpiry::EX(300)),
-            )
-            .await?;
-
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn incr_warn_count(&self, user: UserId, guild: GuildId) -> RedisResult<u64> {
-        let mut conn = self.conn.clone();
-        let value: u64 = conn
-            .incr(keys::WARN_COUNT.user(user).guild(guild), 1)
-            .await?;
-
-        Ok(value)
-    }
-
-    pub async fn del_warn_count(&self, user: UserId, guild: GuildId) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.del(keys::WARN_COUNT.user(user).guild(guild)).await?;
-
-        Ok(())
-    }
-}
-
-impl Storage {
-    pub async fn get_code_expansion(
-        &self,
-        message: MessageId,
-    ) -> RedisResult<Option<CodeExpansionData>> {
-        let mut conn = self.conn.clone();
-        let value: Option<CodeExpansionData> =
-            conn.get(keys::CODE_EXPANSION.message(message)).await?;
-
-        Ok(value)
-    }
-
-    pub async fn set_code_expansion(
-        &self,
-        message: MessageId,
-        data: CodeExpansionData,
-    ) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn
-            .set_options(
-                keys::CODE_EXPANSION.message(message),
-                data,
-                redis::SetOptions::default().with_expiration(redis::SetExpiry::EX(300)),
-            )
-            .await?;
-
-        Ok(())
-    }
-
-    pub async fn del_code_expansion(&self, original: MessageId) -> RedisResult<()> {
-        let mut conn = self.conn.clone();
-        () = conn.del(keys::CODE_EXPANSION.message(original)).await?;
-
-        Ok(())
-    }
-}
@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use poise::serenity_prelude::UserId;
+use serde::{Deserialize, Serialize};
+
+use crate::utils;
+
+/// A private note left by a moderator on a user in a guild.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Note {
+    pub id: String,
+    pub author: UserId,
+    pub content: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl Note {
+    pub fn new(author: UserId, content: String) -> Self {
+        Self {
+            id: utils::nanoid(8),
+            author,
+            content,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use poise::serenity_prelude::{GuildId, RoleId, UserId};
+use serde::{Deserialize, Serialize};
+
+/// A member whose roles are replaced by the guild's quarantine role.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Quarantine {
+    pub guild: GuildId,
+    pub user: UserId,
+    /// The roles to restore when the quarantine ends.
+    pub roles: Vec<RoleId>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// When the quarantine ends automatically, if ever.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// The moderation case of the quarantine.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub case: Option<u64>,
+}
+
+impl Quarantine {
+    /// The hash field that identifies this quarantine.
+    pub fn field(guild: GuildId, user: UserId) -> String {
+        format!("{guild}:{user}")
+    }
+}
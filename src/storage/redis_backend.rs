@@ -0,0 +1,809 @@
+// SPDX-FileCopyrightText: 2024 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{self as serenity, GenericChannelId, GuildId, MessageId, UserId};
+use redis::{
+    AsyncCommands as _, RedisResult,
+    aio::{ConnectionManager, ConnectionManagerConfig},
+};
+use std::{fmt::Debug, time::Duration};
+
+use super::{
+    StorageBackend,
+    appeal::Appeal,
+    automod::AutomodRule,
+    ban_group::{BanGroup, BanProposal},
+    case::ModerationCase,
+    code_expansion::CodeExpansionData,
+    config_history::{self, ConfigHistoryEntry},
+    keys,
+    lockdown::Lockdown,
+    log::{self, MessageLog, MessageLogQuery},
+    note::Note,
+    presence::PresenceData,
+    quarantine::Quarantine,
+    reminder::ReminderData,
+    temp_ban::TempBan,
+    ttl,
+    warn::Warn,
+};
+use crate::{config::GuildConfig, handlers::intelligence::IntelligenceMessages};
+
+pub struct RedisBackend {
+    conn: ConnectionManager,
+}
+
+impl Debug for RedisBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisBackend").finish_non_exhaustive()
+    }
+}
+
+impl RedisBackend {
+    pub async fn new(redis: redis::Client) -> RedisResult<Self> {
+        let conn = ConnectionManager::new_with_config(
+            redis,
+            ConnectionManagerConfig::new()
+                .set_connection_timeout(Some(Duration::from_secs(10)))
+                .set_response_timeout(Some(Duration::from_mins(1)))
+                .set_number_of_retries(3),
+        )
+        .await?;
+
+        Ok(Self { conn })
+    }
+}
+
+#[serenity::async_trait]
+impl StorageBackend for RedisBackend {
+    async fn size(&self) -> Result<u64> {
+        let mut conn = self.conn.clone();
+        let keys: u64 = redis::cmd("DBSIZE").query_async(&mut conn).await?;
+        Ok(keys)
+    }
+
+    async fn get_schema_version(&self) -> Result<u64> {
+        let mut conn = self.conn.clone();
+        let ret: Option<u64> = conn.get(keys::SCHEMA_VERSION).await?;
+        Ok(ret.unwrap_or_default())
+    }
+
+    async fn set_schema_version(&self, version: u64) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.set(keys::SCHEMA_VERSION, version).await?;
+        Ok(())
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        use futures_util::StreamExt as _;
+
+        let mut conn = self.conn.clone();
+        let values: Vec<String> = conn
+            .scan_match(format!("{prefix}*"))
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<RedisResult<Vec<_>>>()?;
+
+        Ok(values)
+    }
+
+    async fn del_keys(&self, keys: &[String]) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.conn.clone();
+        () = conn.del(keys).await?;
+        Ok(())
+    }
+
+    async fn get_config(&self, guild_id: GuildId) -> Result<GuildConfig> {
+        let mut conn = self.conn.clone();
+        let ret: Option<GuildConfig> = conn.get(keys::GUILD_CONFIG.guild(guild_id)).await?;
+        Ok(ret.unwrap_or_default())
+    }
+
+    async fn set_config(&self, guild_id: GuildId, value: &GuildConfig) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.set(keys::GUILD_CONFIG.guild(guild_id), value).await?;
+        Ok(())
+    }
+
+    async fn del_config(&self, guild_id: GuildId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.del(keys::GUILD_CONFIG.guild(guild_id)).await?;
+        Ok(())
+    }
+
+    async fn get_config_history(&self, guild_id: GuildId) -> Result<Vec<ConfigHistoryEntry>> {
+        let mut conn = self.conn.clone();
+        let ret: Vec<ConfigHistoryEntry> = conn
+            .zrange(keys::CONFIG_HISTORY.guild(guild_id), 0, -1)
+            .await?;
+        Ok(ret)
+    }
+
+    async fn add_config_history(
+        &self,
+        guild_id: GuildId,
+        entry: &ConfigHistoryEntry,
+    ) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .zadd(keys::CONFIG_HISTORY.guild(guild_id), entry, entry.version)
+            .await?;
+
+        if entry.version > config_history::MAX_ENTRIES {
+            () = conn
+                .zrembyscore(
+                    keys::CONFIG_HISTORY.guild(guild_id),
+                    0,
+                    entry.version - config_history::MAX_ENTRIES,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn incr_config_version(&self, guild_id: GuildId) -> Result<u64> {
+        let mut conn = self.conn.clone();
+        let value: u64 = conn.incr(keys::CONFIG_VERSION.guild(guild_id), 1).await?;
+
+        Ok(value)
+    }
+
+    async fn get_config_version(&self, guild_id: GuildId) -> Result<u64> {
+        let mut conn = self.conn.clone();
+        let value: Option<u64> = conn.get(keys::CONFIG_VERSION.guild(guild_id)).await?;
+
+        Ok(value.unwrap_or_default())
+    }
+
+    async fn set_config_version(&self, guild_id: GuildId, version: u64) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .set(keys::CONFIG_VERSION.guild(guild_id), version)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_presence(&self) -> Result<Option<PresenceData>> {
+        let mut conn = self.conn.clone();
+        let ret: Option<PresenceData> = conn.get(keys::PRESENCE).await?;
+        Ok(ret)
+    }
+
+    async fn set_presence(&self, value: &PresenceData) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.set(keys::PRESENCE, value).await?;
+        Ok(())
+    }
+
+    async fn del_presence(&self) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.del(keys::PRESENCE).await?;
+        Ok(())
+    }
+
+    async fn get_starboard(&self, message_id: MessageId) -> Result<Option<u64>> {
+        let mut conn = self.conn.clone();
+        let ret: Option<u64> = conn.get(keys::STARBOARD.message(message_id)).await?;
+        Ok(ret)
+    }
+
+    async fn set_starboard(&self, message_id: MessageId, value: &u64) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .set_options(
+                keys::STARBOARD.message(message_id),
+                value,
+                redis::SetOptions::default()
+                    .with_expiration(redis::SetExpiry::EX(ttl::STARBOARD.as_secs())),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn del_starboard(&self, message_id: MessageId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.del(keys::STARBOARD.message(message_id)).await?;
+        Ok(())
+    }
+
+    async fn get_message_log(&self, message_id: MessageId) -> Result<Option<MessageLog>> {
+        let mut conn = self.conn.clone();
+        let ret: Option<MessageLog> = conn.get(keys::MESSAGE_LOG.message(message_id)).await?;
+        Ok(ret)
+    }
+
+    async fn set_message_log(
+        &self,
+        guild_id: GuildId,
+        message_id: MessageId,
+        value: &MessageLog,
+        ttl: Duration,
+    ) -> Result<()> {
+        let mut conn = self.conn.clone();
+
+        let min_score = chrono::Utc::now().timestamp_millis() - i64::try_from(ttl.as_millis())? - 1;
+        let ttl_secs = i64::try_from(ttl.as_secs())?;
+
+        // Besides the guild-wide index, author and channel indexes narrow down searches
+        let mut indexes = vec![
+            keys::MESSAGE_LOG_INDEX.guild(guild_id),
+            keys::MESSAGE_LOG_INDEX.guild(guild_id).user(value.author),
+        ];
+        if let Some(channel) = value.channel {
+            indexes.push(keys::MESSAGE_LOG_INDEX.guild(guild_id).channel(channel));
+        }
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().set_options(
+            keys::MESSAGE_LOG.message(message_id),
+            value,
+            redis::SetOptions::default().with_expiration(redis::SetExpiry::EX(ttl.as_secs())),
+        );
+
+        for index in indexes {
+            let index = index.to_string();
+
+            pipe.zadd(
+                index.clone(),
+                message_id.get(),
+                log::timestamp_millis(message_id),
+            )
+            .zrembyscore(index.clone(), 0, min_score)
+            .expire(index, ttl_secs);
+        }
+
+        () = pipe.query_async(&mut conn).await?;
+
+        Ok(())
+    }
+
+    async fn del_message_log(&self, message_id: MessageId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.del(keys::MESSAGE_LOG.message(message_id)).await?;
+        Ok(())
+    }
+
+    async fn scan_message_logs(
+        &self,
+        guild_id: GuildId,
+        query: &MessageLogQuery,
+    ) -> Result<Vec<(MessageId, MessageLog)>> {
+        /// The number of logs fetched from the index at a time.
+        const BATCH_SIZE: isize = 100;
+
+        let mut conn = self.conn.clone();
+
+        let index = if let Some(author) = query.author {
+            keys::MESSAGE_LOG_INDEX.guild(guild_id).user(author)
+        } else if let Some(channel) = query.channel {
+            keys::MESSAGE_LOG_INDEX.guild(guild_id).channel(channel)
+        } else {
+            keys::MESSAGE_LOG_INDEX.guild(guild_id)
+        }
+        .to_string();
+
+        let mut results = Vec::new();
+        let mut offset = 0;
+
+        while results.len() < query.limit {
+            let ids: Vec<u64> = conn
+                .zrevrangebyscore_limit(
+                    index.clone(),
+                    query.before.timestamp_millis(),
+                    query.after.timestamp_millis(),
+                    offset,
+                    BATCH_SIZE,
+                )
+                .await?;
+
+            if ids.is_empty() {
+                break;
+            }
+            offset += isize::try_from(ids.len())?;
+
+            let ids = ids.into_iter().map(MessageId::new).collect::<Vec<_>>();
+            let values: Vec<Option<MessageLog>> = conn
+                .mget(
+                    ids.iter()
+                        .map(|id| keys::MESSAGE_LOG.message(*id))
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
+
+            // Logs that expired before the index was pruned are skipped
+            results.extend(
+                ids.into_iter()
+                    .zip(values)
+                    .filter_map(|(id, value)| value.map(|value| (id, value)))
+                    .filter(|(_, log)| query.matches(log)),
+            );
+        }
+
+        results.truncate(query.limit);
+        Ok(results)
+    }
+
+    async fn scan_reminders(&self) -> Result<Vec<ReminderData>> {
+        use futures_util::StreamExt as _;
+
+        let mut conn = self.conn.clone();
+
+        let mut cmd = redis::cmd("ZSCAN");
+        cmd.arg(keys::REMINDERS).cursor_arg(0).arg("NOSCORES");
+
+        let values: Vec<ReminderData> = cmd
+            .iter_async::<ReminderData>(&mut conn)
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<RedisResult<Vec<_>>>()?;
+
+        Ok(values)
+    }
+
+    async fn add_reminders(&self, value: &ReminderData) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .zadd(keys::REMINDERS, value, value.timestamp.timestamp())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn clean_reminders(&self) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .zrembyscore(keys::REMINDERS, 0, chrono::Utc::now().timestamp() - 1)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn scan_temp_bans(&self) -> Result<Vec<TempBan>> {
+        let mut conn = self.conn.clone();
+        let values: Vec<TempBan> = conn.zrange(keys::TEMP_BANS, 0, -1).await?;
+        Ok(values)
+    }
+
+    async fn add_temp_ban(&self, value: &TempBan) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .zadd(keys::TEMP_BANS, value, value.expires_at.timestamp())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn del_temp_ban(&self, value: &TempBan) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.zrem(keys::TEMP_BANS, value).await?;
+        Ok(())
+    }
+
+    async fn scan_autoreply(&self, guild_id: GuildId) -> Result<Vec<(String, String)>> {
+        use futures_util::StreamExt as _;
+
+        let mut conn = self.conn.clone();
+        let values: Vec<(String, String)> = conn
+            .hscan(keys::AUTOREPLY.guild(guild_id))
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<RedisResult<Vec<_>>>()?;
+
+        Ok(values)
+    }
+
+    async fn add_autoreply(&self, guild_id: GuildId, f: &str, v: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hset(keys::AUTOREPLY.guild(guild_id), f, v).await?;
+        Ok(())
+    }
+
+    async fn del_autoreply(&self, guild_id: GuildId, f: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hdel(keys::AUTOREPLY.guild(guild_id), f).await?;
+        Ok(())
+    }
+
+    async fn delall_autoreply(&self, guild_id: GuildId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.del(keys::AUTOREPLY.guild(guild_id)).await?;
+        Ok(())
+    }
+
+    async fn scan_automod_rules(&self, guild: GuildId) -> Result<Vec<AutomodRule>> {
+        let mut conn = self.conn.clone();
+        let mut rules: Vec<AutomodRule> = conn.hvals(keys::AUTOMOD_RULES.guild(guild)).await?;
+        rules.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(rules)
+    }
+
+    async fn set_automod_rule(&self, guild: GuildId, rule: &AutomodRule) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .hset(keys::AUTOMOD_RULES.guild(guild), &rule.id, rule)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn del_automod_rule(&self, guild: GuildId, id: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hdel(keys::AUTOMOD_RULES.guild(guild), id).await?;
+
+        Ok(())
+    }
+
+    async fn get_intelligence_consent(&self, user_id: UserId) -> Result<bool> {
+        let mut conn = self.conn.clone();
+        let value: bool = conn
+            .sismember(keys::INTELLIGENCE_CONSENT, user_id.get())
+            .await?;
+
+        Ok(value)
+    }
+
+    async fn add_intelligence_consent(&self, user_id: UserId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.sadd(keys::INTELLIGENCE_CONSENT, user_id.get()).await?;
+
+        Ok(())
+    }
+
+    async fn get_intelligence_context(
+        &self,
+        user: UserId,
+        channel: GenericChannelId,
+    ) -> Result<Option<IntelligenceMessages>> {
+        let mut conn = self.conn.clone();
+        let value: Option<IntelligenceMessages> = conn
+            .get(keys::INTELLIGENCE_CONTEXT.user(user).channel(channel))
+            .await?;
+
+        Ok(value)
+    }
+
+    async fn set_intelligence_context(
+        &self,
+        user: UserId,
+        channel: GenericChannelId,
+        context: &IntelligenceMessages,
+    ) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .set_options(
+                keys::INTELLIGENCE_CONTEXT.user(user).channel(channel),
+                context,
+                redis::SetOptions::default()
+                    .with_expiration(redis::SetExpiry::EX(ttl::INTELLIGENCE_CONTEXT.as_secs())),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_warn_count(&self, user: UserId, guild: GuildId) -> Result<Option<u64>> {
+        let mut conn = self.conn.clone();
+        let value: Option<u64> = conn.get(keys::WARN_COUNT.user(user).guild(guild)).await?;
+
+        Ok(value)
+    }
+
+    async fn scan_warns(&self, user: UserId, guild: GuildId) -> Result<Vec<Warn>> {
+        let mut conn = self.conn.clone();
+        let mut warns: Vec<Warn> = conn.hvals(keys::WARNS.user(user).guild(guild)).await?;
+        warns.sort_by_key(|warn| warn.timestamp);
+
+        Ok(warns)
+    }
+
+    async fn scan_warned_users(&self, guild: GuildId) -> Result<Vec<UserId>> {
+        use futures_util::StreamExt as _;
+
+        let mut conn = self.conn.clone();
+        let keys: Vec<String> = conn
+            .scan_match(format!("{}:u*:g{guild}", keys::WARNS))
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<RedisResult<Vec<_>>>()?;
+
+        Ok(keys
+            .iter()
+            .filter_map(|key| keys::user_guild(&keys::WARNS, key))
+            .map(|(user, _)| user)
+            .collect())
+    }
+
+    async fn add_warn(&self, user: UserId, guild: GuildId, warn: &Warn) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .hset(keys::WARNS.user(user).guild(guild), &warn.id, warn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn del_warn(&self, user: UserId, guild: GuildId, id: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hdel(keys::WARNS.user(user).guild(guild), id).await?;
+
+        Ok(())
+    }
+
+    async fn del_warns(&self, user: UserId, guild: GuildId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.del(keys::WARNS.user(user).guild(guild)).await?;
+
+        Ok(())
+    }
+
+    async fn scan_notes(&self, user: UserId, guild: GuildId) -> Result<Vec<Note>> {
+        let mut conn = self.conn.clone();
+        let mut notes: Vec<Note> = conn.hvals(keys::NOTES.user(user).guild(guild)).await?;
+        notes.sort_by_key(|note| note.timestamp);
+
+        Ok(notes)
+    }
+
+    async fn scan_noted_users(&self, guild: GuildId) -> Result<Vec<UserId>> {
+        use futures_util::StreamExt as _;
+
+        let mut conn = self.conn.clone();
+        let keys: Vec<String> = conn
+            .scan_match(format!("{}:u*:g{guild}", keys::NOTES))
+            .await?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<RedisResult<Vec<_>>>()?;
+
+        Ok(keys
+            .iter()
+            .filter_map(|key| keys::user_guild(&keys::NOTES, key))
+            .map(|(user, _)| user)
+            .collect())
+    }
+
+    async fn add_note(&self, user: UserId, guild: GuildId, note: &Note) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .hset(keys::NOTES.user(user).guild(guild), &note.id, note)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn del_note(&self, user: UserId, guild: GuildId, id: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hdel(keys::NOTES.user(user).guild(guild), id).await?;
+
+        Ok(())
+    }
+
+    async fn get_lockdown(&self, guild: GuildId) -> Result<Option<Lockdown>> {
+        let mut conn = self.conn.clone();
+        let value: Option<Lockdown> = conn.hget(keys::LOCKDOWNS, guild.get()).await?;
+
+        Ok(value)
+    }
+
+    async fn set_lockdown(&self, lockdown: &Lockdown) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .hset(keys::LOCKDOWNS, lockdown.guild.get(), lockdown)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn del_lockdown(&self, guild: GuildId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hdel(keys::LOCKDOWNS, guild.get()).await?;
+
+        Ok(())
+    }
+
+    async fn scan_lockdowns(&self) -> Result<Vec<Lockdown>> {
+        let mut conn = self.conn.clone();
+        let values: Vec<Lockdown> = conn.hvals(keys::LOCKDOWNS).await?;
+
+        Ok(values)
+    }
+
+    async fn get_quarantine(&self, guild: GuildId, user: UserId) -> Result<Option<Quarantine>> {
+        let mut conn = self.conn.clone();
+        let value: Option<Quarantine> = conn
+            .hget(keys::QUARANTINES, Quarantine::field(guild, user))
+            .await?;
+
+        Ok(value)
+    }
+
+    async fn set_quarantine(&self, quarantine: &Quarantine) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .hset(
+                keys::QUARANTINES,
+                Quarantine::field(quarantine.guild, quarantine.user),
+                quarantine,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn del_quarantine(&self, guild: GuildId, user: UserId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .hdel(keys::QUARANTINES, Quarantine::field(guild, user))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn scan_quarantines(&self) -> Result<Vec<Quarantine>> {
+        let mut conn = self.conn.clone();
+        let values: Vec<Quarantine> = conn.hvals(keys::QUARANTINES).await?;
+
+        Ok(values)
+    }
+
+    async fn incr_case_id(&self, guild: GuildId) -> Result<u64> {
+        let mut conn = self.conn.clone();
+        let value: u64 = conn.incr(keys::CASE_ID.guild(guild), 1).await?;
+
+        Ok(value)
+    }
+
+    async fn get_case_id(&self, guild: GuildId) -> Result<u64> {
+        let mut conn = self.conn.clone();
+        let value: Option<u64> = conn.get(keys::CASE_ID.guild(guild)).await?;
+
+        Ok(value.unwrap_or_default())
+    }
+
+    async fn set_case_id(&self, guild: GuildId, id: u64) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.set(keys::CASE_ID.guild(guild), id).await?;
+
+        Ok(())
+    }
+
+    async fn get_appeal(&self, guild: GuildId, user: UserId) -> Result<Option<Appeal>> {
+        let mut conn = self.conn.clone();
+        let ret: Option<Appeal> = conn.get(keys::APPEALS.guild(guild).user(user)).await?;
+        Ok(ret)
+    }
+
+    async fn set_appeal(&self, guild: GuildId, user: UserId, appeal: &Appeal) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .set(keys::APPEALS.guild(guild).user(user), appeal)
+            .await?;
+        Ok(())
+    }
+
+    async fn scan_ban_groups(&self) -> Result<Vec<BanGroup>> {
+        let mut conn = self.conn.clone();
+        let mut groups: Vec<BanGroup> = conn.hvals(keys::BAN_GROUPS).await?;
+        groups.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(groups)
+    }
+
+    async fn set_ban_group(&self, group: &BanGroup) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hset(keys::BAN_GROUPS, &group.name, group).await?;
+
+        Ok(())
+    }
+
+    async fn del_ban_group(&self, name: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hdel(keys::BAN_GROUPS, name).await?;
+
+        Ok(())
+    }
+
+    async fn get_ban_proposal(&self, guild: GuildId, user: UserId) -> Result<Option<BanProposal>> {
+        let mut conn = self.conn.clone();
+        let ret: Option<BanProposal> = conn
+            .get(keys::BAN_PROPOSALS.guild(guild).user(user))
+            .await?;
+        Ok(ret)
+    }
+
+    async fn set_ban_proposal(
+        &self,
+        guild: GuildId,
+        user: UserId,
+        proposal: &BanProposal,
+    ) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .set(keys::BAN_PROPOSALS.guild(guild).user(user), proposal)
+            .await?;
+        Ok(())
+    }
+
+    async fn del_ban_proposal(&self, guild: GuildId, user: UserId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .del(keys::BAN_PROPOSALS.guild(guild).user(user))
+            .await?;
+        Ok(())
+    }
+
+    async fn get_case(&self, guild: GuildId, id: u64) -> Result<Option<ModerationCase>> {
+        let mut conn = self.conn.clone();
+        let value: Option<ModerationCase> = conn.hget(keys::CASES.guild(guild), id).await?;
+
+        Ok(value)
+    }
+
+    async fn set_case(&self, guild: GuildId, case: &ModerationCase) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hset(keys::CASES.guild(guild), case.id, case).await?;
+
+        Ok(())
+    }
+
+    async fn del_case(&self, guild: GuildId, id: u64) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.hdel(keys::CASES.guild(guild), id).await?;
+
+        Ok(())
+    }
+
+    async fn scan_cases(&self, guild: GuildId) -> Result<Vec<ModerationCase>> {
+        let mut conn = self.conn.clone();
+        let values: Vec<ModerationCase> = conn.hvals(keys::CASES.guild(guild)).await?;
+
+        Ok(values)
+    }
+
+    async fn get_code_expansion(&self, message: MessageId) -> Result<Option<CodeExpansionData>> {
+        let mut conn = self.conn.clone();
+        let value: Option<CodeExpansionData> =
+            conn.get(keys::CODE_EXPANSION.message(message)).await?;
+
+        Ok(value)
+    }
+
+    async fn set_code_expansion(&self, message: MessageId, data: CodeExpansionData) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn
+            .set_options(
+                keys::CODE_EXPANSION.message(message),
+                data,
+                redis::SetOptions::default()
+                    .with_expiration(redis::SetExpiry::EX(ttl::CODE_EXPANSION.as_secs())),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn del_code_expansion(&self, original: MessageId) -> Result<()> {
+        let mut conn = self.conn.clone();
+        () = conn.del(keys::CODE_EXPANSION.message(original)).await?;
+
+        Ok(())
+    }
+}
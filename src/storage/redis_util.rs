@@ -5,8 +5,20 @@
 use crate::{config::GuildConfig, handlers::intelligence::IntelligenceMessages};
 
 use super::{
-    code_expansion::CodeExpansionData, log::MessageLog, presence::PresenceData,
+    appeal::Appeal,
+    automod::AutomodRule,
+    ban_group::{BanGroup, BanProposal},
+    case::ModerationCase,
+    code_expansion::CodeExpansionData,
+    config_history::ConfigHistoryEntry,
+    lockdown::Lockdown,
+    log::MessageLog,
+    note::Note,
+    presence::PresenceData,
+    quarantine::Quarantine,
     reminder::ReminderData,
+    temp_ban::TempBan,
+    warn::Warn,
 };
 
 macro_rules! impl_redis_serde {
@@ -42,9 +54,20 @@ macro_rules! impl_redis_serde {
 
 impl_redis_serde!(
     PresenceData,
+    Appeal,
+    AutomodRule,
+    BanGroup,
+    BanProposal,
+    Lockdown,
+    Quarantine,
     ReminderData,
+    TempBan,
     MessageLog,
     GuildConfig,
+    ConfigHistoryEntry,
+    ModerationCase,
     IntelligenceMessages,
     CodeExpansionData,
+    Warn,
+    Note,
 );
@@ -0,0 +1,1614 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::{Result, eyre};
+use poise::serenity_prelude::{self as serenity, GenericChannelId, GuildId, MessageId, UserId};
+use rusqlite::{Connection, OptionalExtension as _, params};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    fmt::Debug,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::task;
+
+use super::{
+    StorageBackend,
+    appeal::Appeal,
+    automod::AutomodRule,
+    ban_group::{BanGroup, BanProposal},
+    case::ModerationCase,
+    code_expansion::CodeExpansionData,
+    config_history::{self, ConfigHistoryEntry},
+    keys::{self, StorageKey},
+    lockdown::Lockdown,
+    log::{self, MessageLog, MessageLogQuery},
+    note::Note,
+    presence::PresenceData,
+    quarantine::Quarantine,
+    reminder::ReminderData,
+    temp_ban::TempBan,
+    ttl,
+    warn::Warn,
+};
+use crate::{config::GuildConfig, handlers::intelligence::IntelligenceMessages};
+
+static SCHEMA: &str = r"
+CREATE TABLE IF NOT EXISTS kv (
+    key TEXT PRIMARY KEY NOT NULL,
+    value BLOB NOT NULL,
+    expires_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS kv_expires_at ON kv (expires_at);
+
+CREATE TABLE IF NOT EXISTS hashes (
+    key TEXT NOT NULL,
+    field TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (key, field)
+);
+
+CREATE TABLE IF NOT EXISTS sets (
+    key TEXT NOT NULL,
+    member TEXT NOT NULL,
+    PRIMARY KEY (key, member)
+);
+
+CREATE TABLE IF NOT EXISTS sorted_sets (
+    key TEXT NOT NULL,
+    member BLOB NOT NULL,
+    score INTEGER NOT NULL,
+    PRIMARY KEY (key, member)
+);
+";
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn kv_get(conn: &Connection, key: &str) -> rusqlite::Result<Option<Vec<u8>>> {
+    conn.query_row(
+        "SELECT value FROM kv WHERE key = ?1 AND (expires_at IS NULL OR expires_at > ?2)",
+        params![key, now_millis()],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+fn kv_set(
+    conn: &Connection,
+    key: &str,
+    value: &[u8],
+    ttl: Option<Duration>,
+) -> rusqlite::Result<()> {
+    let now = now_millis();
+    let expires_at =
+        ttl.map(|ttl| now.saturating_add(i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX)));
+
+    conn.execute("DELETE FROM kv WHERE expires_at <= ?1", params![now])?;
+    conn.execute(
+        "INSERT INTO kv (key, value, expires_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value, expires_at = excluded.expires_at",
+        params![key, value, expires_at],
+    )?;
+
+    Ok(())
+}
+
+fn kv_del(conn: &Connection, key: &str) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM kv WHERE key = ?1", params![key])?;
+    Ok(())
+}
+
+/// An embedded storage backend that emulates the subset of Redis data structures used by Valfisk on top of SQLite.
+pub struct SqliteBackend {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl Debug for SqliteBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteBackend").finish_non_exhaustive()
+    }
+}
+
+impl SqliteBackend {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::init(conn)
+    }
+
+    #[cfg(test)]
+    pub fn memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    async fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+
+        task::spawn_blocking(move || {
+            let conn = conn
+                .lock()
+                .map_err(|_| eyre!("SQLite connection mutex was poisoned"))?;
+
+            Ok(f(&conn)?)
+        })
+        .await?
+    }
+
+    async fn get<T>(&self, key: StorageKey) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let key = key.to_string();
+        let value = self.call(move |conn| kv_get(conn, &key)).await?;
+
+        Ok(value
+            .map(|value| serde_json::from_slice(&value))
+            .transpose()?)
+    }
+
+    async fn set<T>(&self, key: StorageKey, value: &T, ttl: Option<Duration>) -> Result<()>
+    where
+        T: Serialize + Sync + ?Sized,
+    {
+        let key = key.to_string();
+        let value = serde_json::to_vec(value)?;
+
+        self.call(move |conn| kv_set(conn, &key, &value, ttl)).await
+    }
+
+    async fn del(&self, key: StorageKey) -> Result<()> {
+        let key = key.to_string();
+        self.call(move |conn| kv_del(conn, &key)).await
+    }
+}
+
+#[serenity::async_trait]
+impl StorageBackend for SqliteBackend {
+    async fn size(&self) -> Result<u64> {
+        let keys: i64 = self
+            .call(|conn| {
+                conn.query_row(
+                    "SELECT
+                        (SELECT COUNT(*) FROM kv WHERE expires_at IS NULL OR expires_at > ?1)
+                        + (SELECT COUNT(DISTINCT key) FROM hashes)
+                        + (SELECT COUNT(DISTINCT key) FROM sets)
+                        + (SELECT COUNT(DISTINCT key) FROM sorted_sets)",
+                    params![now_millis()],
+                    |row| row.get(0),
+                )
+            })
+            .await?;
+
+        Ok(keys.try_into()?)
+    }
+
+    async fn get_schema_version(&self) -> Result<u64> {
+        let ret: Option<u64> = self.get(keys::SCHEMA_VERSION).await?;
+        Ok(ret.unwrap_or_default())
+    }
+
+    async fn set_schema_version(&self, version: u64) -> Result<()> {
+        self.set(keys::SCHEMA_VERSION, &version, None).await
+    }
+
+    async fn scan_prefix(&self, prefix: &str) -> Result<Vec<String>> {
+        let prefix = prefix.to_owned();
+
+        self.call(move |conn| {
+            conn.prepare(
+                "SELECT key FROM kv WHERE substr(key, 1, length(?1)) = ?1
+                    AND (expires_at IS NULL OR expires_at > ?2)
+                UNION SELECT key FROM hashes WHERE substr(key, 1, length(?1)) = ?1
+                UNION SELECT key FROM sets WHERE substr(key, 1, length(?1)) = ?1
+                UNION SELECT key FROM sorted_sets WHERE substr(key, 1, length(?1)) = ?1",
+            )?
+            .query_map(params![prefix, now_millis()], |row| row.get(0))?
+            .collect()
+        })
+        .await
+    }
+
+    async fn del_keys(&self, keys: &[String]) -> Result<()> {
+        let keys = keys.to_vec();
+
+        self.call(move |conn| {
+            let tx = conn.unchecked_transaction()?;
+
+            for key in &keys {
+                for table in ["kv", "hashes", "sets", "sorted_sets"] {
+                    tx.execute(&format!("DELETE FROM {table} WHERE key = ?1"), params![key])?;
+                }
+            }
+
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn get_config(&self, guild_id: GuildId) -> Result<GuildConfig> {
+        let ret: Option<GuildConfig> = self.get(keys::GUILD_CONFIG.guild(guild_id)).await?;
+        Ok(ret.unwrap_or_default())
+    }
+
+    async fn set_config(&self, guild_id: GuildId, value: &GuildConfig) -> Result<()> {
+        self.set(keys::GUILD_CONFIG.guild(guild_id), value, None)
+            .await
+    }
+
+    async fn del_config(&self, guild_id: GuildId) -> Result<()> {
+        self.del(keys::GUILD_CONFIG.guild(guild_id)).await
+    }
+
+    async fn get_config_history(&self, guild_id: GuildId) -> Result<Vec<ConfigHistoryEntry>> {
+        let key = keys::CONFIG_HISTORY.guild(guild_id).to_string();
+
+        let values: Vec<Vec<u8>> = self
+            .call(move |conn| {
+                conn.prepare("SELECT member FROM sorted_sets WHERE key = ?1 ORDER BY score")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(values
+            .iter()
+            .map(|value| serde_json::from_slice(value))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn add_config_history(
+        &self,
+        guild_id: GuildId,
+        entry: &ConfigHistoryEntry,
+    ) -> Result<()> {
+        let key = keys::CONFIG_HISTORY.guild(guild_id).to_string();
+        let member = serde_json::to_vec(entry)?;
+        let score = i64::try_from(entry.version)?;
+        let min = score - i64::try_from(config_history::MAX_ENTRIES)?;
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO sorted_sets (key, member, score) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, member) DO UPDATE SET score = excluded.score",
+                params![key, member, score],
+            )?;
+            conn.execute(
+                "DELETE FROM sorted_sets WHERE key = ?1 AND score <= ?2",
+                params![key, min],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn incr_config_version(&self, guild_id: GuildId) -> Result<u64> {
+        let key = keys::CONFIG_VERSION.guild(guild_id).to_string();
+
+        self.call(move |conn| {
+            let value = kv_get(conn, &key)?
+                .and_then(|value| serde_json::from_slice::<u64>(&value).ok())
+                .unwrap_or_default()
+                + 1;
+
+            kv_set(conn, &key, value.to_string().as_bytes(), None)?;
+            Ok(value)
+        })
+        .await
+    }
+
+    async fn get_config_version(&self, guild_id: GuildId) -> Result<u64> {
+        Ok(self
+            .get(keys::CONFIG_VERSION.guild(guild_id))
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn set_config_version(&self, guild_id: GuildId, version: u64) -> Result<()> {
+        self.set(keys::CONFIG_VERSION.guild(guild_id), &version, None)
+            .await
+    }
+
+    async fn get_presence(&self) -> Result<Option<PresenceData>> {
+        self.get(keys::PRESENCE).await
+    }
+
+    async fn set_presence(&self, value: &PresenceData) -> Result<()> {
+        self.set(keys::PRESENCE, value, None).await
+    }
+
+    async fn del_presence(&self) -> Result<()> {
+        self.del(keys::PRESENCE).await
+    }
+
+    async fn get_starboard(&self, message_id: MessageId) -> Result<Option<u64>> {
+        self.get(keys::STARBOARD.message(message_id)).await
+    }
+
+    async fn set_starboard(&self, message_id: MessageId, value: &u64) -> Result<()> {
+        self.set(
+            keys::STARBOARD.message(message_id),
+            value,
+            Some(ttl::STARBOARD),
+        )
+        .await
+    }
+
+    async fn del_starboard(&self, message_id: MessageId) -> Result<()> {
+        self.del(keys::STARBOARD.message(message_id)).await
+    }
+
+    async fn get_message_log(&self, message_id: MessageId) -> Result<Option<MessageLog>> {
+        self.get(keys::MESSAGE_LOG.message(message_id)).await
+    }
+
+    async fn set_message_log(
+        &self,
+        guild_id: GuildId,
+        message_id: MessageId,
+        value: &MessageLog,
+        ttl: Duration,
+    ) -> Result<()> {
+        let key = keys::MESSAGE_LOG.message(message_id).to_string();
+        let index = keys::MESSAGE_LOG_INDEX.guild(guild_id).to_string();
+        let value = serde_json::to_vec(value)?;
+        let member = message_id.get().to_string();
+        let score = log::timestamp_millis(message_id);
+        let min = now_millis() - i64::try_from(ttl.as_millis())?;
+
+        self.call(move |conn| {
+            kv_set(conn, &key, &value, Some(ttl))?;
+            conn.execute(
+                "INSERT INTO sorted_sets (key, member, score) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, member) DO UPDATE SET score = excluded.score",
+                params![index, member, score],
+            )?;
+            conn.execute(
+                "DELETE FROM sorted_sets WHERE key = ?1 AND score < ?2",
+                params![index, min],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_message_log(&self, message_id: MessageId) -> Result<()> {
+        self.del(keys::MESSAGE_LOG.message(message_id)).await
+    }
+
+    async fn scan_message_logs(
+        &self,
+        guild_id: GuildId,
+        query: &MessageLogQuery,
+    ) -> Result<Vec<(MessageId, MessageLog)>> {
+        let index = keys::MESSAGE_LOG_INDEX.guild(guild_id).to_string();
+        // Index members are message IDs, which complete this prefix to the key of their log
+        let prefix = format!("{}:m", keys::MESSAGE_LOG);
+        let (after, before) = (
+            query.after.timestamp_millis(),
+            query.before.timestamp_millis(),
+        );
+        let author = query.author.map(|id| id.get().to_string());
+        let channel = query.channel.map(|id| id.get().to_string());
+        let contains = query.contains.clone();
+        let limit = i64::try_from(query.limit)?;
+
+        // Logs that expired before the index was pruned are skipped
+        let rows: Vec<(String, Vec<u8>)> = self
+            .call(move |conn| {
+                conn.prepare(
+                    "SELECT sorted_sets.member, kv.value FROM sorted_sets
+                     JOIN kv ON kv.key = ?2 || sorted_sets.member
+                     WHERE sorted_sets.key = ?1
+                       AND sorted_sets.score >= ?3 AND sorted_sets.score <= ?4
+                       AND (kv.expires_at IS NULL OR kv.expires_at > ?5)
+                       AND (?6 IS NULL OR CAST(json_extract(CAST(kv.value AS TEXT), '$.author') AS TEXT) = ?6)
+                       AND (?7 IS NULL OR CAST(json_extract(CAST(kv.value AS TEXT), '$.channel') AS TEXT) = ?7)
+                       AND (?8 IS NULL OR instr(lower(json_extract(CAST(kv.value AS TEXT), '$.content')), lower(?8)) > 0)
+                     ORDER BY sorted_sets.score DESC
+                     LIMIT ?9",
+                )?
+                .query_map(
+                    params![
+                        index,
+                        prefix,
+                        after,
+                        before,
+                        now_millis(),
+                        author,
+                        channel,
+                        contains,
+                        limit
+                    ],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )?
+                .collect()
+            })
+            .await?;
+
+        rows.into_iter()
+            .filter_map(|(member, value)| {
+                let id = MessageId::new(member.parse().ok()?);
+                Some(
+                    serde_json::from_slice(&value)
+                        .map(|log| (id, log))
+                        .map_err(Into::into),
+                )
+            })
+            .collect()
+    }
+
+    async fn scan_reminders(&self) -> Result<Vec<ReminderData>> {
+        let key = keys::REMINDERS.to_string();
+
+        let values: Vec<Vec<u8>> = self
+            .call(move |conn| {
+                conn.prepare("SELECT member FROM sorted_sets WHERE key = ?1 ORDER BY score")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(values
+            .iter()
+            .map(|value| serde_json::from_slice(value))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn add_reminders(&self, value: &ReminderData) -> Result<()> {
+        let key = keys::REMINDERS.to_string();
+        let member = serde_json::to_vec(value)?;
+        let score = value.timestamp.timestamp();
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO sorted_sets (key, member, score) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, member) DO UPDATE SET score = excluded.score",
+                params![key, member, score],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn clean_reminders(&self) -> Result<()> {
+        let key = keys::REMINDERS.to_string();
+        let max = chrono::Utc::now().timestamp() - 1;
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM sorted_sets WHERE key = ?1 AND score BETWEEN 0 AND ?2",
+                params![key, max],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn scan_temp_bans(&self) -> Result<Vec<TempBan>> {
+        let key = keys::TEMP_BANS.to_string();
+
+        let values: Vec<Vec<u8>> = self
+            .call(move |conn| {
+                conn.prepare("SELECT member FROM sorted_sets WHERE key = ?1 ORDER BY score")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(values
+            .iter()
+            .map(|value| serde_json::from_slice(value))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn add_temp_ban(&self, value: &TempBan) -> Result<()> {
+        let key = keys::TEMP_BANS.to_string();
+        let member = serde_json::to_vec(value)?;
+        let score = value.expires_at.timestamp();
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO sorted_sets (key, member, score) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, member) DO UPDATE SET score = excluded.score",
+                params![key, member, score],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_temp_ban(&self, value: &TempBan) -> Result<()> {
+        let key = keys::TEMP_BANS.to_string();
+        let member = serde_json::to_vec(value)?;
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM sorted_sets WHERE key = ?1 AND member = ?2",
+                params![key, member],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn scan_autoreply(&self, guild_id: GuildId) -> Result<Vec<(String, String)>> {
+        let key = keys::AUTOREPLY.guild(guild_id).to_string();
+
+        self.call(move |conn| {
+            conn.prepare("SELECT field, value FROM hashes WHERE key = ?1")?
+                .query_map(params![key], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect()
+        })
+        .await
+    }
+
+    async fn add_autoreply(&self, guild_id: GuildId, f: &str, v: &str) -> Result<()> {
+        let key = keys::AUTOREPLY.guild(guild_id).to_string();
+        let (f, v) = (f.to_owned(), v.to_owned());
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![key, f, v],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_autoreply(&self, guild_id: GuildId, f: &str) -> Result<()> {
+        let key = keys::AUTOREPLY.guild(guild_id).to_string();
+        let f = f.to_owned();
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                params![key, f],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delall_autoreply(&self, guild_id: GuildId) -> Result<()> {
+        let key = keys::AUTOREPLY.guild(guild_id).to_string();
+
+        self.call(move |conn| {
+            conn.execute("DELETE FROM hashes WHERE key = ?1", params![key])?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn scan_automod_rules(&self, guild: GuildId) -> Result<Vec<AutomodRule>> {
+        let key = keys::AUTOMOD_RULES.guild(guild).to_string();
+
+        let values: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT value FROM hashes WHERE key = ?1 ORDER BY field")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(values
+            .iter()
+            .map(|value| serde_json::from_str(value))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn set_automod_rule(&self, guild: GuildId, rule: &AutomodRule) -> Result<()> {
+        let key = keys::AUTOMOD_RULES.guild(guild).to_string();
+        let (field, value) = (rule.id.clone(), serde_json::to_string(rule)?);
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![key, field, value],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_automod_rule(&self, guild: GuildId, id: &str) -> Result<()> {
+        let key = keys::AUTOMOD_RULES.guild(guild).to_string();
+        let id = id.to_owned();
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                params![key, id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_intelligence_consent(&self, user_id: UserId) -> Result<bool> {
+        let key = keys::INTELLIGENCE_CONSENT.to_string();
+        let member = user_id.get().to_string();
+
+        self.call(move |conn| {
+            conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM sets WHERE key = ?1 AND member = ?2)",
+                params![key, member],
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn add_intelligence_consent(&self, user_id: UserId) -> Result<()> {
+        let key = keys::INTELLIGENCE_CONSENT.to_string();
+        let member = user_id.get().to_string();
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO sets (key, member) VALUES (?1, ?2)",
+                params![key, member],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_intelligence_context(
+        &self,
+        user: UserId,
+        channel: GenericChannelId,
+    ) -> Result<Option<IntelligenceMessages>> {
+        self.get(keys::INTELLIGENCE_CONTEXT.user(user).channel(channel))
+            .await
+    }
+
+    async fn set_intelligence_context(
+        &self,
+        user: UserId,
+        channel: GenericChannelId,
+        context: &IntelligenceMessages,
+    ) -> Result<()> {
+        self.set(
+            keys::INTELLIGENCE_CONTEXT.user(user).channel(channel),
+            context,
+            Some(ttl::INTELLIGENCE_CONTEXT),
+        )
+        .await
+    }
+
+    async fn get_warn_count(&self, user: UserId, guild: GuildId) -> Result<Option<u64>> {
+        self.get(keys::WARN_COUNT.user(user).guild(guild)).await
+    }
+
+    async fn scan_warns(&self, user: UserId, guild: GuildId) -> Result<Vec<Warn>> {
+        let key = keys::WARNS.user(user).guild(guild).to_string();
+
+        let values: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT value FROM hashes WHERE key = ?1")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        let mut warns = values
+            .iter()
+            .map(|value| serde_json::from_str(value))
+            .collect::<Result<Vec<Warn>, _>>()?;
+        warns.sort_by_key(|warn| warn.timestamp);
+
+        Ok(warns)
+    }
+
+    async fn scan_warned_users(&self, guild: GuildId) -> Result<Vec<UserId>> {
+        let pattern = format!("{}:u%:g{guild}", keys::WARNS);
+
+        let keys: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT DISTINCT key FROM hashes WHERE key LIKE ?1")?
+                    .query_map(params![pattern], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(keys
+            .iter()
+            .filter_map(|key| keys::user_guild(&keys::WARNS, key))
+            .map(|(user, _)| user)
+            .collect())
+    }
+
+    async fn add_warn(&self, user: UserId, guild: GuildId, warn: &Warn) -> Result<()> {
+        let key = keys::WARNS.user(user).guild(guild).to_string();
+        let (field, value) = (warn.id.clone(), serde_json::to_string(warn)?);
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![key, field, value],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_warn(&self, user: UserId, guild: GuildId, id: &str) -> Result<()> {
+        let key = keys::WARNS.user(user).guild(guild).to_string();
+        let id = id.to_owned();
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                params![key, id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_warns(&self, user: UserId, guild: GuildId) -> Result<()> {
+        let key = keys::WARNS.user(user).guild(guild).to_string();
+
+        self.call(move |conn| {
+            conn.execute("DELETE FROM hashes WHERE key = ?1", params![key])?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn scan_notes(&self, user: UserId, guild: GuildId) -> Result<Vec<Note>> {
+        let key = keys::NOTES.user(user).guild(guild).to_string();
+
+        let values: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT value FROM hashes WHERE key = ?1")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        let mut notes = values
+            .iter()
+            .map(|value| serde_json::from_str(value))
+            .collect::<Result<Vec<Note>, _>>()?;
+        notes.sort_by_key(|note| note.timestamp);
+
+        Ok(notes)
+    }
+
+    async fn scan_noted_users(&self, guild: GuildId) -> Result<Vec<UserId>> {
+        let pattern = format!("{}:u%:g{guild}", keys::NOTES);
+
+        let keys: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT DISTINCT key FROM hashes WHERE key LIKE ?1")?
+                    .query_map(params![pattern], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(keys
+            .iter()
+            .filter_map(|key| keys::user_guild(&keys::NOTES, key))
+            .map(|(user, _)| user)
+            .collect())
+    }
+
+    async fn add_note(&self, user: UserId, guild: GuildId, note: &Note) -> Result<()> {
+        let key = keys::NOTES.user(user).guild(guild).to_string();
+        let (field, value) = (note.id.clone(), serde_json::to_string(note)?);
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![key, field, value],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_note(&self, user: UserId, guild: GuildId, id: &str) -> Result<()> {
+        let key = keys::NOTES.user(user).guild(guild).to_string();
+        let id = id.to_owned();
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                params![key, id],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_lockdown(&self, guild: GuildId) -> Result<Option<Lockdown>> {
+        let key = keys::LOCKDOWNS.to_string();
+
+        let value: Option<String> = self
+            .call(move |conn| {
+                conn.query_row(
+                    "SELECT value FROM hashes WHERE key = ?1 AND field = ?2",
+                    params![key, guild.to_string()],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await?;
+
+        Ok(value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?)
+    }
+
+    async fn set_lockdown(&self, lockdown: &Lockdown) -> Result<()> {
+        let key = keys::LOCKDOWNS.to_string();
+        let (field, value) = (lockdown.guild.to_string(), serde_json::to_string(lockdown)?);
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![key, field, value],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_lockdown(&self, guild: GuildId) -> Result<()> {
+        let key = keys::LOCKDOWNS.to_string();
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                params![key, guild.to_string()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn scan_lockdowns(&self) -> Result<Vec<Lockdown>> {
+        let key = keys::LOCKDOWNS.to_string();
+
+        let values: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT value FROM hashes WHERE key = ?1")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(values
+            .iter()
+            .map(|value| serde_json::from_str(value))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn get_quarantine(&self, guild: GuildId, user: UserId) -> Result<Option<Quarantine>> {
+        let key = keys::QUARANTINES.to_string();
+        let field = Quarantine::field(guild, user);
+
+        let value: Option<String> = self
+            .call(move |conn| {
+                conn.query_row(
+                    "SELECT value FROM hashes WHERE key = ?1 AND field = ?2",
+                    params![key, field],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await?;
+
+        Ok(value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?)
+    }
+
+    async fn set_quarantine(&self, quarantine: &Quarantine) -> Result<()> {
+        let key = keys::QUARANTINES.to_string();
+        let (field, value) = (
+            Quarantine::field(quarantine.guild, quarantine.user),
+            serde_json::to_string(quarantine)?,
+        );
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![key, field, value],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_quarantine(&self, guild: GuildId, user: UserId) -> Result<()> {
+        let key = keys::QUARANTINES.to_string();
+        let field = Quarantine::field(guild, user);
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                params![key, field],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn scan_quarantines(&self) -> Result<Vec<Quarantine>> {
+        let key = keys::QUARANTINES.to_string();
+
+        let values: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT value FROM hashes WHERE key = ?1")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(values
+            .iter()
+            .map(|value| serde_json::from_str(value))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn incr_case_id(&self, guild: GuildId) -> Result<u64> {
+        let key = keys::CASE_ID.guild(guild).to_string();
+
+        self.call(move |conn| {
+            let value = kv_get(conn, &key)?
+                .and_then(|value| serde_json::from_slice::<u64>(&value).ok())
+                .unwrap_or_default()
+                + 1;
+
+            kv_set(conn, &key, value.to_string().as_bytes(), None)?;
+            Ok(value)
+        })
+        .await
+    }
+
+    async fn get_case_id(&self, guild: GuildId) -> Result<u64> {
+        let key = keys::CASE_ID.guild(guild).to_string();
+
+        self.call(move |conn| {
+            Ok(kv_get(conn, &key)?
+                .and_then(|value| serde_json::from_slice::<u64>(&value).ok())
+                .unwrap_or_default())
+        })
+        .await
+    }
+
+    async fn set_case_id(&self, guild: GuildId, id: u64) -> Result<()> {
+        let key = keys::CASE_ID.guild(guild).to_string();
+
+        self.call(move |conn| kv_set(conn, &key, id.to_string().as_bytes(), None))
+            .await
+    }
+
+    async fn get_appeal(&self, guild: GuildId, user: UserId) -> Result<Option<Appeal>> {
+        self.get(keys::APPEALS.guild(guild).user(user)).await
+    }
+
+    async fn set_appeal(&self, guild: GuildId, user: UserId, appeal: &Appeal) -> Result<()> {
+        self.set(keys::APPEALS.guild(guild).user(user), appeal, None)
+            .await
+    }
+
+    async fn scan_ban_groups(&self) -> Result<Vec<BanGroup>> {
+        let key = keys::BAN_GROUPS.to_string();
+
+        let values: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT value FROM hashes WHERE key = ?1 ORDER BY field")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(values
+            .iter()
+            .map(|value| serde_json::from_str(value))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn set_ban_group(&self, group: &BanGroup) -> Result<()> {
+        let key = keys::BAN_GROUPS.to_string();
+        let (field, value) = (group.name.clone(), serde_json::to_string(group)?);
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![key, field, value],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_ban_group(&self, name: &str) -> Result<()> {
+        let key = keys::BAN_GROUPS.to_string();
+        let name = name.to_owned();
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                params![key, name],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_ban_proposal(&self, guild: GuildId, user: UserId) -> Result<Option<BanProposal>> {
+        self.get(keys::BAN_PROPOSALS.guild(guild).user(user)).await
+    }
+
+    async fn set_ban_proposal(
+        &self,
+        guild: GuildId,
+        user: UserId,
+        proposal: &BanProposal,
+    ) -> Result<()> {
+        self.set(keys::BAN_PROPOSALS.guild(guild).user(user), proposal, None)
+            .await
+    }
+
+    async fn del_ban_proposal(&self, guild: GuildId, user: UserId) -> Result<()> {
+        self.del(keys::BAN_PROPOSALS.guild(guild).user(user)).await
+    }
+
+    async fn get_case(&self, guild: GuildId, id: u64) -> Result<Option<ModerationCase>> {
+        let key = keys::CASES.guild(guild).to_string();
+
+        let value: Option<String> = self
+            .call(move |conn| {
+                conn.query_row(
+                    "SELECT value FROM hashes WHERE key = ?1 AND field = ?2",
+                    params![key, id.to_string()],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await?;
+
+        Ok(value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?)
+    }
+
+    async fn set_case(&self, guild: GuildId, case: &ModerationCase) -> Result<()> {
+        let key = keys::CASES.guild(guild).to_string();
+        let (field, value) = (case.id.to_string(), serde_json::to_string(case)?);
+
+        self.call(move |conn| {
+            conn.execute(
+                "INSERT INTO hashes (key, field, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (key, field) DO UPDATE SET value = excluded.value",
+                params![key, field, value],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn del_case(&self, guild: GuildId, id: u64) -> Result<()> {
+        let key = keys::CASES.guild(guild).to_string();
+
+        self.call(move |conn| {
+            conn.execute(
+                "DELETE FROM hashes WHERE key = ?1 AND field = ?2",
+                params![key, id.to_string()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn scan_cases(&self, guild: GuildId) -> Result<Vec<ModerationCase>> {
+        let key = keys::CASES.guild(guild).to_string();
+
+        let values: Vec<String> = self
+            .call(move |conn| {
+                conn.prepare("SELECT value FROM hashes WHERE key = ?1")?
+                    .query_map(params![key], |row| row.get(0))?
+                    .collect()
+            })
+            .await?;
+
+        Ok(values
+            .iter()
+            .map(|value| serde_json::from_str(value))
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn get_code_expansion(&self, message: MessageId) -> Result<Option<CodeExpansionData>> {
+        self.get(keys::CODE_EXPANSION.message(message)).await
+    }
+
+    async fn set_code_expansion(&self, message: MessageId, data: CodeExpansionData) -> Result<()> {
+        self.set(
+            keys::CODE_EXPANSION.message(message),
+            &data,
+            Some(ttl::CODE_EXPANSION),
+        )
+        .await
+    }
+
+    async fn del_code_expansion(&self, original: MessageId) -> Result<()> {
+        self.del(keys::CODE_EXPANSION.message(original)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        automod::{AutomodAction, AutomodPattern},
+        ban_group::BanGroupPolicy,
+    };
+    use poise::serenity_prelude::RoleId;
+
+    #[tokio::test]
+    async fn config_roundtrip() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let guild = GuildId::new(1234);
+
+        assert!(storage.get_config(guild).await?.moderator_role.is_none());
+
+        let config = GuildConfig {
+            starboard_threshold: Some(5),
+            ..Default::default()
+        };
+
+        storage.set_config(guild, &config).await?;
+        assert_eq!(
+            storage.get_config(guild).await?.starboard_threshold,
+            Some(5)
+        );
+
+        storage.del_config(guild).await?;
+        assert_eq!(storage.get_config(guild).await?.starboard_threshold, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn expired_values_are_hidden() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+
+        storage
+            .set(keys::STARBOARD.message(MessageId::new(1)), &1u64, None)
+            .await?;
+        storage
+            .set(
+                keys::STARBOARD.message(MessageId::new(2)),
+                &2u64,
+                Some(Duration::ZERO),
+            )
+            .await?;
+
+        assert_eq!(storage.get_starboard(MessageId::new(1)).await?, Some(1));
+        assert_eq!(storage.get_starboard(MessageId::new(2)).await?, None);
+        assert_eq!(storage.size().await?, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scan_and_delete_prefix() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let guild = GuildId::new(1);
+
+        storage
+            .set(
+                StorageKey::new("old-v1").message(MessageId::new(1)),
+                &1u64,
+                None,
+            )
+            .await?;
+        storage.add_autoreply(guild, "a", "1").await?;
+        storage.set_config(guild, &GuildConfig::default()).await?;
+
+        let mut keys = storage.scan_prefix("old-v1:").await?;
+        keys.extend(storage.scan_prefix("autoreply-v2:").await?);
+        assert_eq!(
+            keys,
+            vec!["old-v1:m1".to_owned(), "autoreply-v2:g1".to_owned()]
+        );
+
+        storage.del_keys(&keys).await?;
+        assert!(storage.scan_prefix("old-v1:").await?.is_empty());
+        assert!(storage.scan_autoreply(guild).await?.is_empty());
+        assert_eq!(storage.size().await?, 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn warns_hash() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (user, guild) = (UserId::new(1), GuildId::new(2));
+
+        let first = Warn::new(Some(1));
+        let second = Warn {
+            timestamp: first.timestamp + chrono::Duration::seconds(1),
+            ..Warn::new(Some(2))
+        };
+
+        storage.add_warn(user, guild, &first).await?;
+        storage.add_warn(user, guild, &second).await?;
+        storage
+            .add_warn(UserId::new(3), guild, &Warn::new(None))
+            .await?;
+        storage
+            .add_warn(user, GuildId::new(4), &Warn::new(None))
+            .await?;
+
+        assert_eq!(
+            storage.scan_warns(user, guild).await?,
+            vec![first.clone(), second.clone()]
+        );
+
+        let mut users = storage.scan_warned_users(guild).await?;
+        users.sort();
+        assert_eq!(users, vec![user, UserId::new(3)]);
+
+        storage.del_warn(user, guild, &first.id).await?;
+        assert_eq!(storage.scan_warns(user, guild).await?, vec![second]);
+
+        storage.del_warns(user, guild).await?;
+        assert!(storage.scan_warns(user, guild).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ban_groups_and_proposals() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (guild, user) = (GuildId::new(1), UserId::new(2));
+
+        let mut group = BanGroup::new("network".to_owned(), BanGroupPolicy::Automatic);
+        group.guilds.extend([guild, GuildId::new(3)]);
+        storage.set_ban_group(&group).await?;
+        storage
+            .set_ban_group(&BanGroup::new("alpha".to_owned(), BanGroupPolicy::Confirm))
+            .await?;
+
+        let groups = storage.scan_ban_groups().await?;
+        assert_eq!(
+            groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(),
+            vec!["alpha", "network"]
+        );
+        assert_eq!(groups[1], group);
+
+        storage.del_ban_group("alpha").await?;
+        assert_eq!(storage.scan_ban_groups().await?, vec![group]);
+
+        let proposal = BanProposal {
+            origin: GuildId::new(3),
+            origin_name: "Origin".to_owned(),
+            reason: None,
+            moderator: Some(UserId::new(4)),
+            timestamp: chrono::Utc::now(),
+        };
+        storage.set_ban_proposal(guild, user, &proposal).await?;
+        assert_eq!(storage.get_ban_proposal(guild, user).await?, Some(proposal));
+
+        storage.del_ban_proposal(guild, user).await?;
+        assert!(storage.get_ban_proposal(guild, user).await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn notes_hash() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (user, guild, author) = (UserId::new(1), GuildId::new(2), UserId::new(3));
+
+        let first = Note::new(author, "first".to_owned());
+        let second = Note {
+            timestamp: first.timestamp + chrono::Duration::seconds(1),
+            ..Note::new(author, "second".to_owned())
+        };
+
+        storage.add_note(user, guild, &second).await?;
+        storage.add_note(user, guild, &first).await?;
+        assert_eq!(
+            storage.scan_notes(user, guild).await?,
+            vec![first.clone(), second.clone()]
+        );
+        assert!(storage.scan_notes(user, GuildId::new(4)).await?.is_empty());
+
+        storage.del_note(user, guild, &first.id).await?;
+        assert_eq!(storage.scan_notes(user, guild).await?, vec![second]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn quarantines_hash() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (guild, user) = (GuildId::new(1), UserId::new(2));
+
+        let quarantine = Quarantine {
+            guild,
+            user,
+            roles: vec![RoleId::new(3), RoleId::new(4)],
+            started_at: chrono::Utc::now(),
+            expires_at: None,
+            case: Some(5),
+        };
+
+        storage.set_quarantine(&quarantine).await?;
+        assert_eq!(
+            storage.get_quarantine(guild, user).await?,
+            Some(quarantine.clone())
+        );
+        assert!(
+            storage
+                .get_quarantine(GuildId::new(6), user)
+                .await?
+                .is_none()
+        );
+        assert_eq!(storage.scan_quarantines().await?, vec![quarantine]);
+
+        storage.del_quarantine(guild, user).await?;
+        assert!(storage.get_quarantine(guild, user).await?.is_none());
+        assert!(storage.scan_quarantines().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn message_logs_are_indexed() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let guild = GuildId::new(1);
+        let now = chrono::Utc::now();
+
+        let id_at = |time: chrono::DateTime<chrono::Utc>| {
+            MessageId::new(
+                (u64::try_from(time.timestamp_millis()).unwrap() - 1_420_070_400_000) << 22,
+            )
+        };
+        let log = |content: &str, author: u64| MessageLog {
+            content: content.to_owned(),
+            author: UserId::new(author),
+            channel: Some(GenericChannelId::new(4)),
+            attachments: Vec::new(),
+            cached_attachments: std::collections::HashMap::new(),
+        };
+        let query = |limit: usize| MessageLogQuery {
+            after: now - chrono::Duration::days(7),
+            before: now,
+            author: None,
+            channel: None,
+            contains: None,
+            limit,
+        };
+
+        let (old, recent, newest) = (
+            id_at(now - chrono::Duration::days(3)),
+            id_at(now - chrono::Duration::minutes(5)),
+            id_at(now - chrono::Duration::minutes(1)),
+        );
+
+        storage
+            .set_message_log(guild, old, &log("old", 2), Duration::from_hours(96))
+            .await?;
+        storage
+            .set_message_log(guild, recent, &log("Recent", 2), Duration::from_hours(24))
+            .await?;
+        storage
+            .set_message_log(guild, newest, &log("newest", 5), Duration::from_hours(24))
+            .await?;
+        storage
+            .set_message_log(
+                GuildId::new(3),
+                id_at(now),
+                &log("other", 2),
+                Duration::from_hours(24),
+            )
+            .await?;
+
+        let contents = |logs: Vec<(MessageId, MessageLog)>| {
+            logs.into_iter()
+                .map(|(id, log)| (id, log.content))
+                .collect::<Vec<_>>()
+        };
+
+        // The shorter retention prunes the older message from the index
+        assert_eq!(
+            contents(storage.scan_message_logs(guild, &query(10)).await?),
+            vec![(newest, "newest".to_owned()), (recent, "Recent".to_owned())]
+        );
+        assert_eq!(
+            contents(storage.scan_message_logs(guild, &query(1)).await?),
+            vec![(newest, "newest".to_owned())]
+        );
+
+        let filtered = [
+            MessageLogQuery {
+                author: Some(UserId::new(2)),
+                ..query(10)
+            },
+            MessageLogQuery {
+                contains: Some("recent".to_owned()),
+                channel: Some(GenericChannelId::new(4)),
+                ..query(10)
+            },
+        ];
+        for query in &filtered {
+            assert_eq!(
+                contents(storage.scan_message_logs(guild, query).await?),
+                vec![(recent, "Recent".to_owned())]
+            );
+        }
+
+        assert!(
+            storage
+                .scan_message_logs(
+                    guild,
+                    &MessageLogQuery {
+                        channel: Some(GenericChannelId::new(5)),
+                        ..query(10)
+                    }
+                )
+                .await?
+                .is_empty()
+        );
+
+        storage.del_message_log(recent).await?;
+        storage.del_message_log(newest).await?;
+        assert!(
+            storage
+                .scan_message_logs(guild, &query(10))
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn legacy_warn_counts_are_migrated() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let (user, guild) = (UserId::new(1), GuildId::new(2));
+
+        storage
+            .set(keys::WARN_COUNT.user(user).guild(guild), &3_u64, None)
+            .await?;
+
+        assert!(
+            crate::storage::migrations::run(&storage, true)
+                .await
+                .is_err()
+        );
+        assert_eq!(storage.get_warn_count(user, guild).await?, Some(3));
+
+        crate::storage::migrations::run(&storage, false).await?;
+        assert_eq!(storage.get_warn_count(user, guild).await?, None);
+        assert_eq!(storage.scan_warns(user, guild).await?.len(), 3);
+        assert_eq!(storage.scan_warned_users(guild).await?, vec![user]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn autoreply_hash() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let guild = GuildId::new(1);
+
+        storage.add_autoreply(guild, "a", "1").await?;
+        storage.add_autoreply(guild, "b", "2").await?;
+        storage.add_autoreply(guild, "a", "3").await?;
+
+        let mut values = storage.scan_autoreply(guild).await?;
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                ("a".to_owned(), "3".to_owned()),
+                ("b".to_owned(), "2".to_owned())
+            ]
+        );
+
+        storage.del_autoreply(guild, "a").await?;
+        assert_eq!(storage.scan_autoreply(guild).await?.len(), 1);
+
+        storage.delall_autoreply(guild).await?;
+        assert!(storage.scan_autoreply(guild).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn automod_rules_hash() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let guild = GuildId::new(1);
+
+        let rule = AutomodRule::new(
+            AutomodPattern::Words(vec!["spam".to_owned()]),
+            AutomodAction::Delete,
+        );
+
+        storage.set_automod_rule(guild, &rule).await?;
+        assert_eq!(storage.scan_automod_rules(guild).await?, vec![rule.clone()]);
+        assert!(
+            storage
+                .scan_automod_rules(GuildId::new(2))
+                .await?
+                .is_empty()
+        );
+
+        storage.del_automod_rule(guild, &rule.id).await?;
+        assert!(storage.scan_automod_rules(guild).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn intelligence_consent_set() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let user = UserId::new(1);
+
+        assert!(!storage.get_intelligence_consent(user).await?);
+        storage.add_intelligence_consent(user).await?;
+        storage.add_intelligence_consent(user).await?;
+        assert!(storage.get_intelligence_consent(user).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reminders_are_cleaned() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+
+        let past = ReminderData {
+            channel: GenericChannelId::new(1),
+            user: UserId::new(1),
+            content: None,
+            timestamp: chrono::Utc::now() - chrono::TimeDelta::hours(1),
+        };
+
+        let future = ReminderData {
+            timestamp: chrono::Utc::now() + chrono::TimeDelta::hours(1),
+            ..past.clone()
+        };
+
+        storage.add_reminders(&future).await?;
+        storage.add_reminders(&past).await?;
+        assert_eq!(storage.scan_reminders().await?.len(), 2);
+
+        storage.clean_reminders().await?;
+        let reminders = storage.scan_reminders().await?;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].timestamp, future.timestamp);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{GuildId, UserId};
+use serde::{Deserialize, Serialize};
+
+use super::StorageBackend;
+
+/// A ban that is lifted automatically once `expires_at` has passed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TempBan {
+    pub guild: GuildId,
+    pub user: UserId,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// The moderation case of the ban.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub case: Option<u64>,
+}
+
+/// Cancel any scheduled unbans of `user` in `guild`.
+async fn cancel(storage: &dyn StorageBackend, guild: GuildId, user: UserId) -> Result<()> {
+    for temp_ban in storage.scan_temp_bans().await? {
+        if temp_ban.guild == guild && temp_ban.user == user {
+            storage.del_temp_ban(&temp_ban).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace any scheduled unbans of `user` in `guild` after they are banned again.
+/// A permanent ban (`expires_at` of `None`) only cancels the pending unbans.
+pub async fn schedule(
+    storage: &dyn StorageBackend,
+    guild: GuildId,
+    user: UserId,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    case: Option<u64>,
+) -> Result<()> {
+    cancel(storage, guild, user).await?;
+
+    if let Some(expires_at) = expires_at {
+        storage
+            .add_temp_ban(&TempBan {
+                guild,
+                user,
+                expires_at,
+                case,
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Scheduled unbans that are due.
+pub async fn expired(storage: &dyn StorageBackend) -> Result<Vec<TempBan>> {
+    let now = chrono::Utc::now();
+
+    Ok(storage
+        .scan_temp_bans()
+        .await?
+        .into_iter()
+        .take_while(|temp_ban| temp_ban.expires_at <= now)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SqliteBackend;
+
+    #[tokio::test]
+    async fn expired_and_cancelled() -> Result<()> {
+        let storage = SqliteBackend::memory()?;
+        let guild = GuildId::new(1);
+        let now = chrono::Utc::now();
+
+        let past = TempBan {
+            guild,
+            user: UserId::new(2),
+            expires_at: now - chrono::Duration::minutes(1),
+            case: Some(1),
+        };
+        let future = TempBan {
+            guild,
+            user: UserId::new(3),
+            expires_at: now + chrono::Duration::hours(1),
+            case: None,
+        };
+
+        storage.add_temp_ban(&future).await?;
+        storage.add_temp_ban(&past).await?;
+
+        assert_eq!(expired(&storage).await?, vec![past.clone()]);
+
+        storage.del_temp_ban(&past).await?;
+        assert!(expired(&storage).await?.is_empty());
+
+        // A permanent re-ban cancels the pending unban
+        schedule(&storage, guild, future.user, None, None).await?;
+        assert!(storage.scan_temp_bans().await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+use eyre::Result;
+use poise::serenity_prelude::{GuildId, UserId};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::StorageBackend;
+use crate::utils;
+
+/// A single warn issued to a user in a guild.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Warn {
+    pub id: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// The moderation case of the warn, absent for warns migrated from plain counters.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub case: Option<u64>,
+}
+
+impl Warn {
+    pub fn new(case: Option<u64>) -> Self {
+        Self {
+            id: utils::nanoid(16),
+            timestamp: chrono::Utc::now(),
+            case,
+        }
+    }
+
+    /// Whether the warn still counts towards escalations, given the guild's warn expiry.
+    pub fn is_active(&self, expiry: Option<Duration>) -> bool {
+        expiry.is_none_or(|expiry| {
+            chrono::Duration::from_std(expiry)
+                .is_ok_and(|expiry| chrono::Utc::now() < self.timestamp + expiry)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WarnCounts {
+    pub active: u64,
+    pub total: u64,
+}
+
+impl WarnCounts {
+    pub fn new(warns: &[Warn], expiry: Option<Duration>) -> Self {
+        Self {
+            active: warns.iter().filter(|warn| warn.is_active(expiry)).count() as u64,
+            total: warns.len() as u64,
+        }
+    }
+
+    /// Count the warns of `user` in `guild`, using the guild's configured warn expiry.
+    pub async fn fetch(storage: &dyn StorageBackend, user: UserId, guild: GuildId) -> Result<Self> {
+        let expiry = storage.get_config(guild).await?.warn_expiry;
+        let warns = storage.scan_warns(user, guild).await?;
+
+        Ok(Self::new(&warns, expiry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_warns_are_inactive() {
+        let old = Warn {
+            timestamp: chrono::Utc::now() - chrono::Duration::days(100),
+            ..Warn::new(None)
+        };
+        let recent = Warn::new(Some(1));
+
+        let expiry = Some(Duration::from_hours(24 * 90));
+        assert!(!old.is_active(expiry));
+        assert!(recent.is_active(expiry));
+        assert!(old.is_active(None));
+
+        assert_eq!(
+            WarnCounts::new(&[old, recent], expiry),
+            WarnCounts {
+                active: 1,
+                total: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: 2026 Ryan Cao <hello@ryanccn.dev>
+//
+// SPDX-License-Identifier: AGPL-3.0-only
+
+/// The largest number of token pairs that are compared; larger changes are shown as a whole.
+const MAX_COMPARISONS: usize = 1_000_000;
+
+/// The number of unchanged characters kept around each change when rendering.
+const CONTEXT: usize = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Equal(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Split `s` into alternating runs of whitespace and non-whitespace.
+fn tokenize(s: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut prev = None;
+
+    for (idx, c) in s.char_indices() {
+        let whitespace = c.is_whitespace();
+        if prev.is_some_and(|prev| prev != whitespace) {
+            tokens.push(&s[start..idx]);
+            start = idx;
+        }
+        prev = Some(whitespace);
+    }
+
+    if start < s.len() {
+        tokens.push(&s[start..]);
+    }
+
+    tokens
+}
+
+fn push(changes: &mut Vec<Change>, change: Change) {
+    match (changes.last_mut(), change) {
+        (Some(Change::Equal(last)), Change::Equal(s))
+        | (Some(Change::Removed(last)), Change::Removed(s))
+        | (Some(Change::Added(last)), Change::Added(s)) => last.push_str(&s),
+        (_, change) => changes.push(change),
+    }
+}
+
+/// Compute the word-level changes that turn `old` into `new`.
+pub fn diff(old: &str, new: &str) -> Vec<Change> {
+    let (old, new) = (tokenize(old), tokenize(new));
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut changes = Vec::new();
+    push(&mut changes, Change::Equal(old[..prefix].concat()));
+
+    if a.len().saturating_mul(b.len()) > MAX_COMPARISONS {
+        push(&mut changes, Change::Removed(a.concat()));
+        push(&mut changes, Change::Added(b.concat()));
+    } else {
+        // Lengths of the longest common subsequences of every pair of suffixes
+        let width = b.len() + 1;
+        let mut lcs = vec![0_u32; (a.len() + 1) * width];
+
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * width + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                push(&mut changes, Change::Equal(a[i].to_owned()));
+                i += 1;
+                j += 1;
+            } else if j == b.len()
+                || (i < a.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+            {
+                push(&mut changes, Change::Removed(a[i].to_owned()));
+                i += 1;
+            } else {
+                push(&mut changes, Change::Added(b[j].to_owned()));
+                j += 1;
+            }
+        }
+    }
+
+    push(
+        &mut changes,
+        Change::Equal(old[old.len() - suffix..].concat()),
+    );
+
+    changes.retain(|change| match change {
+        Change::Equal(s) | Change::Removed(s) | Change::Added(s) => !s.is_empty(),
+    });
+
+    changes
+}
+
+fn escape_markdown(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Surround the non-whitespace parts of each line of `s` with `marker`.
+fn wrap(s: &str, marker: &str) -> String {
+    s.split('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                return line.to_owned();
+            }
+
+            let start = line.len() - line.trim_start().len();
+            let end = start + trimmed.len();
+            format!(
+                "{}{marker}{}{marker}{}",
+                &line[..start],
+                escape_markdown(trimmed),
+                &line[end..]
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn head(s: &str, len: usize) -> &str {
+    s.char_indices().nth(len).map_or(s, |(idx, _)| &s[..idx])
+}
+
+fn tail(s: &str, len: usize) -> &str {
+    let count = s.chars().count();
+    s.char_indices()
+        .nth(count.saturating_sub(len))
+        .map_or("", |(idx, _)| &s[idx..])
+}
+
+/// Render `changes` as Markdown, with removed text struck through and added text in bold.
+/// Unchanged text far from any change is elided, and the result is cut to `max_len` characters.
+/// Returns the rendered text and whether any part of the text was left out.
+pub fn render(changes: &[Change], max_len: usize) -> (String, bool) {
+    let mut rendered = String::new();
+    let mut omitted = false;
+
+    for (idx, change) in changes.iter().enumerate() {
+        match change {
+            Change::Equal(s) => {
+                let (first, last) = (idx == 0, idx + 1 == changes.len());
+                let len = s.chars().count();
+
+                if first && len > CONTEXT {
+                    rendered.push('…');
+                    rendered.push_str(&escape_markdown(tail(s, CONTEXT)));
+                    omitted = true;
+                } else if last && len > CONTEXT {
+                    rendered.push_str(&escape_markdown(head(s, CONTEXT)));
+                    rendered.push('…');
+                    omitted = true;
+                } else if !first && !last && len > CONTEXT * 2 {
+                    rendered.push_str(&escape_markdown(head(s, CONTEXT)));
+                    rendered.push_str(" … ");
+                    rendered.push_str(&escape_markdown(tail(s, CONTEXT)));
+                    omitted = true;
+                } else {
+                    rendered.push_str(&escape_markdown(s));
+                }
+            }
+            Change::Removed(s) => {
+                if !s.trim().is_empty() {
+                    rendered.push_str(&wrap(s, "~~"));
+                }
+            }
+            Change::Added(s) => rendered.push_str(&wrap(s, "**")),
+        }
+    }
+
+    if rendered.chars().count() > max_len {
+        rendered = head(&rendered, max_len.saturating_sub(1)).to_owned();
+        rendered.push('…');
+        omitted = true;
+    }
+
+    (rendered, omitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_works() {
+        assert_eq!(
+            diff("hello big world", "hello small world!"),
+            vec![
+                Change::Equal("hello ".to_owned()),
+                Change::Removed("big".to_owned()),
+                Change::Added("small".to_owned()),
+                Change::Equal(" ".to_owned()),
+                Change::Removed("world".to_owned()),
+                Change::Added("world!".to_owned()),
+            ]
+        );
+
+        assert_eq!(
+            diff("a b c d", "a c d e"),
+            vec![
+                Change::Equal("a ".to_owned()),
+                Change::Removed("b ".to_owned()),
+                Change::Equal("c d".to_owned()),
+                Change::Added(" e".to_owned()),
+            ]
+        );
+
+        assert_eq!(diff("same", "same"), vec![Change::Equal("same".to_owned())]);
+    }
+
+    #[test]
+    fn render_works() {
+        assert_eq!(
+            render(&diff("it is *fine*", "it is fine\nnow"), 100),
+            ("it is ~~\\*fine\\*~~**fine**\n**now**".to_owned(), false)
+        );
+
+        let long = "word ".repeat(100);
+        let (rendered, omitted) = render(&diff(&format!("{long}old"), &format!("{long}new")), 100);
+        assert!(omitted);
+        assert!(rendered.starts_with('…'));
+        assert!(rendered.ends_with("~~old~~**new**"));
+
+        let (rendered, omitted) = render(&diff("a", &"b".repeat(200)), 50);
+        assert!(omitted);
+        assert_eq!(rendered.chars().count(), 50);
+    }
+}
@@ -110,22 +110,10 @@ You can contact the owner of this app with the error ID `{}` if you require supp
     /// Report the error to a channel defined through the environment variable `ERROR_LOGS_CHANNEL`.
     #[tracing::instrument(skip(self))]
     pub async fn handle_report(&self) {
-        if let Some(channel) = CONFIG.error_logs_channel {
-            let mut error_string = format!("{:#?}", self.report_or_panic);
-            error_string = error_string.replace(&CONFIG.discord_token, "<redacted>");
+        let config = CONFIG.get();
 
-            if let Some(data) = &CONFIG.redis_url {
-                error_string = error_string.replace(data, "<redacted>");
-            }
-            if let Some(data) = &CONFIG.pagespeed_api_key {
-                error_string = error_string.replace(data, "<redacted>");
-            }
-            if let Some(data) = &CONFIG.safe_browsing_api_key {
-                error_string = error_string.replace(data, "<redacted>");
-            }
-            if let Some(data) = &CONFIG.anthropic_api_key {
-                error_string = error_string.replace(data, "<redacted>");
-            }
+        if let Some(channel) = config.error_logs_channel {
+            let error_string = config.redact(&format!("{:#?}", self.report_or_panic));
 
             let mut container = CreateContainer::new(vec![
                 CreateContainerComponent::TextDisplay(CreateTextDisplay::new(format!(
@@ -6,6 +6,7 @@ use std::borrow::Cow;
 
 use bytesize::ByteSize;
 
+pub mod diff;
 mod error_handling;
 pub use error_handling::ValfiskError;
 mod nanoid;
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: AGPL-3.0-only
 
-use eyre::Result;
+use eyre::{Result, eyre};
 use std::{fmt::Display, sync::Arc, time::Duration};
 use tokio::time::timeout;
 
@@ -84,6 +84,21 @@ pub fn format_attachments(attachments: &[serenity::Attachment]) -> String {
         .join("\n")
 }
 
+/// Parse a list of IDs or mentions separated by spaces or commas.
+pub fn parse_ids(s: &str) -> Result<Vec<u64>> {
+    s.split([' ', ','])
+        .map(|id| {
+            id.trim_start_matches(['<', '#', '@', '&', '!'])
+                .trim_end_matches('>')
+        })
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse()
+                .map_err(|_| eyre!("invalid ID or mention `{id}`"))
+        })
+        .collect()
+}
+
 pub async fn interaction_confirm<'a>(
     ctx: &crate::Context<'a>,
     container: serenity::CreateContainer<'_>,
@@ -148,6 +163,109 @@ pub async fn interaction_confirm<'a>(
     Ok((false, handle))
 }
 
+/// Send `pages` as a reply that can be flipped through with buttons. The buttons are removed
+/// after ten minutes without interaction.
+pub async fn interaction_paginate(
+    ctx: &crate::Context<'_>,
+    pages: Vec<serenity::CreateContainer<'static>>,
+) -> Result<()> {
+    use futures_util::StreamExt as _;
+
+    let prev_button_id = utils::nanoid(16);
+    let next_button_id = utils::nanoid(16);
+
+    let render = |page: usize, buttons: bool| {
+        let mut container = pages[page].clone();
+
+        if pages.len() > 1 {
+            container = container.add_component(serenity::CreateContainerComponent::TextDisplay(
+                serenity::CreateTextDisplay::new(format!(
+                    "-# Page {} of {}",
+                    page + 1,
+                    pages.len()
+                )),
+            ));
+        }
+
+        if buttons && pages.len() > 1 {
+            container = container.add_component(serenity::CreateContainerComponent::ActionRow(
+                serenity::CreateActionRow::Buttons(
+                    vec![
+                        serenity::CreateButton::new(prev_button_id.clone())
+                            .label("Previous")
+                            .style(serenity::ButtonStyle::Secondary)
+                            .disabled(page == 0),
+                        serenity::CreateButton::new(next_button_id.clone())
+                            .label("Next")
+                            .style(serenity::ButtonStyle::Secondary)
+                            .disabled(page + 1 == pages.len()),
+                    ]
+                    .into(),
+                ),
+            ));
+        }
+
+        vec![serenity::CreateComponent::Container(container)]
+    };
+
+    let mut page = 0;
+
+    let handle = ctx
+        .send(
+            poise::CreateReply::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .allowed_mentions(serenity::CreateAllowedMentions::new())
+                .components(render(page, true)),
+        )
+        .await?;
+
+    if pages.len() <= 1 {
+        return Ok(());
+    }
+
+    let mut interactions = std::pin::pin!(serenity::collect(ctx.serenity_context(), {
+        let message_id = handle.message().await?.id;
+
+        move |event| match event {
+            serenity::Event::InteractionCreate(event) => event
+                .interaction
+                .as_message_component()
+                .take_if(|i| i.message.id == message_id)
+                .cloned(),
+            _ => None,
+        }
+    }));
+
+    while let Ok(Some(interaction)) = timeout(Duration::from_mins(10), interactions.next()).await {
+        if interaction.data.custom_id == prev_button_id {
+            page = page.saturating_sub(1);
+        } else if interaction.data.custom_id == next_button_id {
+            page = (page + 1).min(pages.len() - 1);
+        }
+
+        interaction
+            .create_response(
+                ctx.http(),
+                serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .components(render(page, true)),
+                ),
+            )
+            .await?;
+    }
+
+    handle
+        .edit(
+            *ctx,
+            poise::CreateReply::default()
+                .flags(serenity::MessageFlags::IS_COMPONENTS_V2)
+                .components(render(page, false)),
+        )
+        .await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,4 +287,14 @@ mod tests {
             "<@&1234> `1234`"
         );
     }
+
+    #[test]
+    fn parse_ids_works() {
+        assert_eq!(
+            parse_ids("<@1234>, <@!5678> <#90> <@&12>  34").unwrap(),
+            vec![1234, 5678, 90, 12, 34]
+        );
+        assert!(parse_ids("").unwrap().is_empty());
+        assert!(parse_ids("@everyone").is_err());
+    }
 }